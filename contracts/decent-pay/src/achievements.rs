@@ -0,0 +1,91 @@
+use crate::credentials;
+use crate::storage_types::{
+    Achievement, AchievementRecord, DataKey, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Vec};
+
+const FIVE_STAR_REVIEWS_REQUIRED: u32 = 10;
+const DISPUTE_FREE_STREAK_REQUIRED: u32 = 10;
+const CATEGORY_SPECIALIST_REPUTATION: u32 = 500;
+
+/// Get the achievements earned by a user
+pub fn get_achievements(env: &Env, user: Address) -> Vec<AchievementRecord> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&DataKey::Achievements(user))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Check if a user has earned an achievement
+pub fn has_achievement(env: &Env, user: Address, achievement: Achievement) -> bool {
+    get_achievements(env, user)
+        .iter()
+        .any(|record| record.achievement == achievement)
+}
+
+/// Award an achievement once, issuing the matching credential
+pub fn award(env: &Env, user: &Address, achievement: Achievement, escrow_id: u32) {
+    credentials::issue(env, user, achievement, escrow_id);
+    let mut achievements = get_achievements(env, user.clone());
+    if achievements.iter().any(|record| record.achievement == achievement) {
+        return;
+    }
+    achievements.push_back(AchievementRecord {
+        achievement,
+        escrow_id,
+        earned_at: env.ledger().timestamp(),
+    });
+    env.storage()
+        .instance()
+        .set(&DataKey::Achievements(user.clone()), &achievements);
+}
+
+fn increment(env: &Env, key: &DataKey) -> u32 {
+    let count = env.storage().instance().get::<DataKey, u32>(key).unwrap_or(0) + 1;
+    env.storage().instance().set(key, &count);
+    count
+}
+
+/// Record a freelancer completing an escrow
+pub fn on_escrow_completed(env: &Env, freelancer: &Address, escrow_id: u32) {
+    award(env, freelancer, Achievement::FirstJob, escrow_id);
+
+    if increment(env, &DataKey::DisputeFreeStreak(freelancer.clone())) >= DISPUTE_FREE_STREAK_REQUIRED {
+        award(env, freelancer, Achievement::ZeroDisputeStreak, escrow_id);
+    }
+}
+
+/// Record a dispute on one of a freelancer's escrows, breaking the dispute-free streak
+pub fn on_dispute_opened(env: &Env, freelancer: &Address) {
+    env.storage()
+        .instance()
+        .set(&DataKey::DisputeFreeStreak(freelancer.clone()), &0u32);
+}
+
+/// Record a rating received by a freelancer
+pub fn on_rating_received(env: &Env, freelancer: &Address, rating: u32, escrow_id: u32) {
+    if rating == 5 && increment(env, &DataKey::FiveStarCount(freelancer.clone())) >= FIVE_STAR_REVIEWS_REQUIRED {
+        award(env, freelancer, Achievement::TenFiveStarReviews, escrow_id);
+    }
+}
+
+/// Record a rating being edited from `old_rating` to `new_rating` stars, so re-editing a
+/// five-star rating cannot count towards five-star reviews more than once
+pub fn on_rating_edited(env: &Env, freelancer: &Address, old_rating: u32, new_rating: u32, escrow_id: u32) {
+    if old_rating == 5 {
+        let key = DataKey::FiveStarCount(freelancer.clone());
+        let count = env.storage().instance().get::<DataKey, u32>(&key).unwrap_or(0);
+        env.storage().instance().set(&key, &count.saturating_sub(1));
+    }
+    on_rating_received(env, freelancer, new_rating, escrow_id);
+}
+
+/// Record a freelancer's updated reputation within a category
+pub fn on_category_reputation(env: &Env, freelancer: &Address, category_reputation: u32, escrow_id: u32) {
+    if category_reputation >= CATEGORY_SPECIALIST_REPUTATION {
+        award(env, freelancer, Achievement::CategorySpecialist, escrow_id);
+    }
+}
@@ -1,5 +1,14 @@
-use crate::storage_types::{DataKey, DeCentPayError, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
-use soroban_sdk::{Address, Env, Error};
+use crate::escrow_core;
+use crate::finality;
+use crate::milestone_log;
+use crate::storage_types::{
+    AccountKey, ActivityAction, Config, ConfigKey, DataKey, DeCentPayError, EscrowKey, CONTRACT_VERSION, DurationBounds, FeeTier, Role, PostingGate, ValueThresholds, DEFAULT_MAX_DURATION, DEFAULT_MAX_EXTENSION,
+    DEFAULT_MIN_DURATION, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{token, Address, BytesN, Env, Error, Vec};
+
+const MAX_FEE_TIERS: u32 = 10;
+const MAX_PAGE_SIZE: u32 = 50;
 
 pub fn initialize(env: &Env, owner: Address, fee_collector: Address, platform_fee_bp: u32) -> Result<(), Error> {
     // Check if already initialized
@@ -30,6 +39,7 @@ pub fn initialize(env: &Env, owner: Address, fee_collector: Address, platform_fe
     env.storage()
         .instance()
         .set(&DataKey::JobCreationPaused, &false);
+    env.storage().instance().set(&ConfigKey::Version, &CONTRACT_VERSION);
     
     Ok(())
 }
@@ -47,7 +57,49 @@ pub fn require_owner(env: &Env) -> Result<(), Error> {
     Ok(())
 }
 
-#[allow(dead_code)]
+/// Require `caller` to be the owner or to hold `role` (admins hold every role)
+pub fn require_role(env: &Env, caller: &Address, role: Role) -> Result<(), Error> {
+    caller.require_auth();
+    if *caller == get_owner(env)? || has_role(env, caller, Role::Admin) || has_role(env, caller, role) {
+        return Ok(());
+    }
+    Err(Error::from_contract_error(DeCentPayError::MissingRole as u32))
+}
+
+pub fn has_role(env: &Env, address: &Address, role: Role) -> bool {
+    env.storage()
+        .instance()
+        .get(&AccountKey::Role(address.clone(), role))
+        .unwrap_or(false)
+}
+
+/// Grant `role` to an address. Admins delegate the operational roles; only the owner appoints admins.
+pub fn grant_role(env: &Env, address: Address, role: Role, caller: Address) -> Result<(), Error> {
+    require_role_manager(env, &caller, role)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(&AccountKey::Role(address, role), &true);
+    Ok(())
+}
+
+pub fn revoke_role(env: &Env, address: Address, role: Role, caller: Address) -> Result<(), Error> {
+    require_role_manager(env, &caller, role)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().remove(&AccountKey::Role(address, role));
+    Ok(())
+}
+
+fn require_role_manager(env: &Env, caller: &Address, role: Role) -> Result<(), Error> {
+    require_role(env, caller, Role::Admin)?;
+    if role == Role::Admin && *caller != get_owner(env)? {
+        return Err(Error::from_contract_error(DeCentPayError::MissingRole as u32));
+    }
+    Ok(())
+}
+
 pub fn get_fee_collector(env: &Env) -> Result<Address, Error> {
     env.storage()
         .instance()
@@ -62,8 +114,8 @@ pub fn get_platform_fee_bp(env: &Env) -> u32 {
         .unwrap_or(0)
 }
 
-pub fn set_platform_fee_bp(env: &Env, fee_bp: u32) -> Result<(), Error> {
-    require_owner(env)?;
+pub fn set_platform_fee_bp(env: &Env, fee_bp: u32, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Treasurer)?;
     if fee_bp > 1000 {
         return Err(Error::from_contract_error(DeCentPayError::FeeTooHigh as u32));
     }
@@ -74,8 +126,122 @@ pub fn set_platform_fee_bp(env: &Env, fee_bp: u32) -> Result<(), Error> {
     Ok(())
 }
 
-pub fn set_fee_collector(env: &Env, fee_collector: Address) -> Result<(), Error> {
-    require_owner(env)?;
+/// Override the platform fee for escrows in a token (None for native XLM)
+pub fn set_token_fee_bp(env: &Env, token: Option<Address>, fee_bp: u32, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Treasurer)?;
+    if fee_bp > 1000 {
+        return Err(Error::from_contract_error(DeCentPayError::FeeTooHigh as u32));
+    }
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&ConfigKey::TokenFeeBP(token_key), &fee_bp);
+    Ok(())
+}
+
+/// Get a token's platform fee override, if any
+pub fn get_token_fee_bp(env: &Env, token: Option<Address>) -> Option<u32> {
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage().instance().get(&ConfigKey::TokenFeeBP(token_key))
+}
+
+/// Set the volume tiers of a token's platform fee, ascending by `min_amount`; an empty list removes them
+pub fn set_fee_tiers(env: &Env, token: Option<Address>, tiers: Vec<FeeTier>, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Treasurer)?;
+    if tiers.len() > MAX_FEE_TIERS {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+    }
+    let mut previous_min = 0;
+    for tier in tiers.iter() {
+        if tier.fee_bp > 1000 {
+            return Err(Error::from_contract_error(DeCentPayError::FeeTooHigh as u32));
+        }
+        if tier.min_amount <= previous_min {
+            return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+        }
+        previous_min = tier.min_amount;
+    }
+
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    if tiers.is_empty() {
+        env.storage().instance().remove(&ConfigKey::FeeTiers(token_key));
+    } else {
+        env.storage().instance().set(&ConfigKey::FeeTiers(token_key), &tiers);
+    }
+    Ok(())
+}
+
+/// Get the volume tiers of a token's platform fee
+pub fn get_fee_tiers(env: &Env, token: Option<Address>) -> Vec<FeeTier> {
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .get(&ConfigKey::FeeTiers(token_key))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Platform fee for an escrow of `amount`: the highest volume tier it reaches,
+/// else the token's override, else the global fee
+pub fn get_fee_bp(env: &Env, token: Option<&Address>, amount: i128) -> u32 {
+    let mut fee_bp = get_token_fee_bp(env, token.cloned()).unwrap_or_else(|| get_platform_fee_bp(env));
+    for tier in get_fee_tiers(env, token.cloned()).iter() {
+        if amount < tier.min_amount {
+            break;
+        }
+        fee_bp = tier.fee_bp;
+    }
+    fee_bp
+}
+
+/// Exempt a depositor from the platform fee, e.g. for partner integrations or promotions
+pub fn set_fee_exempt(env: &Env, address: Address, exempt: bool, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Treasurer)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    if exempt {
+        env.storage().instance().set(&AccountKey::FeeExempt(address), &true);
+    } else {
+        env.storage().instance().remove(&AccountKey::FeeExempt(address));
+    }
+    Ok(())
+}
+
+pub fn is_fee_exempt(env: &Env, address: &Address) -> bool {
+    env.storage()
+        .instance()
+        .get(&AccountKey::FeeExempt(address.clone()))
+        .unwrap_or(false)
+}
+
+pub fn get_referral_share_bp(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&ConfigKey::ReferralShareBP)
+        .unwrap_or(0)
+}
+
+/// Set the share of each platform fee paid to the escrow's referrer
+pub fn set_referral_share_bp(env: &Env, share_bp: u32, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Treasurer)?;
+    if share_bp > 5000 {
+        return Err(Error::from_contract_error(DeCentPayError::FeeTooHigh as u32));
+    }
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(&ConfigKey::ReferralShareBP, &share_bp);
+    Ok(())
+}
+
+pub fn set_fee_collector(env: &Env, fee_collector: Address, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Treasurer)?;
     env.storage()
         .instance()
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
@@ -103,9 +269,62 @@ pub fn is_job_creation_paused(env: &Env) -> bool {
         .unwrap_or(false)
 }
 
+pub fn is_contract_paused(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&ConfigKey::ContractPaused)
+        .unwrap_or(false)
+}
+
+/// Emergency brake: block every state change except refunds
+pub fn set_contract_paused(env: &Env, paused: bool, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Moderator)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(&ConfigKey::ContractPaused, &paused);
+    Ok(())
+}
+
+pub fn require_not_paused(env: &Env) -> Result<(), Error> {
+    require_migrated(env)?;
+    if is_contract_paused(env) {
+        return Err(Error::from_contract_error(DeCentPayError::ContractPaused as u32));
+    }
+    Ok(())
+}
+
+/// Hold a suspicious escrow: nothing, refunds included, can touch it until it is unfrozen.
+/// A final escrow cannot be frozen.
+pub fn set_escrow_frozen(env: &Env, escrow_id: u32, frozen: bool, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Moderator)?;
+    escrow_core::require_valid_escrow(env, escrow_id)?;
+    if frozen {
+        finality::require_not_final(env, escrow_id)?;
+    }
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    if frozen {
+        env.storage().instance().set(&EscrowKey::Frozen(escrow_id), &true);
+    } else {
+        env.storage().instance().remove(&EscrowKey::Frozen(escrow_id));
+    }
+    let action = if frozen { ActivityAction::Frozen } else { ActivityAction::Unfrozen };
+    milestone_log::record_activity(env, escrow_id, &caller, action, None);
+    Ok(())
+}
+
+pub fn is_escrow_frozen(env: &Env, escrow_id: u32) -> bool {
+    env.storage()
+        .instance()
+        .get(&EscrowKey::Frozen(escrow_id))
+        .unwrap_or(false)
+}
+
 #[allow(dead_code)]
-pub fn set_job_creation_paused(env: &Env, paused: bool) -> Result<(), Error> {
-    require_owner(env)?;
+pub fn set_job_creation_paused(env: &Env, paused: bool, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Moderator)?;
     env.storage()
         .instance()
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
@@ -115,3 +334,390 @@ pub fn set_job_creation_paused(env: &Env, paused: bool) -> Result<(), Error> {
     Ok(())
 }
 
+
+pub fn set_posting_fee(env: &Env, token: Option<Address>, amount: i128, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Treasurer)?;
+    if amount < 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::PostingFee(token_key), &amount);
+    Ok(())
+}
+
+pub fn get_posting_fee(env: &Env, token: Option<Address>) -> i128 {
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .get(&DataKey::PostingFee(token_key))
+        .unwrap_or(0)
+}
+
+pub fn set_posting_fee_refundable(env: &Env, refundable: bool, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Treasurer)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::PostingFeeRefundable, &refundable);
+    Ok(())
+}
+
+pub fn is_posting_fee_refundable(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::PostingFeeRefundable)
+        .unwrap_or(false)
+}
+
+/// Set or clear (with `None`) the reputation gate for high-value postings
+pub fn set_posting_gate(env: &Env, gate: Option<PostingGate>, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Admin)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    match gate {
+        Some(gate) => {
+            if gate.value_threshold <= 0 {
+                return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+            }
+            env.storage().instance().set(&DataKey::PostingGate, &gate);
+        }
+        None => env.storage().instance().remove(&DataKey::PostingGate),
+    }
+    Ok(())
+}
+
+pub fn get_posting_gate(env: &Env) -> Option<PostingGate> {
+    env.storage().instance().get(&DataKey::PostingGate)
+}
+
+pub fn set_duration_bounds(env: &Env, min_duration: u32, max_duration: u32, max_extension: u32, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Admin)?;
+    if min_duration == 0 || min_duration > max_duration || max_extension == 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+    }
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(
+        &DataKey::DurationBounds,
+        &DurationBounds {
+            min_duration,
+            max_duration,
+            max_extension,
+        },
+    );
+    Ok(())
+}
+
+pub fn get_duration_bounds(env: &Env) -> DurationBounds {
+    env.storage()
+        .instance()
+        .get(&DataKey::DurationBounds)
+        .unwrap_or(DurationBounds {
+            min_duration: DEFAULT_MIN_DURATION,
+            max_duration: DEFAULT_MAX_DURATION,
+            max_extension: DEFAULT_MAX_EXTENSION,
+        })
+}
+
+/// Get the platform fees accrued in a token (None for native XLM) and not yet withdrawn
+pub fn get_accrued_fees(env: &Env, token: Option<Address>) -> i128 {
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .get(&DataKey::TotalFeesByToken(token_key))
+        .unwrap_or(0)
+}
+
+/// Get the platform fees a token (None for native XLM) has collected in total, withdrawn or not
+pub fn get_collected_fees(env: &Env, token: Option<Address>) -> i128 {
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .get(&ConfigKey::CollectedFees(token_key))
+        .unwrap_or(0)
+}
+
+/// Book platform fees as both withdrawable and collected
+pub fn accrue_fees(env: &Env, token_key: Address, amount: i128) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    let accrued: i128 = env
+        .storage()
+        .instance()
+        .get(&DataKey::TotalFeesByToken(token_key.clone()))
+        .unwrap_or(0);
+    env.storage()
+        .instance()
+        .set(&DataKey::TotalFeesByToken(token_key.clone()), &(accrued + amount));
+    let collected: i128 = env
+        .storage()
+        .instance()
+        .get(&ConfigKey::CollectedFees(token_key.clone()))
+        .unwrap_or(0);
+    env.storage()
+        .instance()
+        .set(&ConfigKey::CollectedFees(token_key), &(collected + amount));
+}
+
+/// Withdraw accrued platform fees to the fee collector. Only the contract's balance above
+/// what it holds in escrow can be withdrawn, so principal is never drained.
+pub fn withdraw_fees(env: &Env, token: Option<Address>, amount: i128) -> Result<(), Error> {
+    let fee_collector = get_fee_collector(env)?;
+    fee_collector.require_auth();
+
+    let accrued = get_accrued_fees(env, token.clone());
+    if amount <= 0 || amount > accrued {
+        return Err(Error::from_contract_error(DeCentPayError::InsufficientFees as u32));
+    }
+
+    let token_key = token.clone().unwrap_or_else(|| env.current_contract_address());
+    let escrowed: i128 = env
+        .storage()
+        .instance()
+        .get(&DataKey::EscrowedAmount(token_key.clone()))
+        .unwrap_or(0);
+    let token_client = token::Client::new(env, &escrow_core::token_address(env, token.as_ref()));
+    if token_client.balance(&env.current_contract_address()) - amount < escrowed {
+        return Err(Error::from_contract_error(DeCentPayError::InsufficientFees as u32));
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::TotalFeesByToken(token_key), &(accrued - amount));
+    escrow_core::transfer(env, token.as_ref(), &env.current_contract_address(), &fee_collector, amount);
+    Ok(())
+}
+
+/// Point native XLM escrows at the network's Stellar Asset Contract
+pub fn set_native_token(env: &Env, native_token: Address) -> Result<(), Error> {
+    require_owner(env)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(&ConfigKey::NativeToken, &native_token);
+    Ok(())
+}
+
+/// Set the minimum escrow and reputation-eligible values for a token (None for native XLM)
+pub fn set_value_thresholds(env: &Env, token: Option<Address>, thresholds: ValueThresholds, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Admin)?;
+    if thresholds.min_escrow_value < 0 || thresholds.reputation_eligible_value < 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&ConfigKey::ValueThresholds(token_key), &thresholds);
+    Ok(())
+}
+
+/// Get a token's value thresholds; unconfigured tokens have no minimum and earn reputation from 0.01 of a whole token
+pub fn get_value_thresholds(env: &Env, token: Option<Address>) -> ValueThresholds {
+    let decimals = escrow_core::token_decimals(env, token.as_ref());
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .get(&ConfigKey::ValueThresholds(token_key))
+        .unwrap_or(ValueThresholds {
+            min_escrow_value: 0,
+            reputation_eligible_value: 10_i128.pow(decimals.saturating_sub(2)),
+        })
+}
+
+pub fn get_tip_fee_bp(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&ConfigKey::TipFeeBP)
+        .unwrap_or(0)
+}
+
+/// Set the fee taken from bonuses, separately from the platform fee
+pub fn set_tip_fee_bp(env: &Env, fee_bp: u32, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Treasurer)?;
+    if fee_bp > 1000 {
+        return Err(Error::from_contract_error(DeCentPayError::FeeTooHigh as u32));
+    }
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(&ConfigKey::TipFeeBP, &fee_bp);
+    Ok(())
+}
+
+/// Add an arbiter to the platform pool
+pub fn authorize_arbiter(env: &Env, arbiter: Address, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Moderator)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::AuthorizedArbiter(arbiter.clone()), &true);
+    let mut pool = get_arbiter_pool(env);
+    if !pool.contains(&arbiter) {
+        pool.push_back(arbiter);
+        env.storage().instance().set(&ConfigKey::ArbiterPool, &pool);
+    }
+    Ok(())
+}
+
+/// Remove an arbiter from the platform pool
+pub fn revoke_arbiter(env: &Env, arbiter: Address, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Moderator)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .remove(&DataKey::AuthorizedArbiter(arbiter.clone()));
+    let mut pool = get_arbiter_pool(env);
+    if let Some(position) = pool.first_index_of(&arbiter) {
+        pool.remove(position);
+        env.storage().instance().set(&ConfigKey::ArbiterPool, &pool);
+    }
+    Ok(())
+}
+
+/// Get the platform arbiters disputes without a panel are appointed from
+pub fn get_arbiter_pool(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&ConfigKey::ArbiterPool)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Allow escrows in a token, lifting any blacklisting
+pub fn whitelist_token(env: &Env, token: Address, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Moderator)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::WhitelistedToken(token.clone()), &true);
+    env.storage()
+        .instance()
+        .remove(&ConfigKey::BlacklistedToken(token.clone()));
+    escrow_core::snapshot_token_info(env, &token);
+    let mut tokens = get_whitelisted_token_list(env);
+    if !tokens.contains(&token) {
+        tokens.push_back(token);
+        env.storage().instance().set(&ConfigKey::WhitelistedTokens, &tokens);
+    }
+    Ok(())
+}
+
+/// Stop new escrows in a token; existing escrows are unaffected
+pub fn remove_whitelisted_token(env: &Env, token: Address, caller: Address) -> Result<(), Error> {
+    require_role(env, &caller, Role::Moderator)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .remove(&DataKey::WhitelistedToken(token.clone()));
+    let mut tokens = get_whitelisted_token_list(env);
+    if let Some(index) = tokens.first_index_of(&token) {
+        tokens.remove(index);
+        env.storage().instance().set(&ConfigKey::WhitelistedTokens, &tokens);
+    }
+    Ok(())
+}
+
+/// Remove a token from the whitelist and refuse it for new escrows until it is whitelisted again
+pub fn blacklist_token(env: &Env, token: Address, caller: Address) -> Result<(), Error> {
+    remove_whitelisted_token(env, token.clone(), caller)?;
+    env.storage()
+        .instance()
+        .set(&ConfigKey::BlacklistedToken(token), &true);
+    Ok(())
+}
+
+pub fn is_blacklisted_token(env: &Env, token: &Address) -> bool {
+    env.storage()
+        .instance()
+        .get(&ConfigKey::BlacklistedToken(token.clone()))
+        .unwrap_or(false)
+}
+
+/// Page through whitelisted tokens in whitelisting order
+pub fn get_whitelisted_tokens(env: &Env, start: u32, limit: u32) -> Vec<Address> {
+    page(env, &get_whitelisted_token_list(env), start, limit)
+}
+
+fn page(env: &Env, items: &Vec<Address>, start: u32, limit: u32) -> Vec<Address> {
+    let page_end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(items.len());
+    if start >= page_end {
+        return Vec::new(env);
+    }
+    items.slice(start..page_end)
+}
+
+pub fn get_whitelisted_token_list(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&ConfigKey::WhitelistedTokens)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Page through authorized arbiters in authorization order
+pub fn get_authorized_arbiters(env: &Env, start: u32, limit: u32) -> Vec<Address> {
+    page(env, &get_arbiter_pool(env), start, limit)
+}
+
+/// Replace the contract's code in place, keeping all escrow state
+pub fn upgrade(env: &Env, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+    require_owner(env)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.deployer().update_current_contract_wasm(new_wasm_hash);
+    Ok(())
+}
+
+/// Fail until `migrate` has brought stored records up to this build's layout, so nothing reads
+/// or writes a record between `upgrade` and the last migration batch
+pub fn require_migrated(env: &Env) -> Result<(), Error> {
+    if get_version(env) != CONTRACT_VERSION {
+        return Err(Error::from_contract_error(DeCentPayError::MigrationPending as u32));
+    }
+    Ok(())
+}
+
+/// Get the storage layout version; deployments from before versioning report 1
+pub fn get_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&ConfigKey::Version)
+        .unwrap_or(1)
+}
+
+/// Get the global contract configuration
+pub fn get_config(env: &Env) -> Result<Config, Error> {
+    let bounds = get_duration_bounds(env);
+    Ok(Config {
+        owner: get_owner(env)?,
+        fee_collector: get_fee_collector(env)?,
+        platform_fee_bp: get_platform_fee_bp(env),
+        job_creation_paused: is_job_creation_paused(env),
+        min_duration: bounds.min_duration,
+        max_duration: bounds.max_duration,
+        max_extension: bounds.max_extension,
+    })
+}
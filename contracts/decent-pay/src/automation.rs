@@ -0,0 +1,242 @@
+use crate::escrow_core;
+use crate::work_lifecycle;
+use crate::storage_types::{
+    EscrowData, EscrowKey, EscrowStatus, Milestone, MilestoneStatus, StandingApproval, DeCentPayError,
+    INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error};
+
+fn load_for_depositor(env: &Env, escrow_id: u32, depositor: &Address) -> Result<EscrowData, Error> {
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != *depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+    Ok(escrow)
+}
+
+fn unpaid_milestone(env: &Env, escrow_id: u32, milestone_index: u32) -> Result<Milestone, Error> {
+    let milestone = work_lifecycle::get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    match milestone.status {
+        MilestoneStatus::NotStarted
+        | MilestoneStatus::Submitted
+        | MilestoneStatus::Rejected
+        | MilestoneStatus::PartiallyApproved => Ok(milestone),
+        _ => Err(Error::from_contract_error(DeCentPayError::MilestoneAlreadyProcessed as u32)),
+    }
+}
+
+/// Pre-approve a milestone to be paid out at the `release_at` unix timestamp
+pub fn schedule_milestone_release(
+    env: &Env,
+    escrow_id: u32,
+    milestone_index: u32,
+    release_at: u64,
+    depositor: Address,
+) -> Result<(), Error> {
+    depositor.require_auth();
+
+    let escrow = load_for_depositor(env, escrow_id, &depositor)?;
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    unpaid_milestone(env, escrow_id, milestone_index)?;
+
+    if release_at <= env.ledger().timestamp() {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ScheduledRelease(escrow_id, milestone_index), &release_at);
+    Ok(())
+}
+
+/// Cancel a scheduled milestone release before it executes
+pub fn cancel_scheduled_release(env: &Env, escrow_id: u32, milestone_index: u32, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    load_for_depositor(env, escrow_id, &depositor)?;
+    if get_scheduled_release(env, escrow_id, milestone_index).is_none() {
+        return Err(Error::from_contract_error(DeCentPayError::NoScheduledRelease as u32));
+    }
+
+    env.storage()
+        .instance()
+        .remove(&EscrowKey::ScheduledRelease(escrow_id, milestone_index));
+    Ok(())
+}
+
+/// Get the unix timestamp a milestone is scheduled to be released at
+pub fn get_scheduled_release(env: &Env, escrow_id: u32, milestone_index: u32) -> Option<u64> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::ScheduledRelease(escrow_id, milestone_index))
+}
+
+/// Pay out a scheduled milestone once its release time is reached; callable by anyone
+pub fn execute_scheduled_release(env: &Env, escrow_id: u32, milestone_index: u32) -> Result<(), Error> {
+    let release_at = get_scheduled_release(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoScheduledRelease as u32))?;
+    if env.ledger().timestamp() < release_at {
+        return Err(Error::from_contract_error(DeCentPayError::ReleaseNotDue as u32));
+    }
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let milestone = unpaid_milestone(env, escrow_id, milestone_index)?;
+    env.storage()
+        .instance()
+        .remove(&EscrowKey::ScheduledRelease(escrow_id, milestone_index));
+    work_lifecycle::approve_and_pay(env, escrow_id, milestone_index, escrow, milestone)
+}
+
+/// Let submitted milestones up to `max_milestone_amount` auto-approve `delay` seconds after
+/// submission, until `budget` has been spent
+pub fn grant_standing_approval(
+    env: &Env,
+    escrow_id: u32,
+    max_milestone_amount: i128,
+    budget: i128,
+    delay: u32,
+    depositor: Address,
+) -> Result<(), Error> {
+    depositor.require_auth();
+
+    let escrow = load_for_depositor(env, escrow_id, &depositor)?;
+    if escrow.status != EscrowStatus::Pending && escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    if max_milestone_amount <= 0 || budget <= 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    let approval = StandingApproval {
+        max_milestone_amount,
+        budget_remaining: budget,
+        delay: u64::from(delay),
+    };
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::StandingApproval(escrow_id), &approval);
+    Ok(())
+}
+
+/// Revoke a standing approval
+pub fn revoke_standing_approval(env: &Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    load_for_depositor(env, escrow_id, &depositor)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .remove(&EscrowKey::StandingApproval(escrow_id));
+    Ok(())
+}
+
+/// Get the standing approval granted on an escrow
+pub fn get_standing_approval(env: &Env, escrow_id: u32) -> Option<StandingApproval> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::StandingApproval(escrow_id))
+}
+
+/// Approve a submitted milestone under the escrow's standing approval; callable by anyone
+pub fn auto_approve_milestone(env: &Env, escrow_id: u32, milestone_index: u32) -> Result<(), Error> {
+    let mut approval = get_standing_approval(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoStandingApproval as u32))?;
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let milestone = work_lifecycle::get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    if milestone.status != MilestoneStatus::Submitted {
+        return Err(Error::from_contract_error(DeCentPayError::MilestoneNotSubmitted as u32));
+    }
+
+    if milestone.amount > approval.max_milestone_amount || milestone.amount > approval.budget_remaining {
+        return Err(Error::from_contract_error(DeCentPayError::ExceedsStandingApproval as u32));
+    }
+
+    if env.ledger().timestamp() < milestone.submitted_at + approval.delay {
+        return Err(Error::from_contract_error(DeCentPayError::ReleaseNotDue as u32));
+    }
+
+    approval.budget_remaining -= milestone.amount;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::StandingApproval(escrow_id), &approval);
+    work_lifecycle::approve_and_pay(env, escrow_id, milestone_index, escrow, milestone)
+}
+
+/// Fix how many seconds a new escrow's submissions wait for review before anyone can release them
+pub fn set_review_period(env: &Env, escrow_id: u32, review_period: u64) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ReviewPeriod(escrow_id), &review_period);
+}
+
+/// Get the review period of an escrow in seconds
+pub fn get_review_period(env: &Env, escrow_id: u32) -> Option<u64> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::ReviewPeriod(escrow_id))
+}
+
+/// Pay out a submission the depositor left unreviewed past the review period; callable by anyone
+pub fn auto_release_milestone(env: &Env, escrow_id: u32, milestone_index: u32) -> Result<(), Error> {
+    let review_period = get_review_period(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoReviewPeriod as u32))?;
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let milestone = work_lifecycle::get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    if milestone.status != MilestoneStatus::Submitted {
+        return Err(Error::from_contract_error(DeCentPayError::MilestoneNotSubmitted as u32));
+    }
+
+    if env.ledger().timestamp() < milestone.submitted_at + review_period {
+        return Err(Error::from_contract_error(DeCentPayError::ReleaseNotDue as u32));
+    }
+
+    work_lifecycle::approve_and_pay(env, escrow_id, milestone_index, escrow, milestone)
+}
@@ -0,0 +1,321 @@
+use crate::admin;
+use crate::escrow_core;
+use crate::finality;
+use crate::marketplace;
+use crate::refund_system;
+use crate::storage_types::{
+    ConfigKey, DataKey, EscrowData, EscrowStatus, DeCentPayError, PerformanceBond, Role, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error};
+
+const MAX_BOND_BP: u32 = 5000; // 50% of escrow value
+const DEFAULT_BOND_THRESHOLD_UNITS: i128 = 1_000; // Whole token units
+const BOND_RULING_WINDOW: u64 = 2_592_000; // 30 days in seconds after an emergency refund opens
+
+/// Require the freelancer to lock a performance bond of `bond_bp` when starting work. Only
+/// escrows worth at least the token's bond threshold can require one.
+pub fn require_performance_bond(env: &Env, escrow_id: u32, bond_bp: u32, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::Pending || escrow.work_started {
+        return Err(Error::from_contract_error(DeCentPayError::WorkAlreadyStarted as u32));
+    }
+
+    if bond_bp > MAX_BOND_BP {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+    }
+
+    if bond_bp > 0 && escrow.total_amount < get_bond_threshold(env, escrow.token.clone()) {
+        return Err(Error::from_contract_error(DeCentPayError::BelowBondThreshold as u32));
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    if bond_bp == 0 {
+        env.storage().instance().remove(&DataKey::PerformanceBond(escrow_id));
+    } else {
+        env.storage()
+            .instance()
+            .set(&DataKey::PerformanceBond(escrow_id), &PerformanceBond { bond_bp, amount: 0 });
+    }
+    Ok(())
+}
+
+/// Set the smallest escrow value of a token (None for native XLM) that may require a performance bond
+pub fn set_bond_threshold(env: &Env, token: Option<Address>, threshold: i128, caller: Address) -> Result<(), Error> {
+    admin::require_role(env, &caller, Role::Admin)?;
+    if threshold < 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&ConfigKey::BondThreshold(token_key), &threshold);
+    Ok(())
+}
+
+/// Get the smallest escrow value of a token that may require a performance bond, 1,000 whole
+/// units unless configured
+pub fn get_bond_threshold(env: &Env, token: Option<Address>) -> i128 {
+    let decimals = escrow_core::token_decimals(env, token.as_ref());
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .get(&ConfigKey::BondThreshold(token_key))
+        .unwrap_or_else(|| {
+            10i128
+                .checked_pow(decimals)
+                .and_then(|scale| scale.checked_mul(DEFAULT_BOND_THRESHOLD_UNITS))
+                .unwrap_or(i128::MAX)
+        })
+}
+
+/// Get the performance bond configured for an escrow
+pub fn get_performance_bond(env: &Env, escrow_id: u32) -> Option<PerformanceBond> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&DataKey::PerformanceBond(escrow_id))
+}
+
+/// Lock the required bond from the beneficiary when work starts
+pub fn lock_performance_bond(env: &Env, escrow_id: u32, escrow: &EscrowData, beneficiary: &Address) -> Result<(), Error> {
+    let Some(mut bond) = get_performance_bond(env, escrow_id) else {
+        return Ok(());
+    };
+
+    bond.amount = escrow.total_amount * i128::from(bond.bond_bp) / 10000;
+    if bond.amount > 0 {
+        escrow_core::transfer(env, escrow.token.as_ref(), beneficiary, &env.current_contract_address(), bond.amount);
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), bond.amount)?;
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::PerformanceBond(escrow_id), &bond);
+    Ok(())
+}
+
+/// Pay out a locked bond to `recipient` and clear it
+fn release_performance_bond(env: &Env, escrow_id: u32, escrow: &EscrowData, recipient: &Address) -> Result<(), Error> {
+    let Some(bond) = get_performance_bond(env, escrow_id) else {
+        return Ok(());
+    };
+
+    env.storage().instance().remove(&DataKey::PerformanceBond(escrow_id));
+    if bond.amount > 0 {
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -bond.amount)?;
+        escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), recipient, bond.amount);
+    }
+    Ok(())
+}
+
+/// Return the bond to the beneficiary once the escrow completes
+pub fn return_performance_bond(env: &Env, escrow_id: u32, escrow: &EscrowData) -> Result<(), Error> {
+    if let Some(beneficiary) = &escrow.beneficiary {
+        release_performance_bond(env, escrow_id, escrow, beneficiary)?;
+    }
+    Ok(())
+}
+
+/// Arbiter decision on a bond of an escrow that ended without release: forfeit it to the
+/// depositor or return it to the beneficiary. Any platform arbiter can rule when the escrow has none.
+pub fn settle_performance_bond(env: &Env, escrow_id: u32, arbiter: Address, forfeit: bool) -> Result<(), Error> {
+    arbiter.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    let is_arbiter = if escrow.arbiters.is_empty() {
+        escrow_core::is_authorized_arbiter(env, arbiter.clone())
+    } else {
+        escrow.arbiters.contains(&arbiter)
+    };
+    if !is_arbiter || arbiter == escrow.depositor || escrow.beneficiary.as_ref() == Some(&arbiter) {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyArbiter as u32));
+    }
+
+    require_settleable_bond(env, escrow_id, &escrow)?;
+
+    let beneficiary = escrow
+        .beneficiary
+        .clone()
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoBond as u32))?;
+    let recipient = if forfeit {
+        refund_system::refund_destination(env, escrow_id, &escrow)
+    } else {
+        beneficiary
+    };
+    release_performance_bond(env, escrow_id, &escrow, &recipient)?;
+    Ok(())
+}
+
+/// Forfeit the bond of an escrow refunded after its freelancer abandoned the work, once arbiters
+/// have had a ruling window to settle it otherwise. Callable by anyone.
+pub fn forfeit_unsettled_bond(env: &Env, escrow_id: u32) -> Result<(), Error> {
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    require_settleable_bond(env, escrow_id, &escrow)?;
+    let ruling_closes = escrow.deadline + refund_system::EMERGENCY_REFUND_DELAY + BOND_RULING_WINDOW;
+    if escrow.status != EscrowStatus::Expired || env.ledger().timestamp() < ruling_closes {
+        return Err(Error::from_contract_error(DeCentPayError::BondNotSettleable as u32));
+    }
+
+    let destination = refund_system::refund_destination(env, escrow_id, &escrow);
+    release_performance_bond(env, escrow_id, &escrow, &destination)
+}
+
+fn require_settleable_bond(env: &Env, escrow_id: u32, escrow: &EscrowData) -> Result<(), Error> {
+    finality::require_not_final(env, escrow_id)?;
+
+    let bond = get_performance_bond(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoBond as u32))?;
+    if bond.amount == 0 {
+        return Err(Error::from_contract_error(DeCentPayError::NoBond as u32));
+    }
+
+    if escrow.status != EscrowStatus::Expired && escrow.status != EscrowStatus::Disputed {
+        return Err(Error::from_contract_error(DeCentPayError::BondNotSettleable as u32));
+    }
+    Ok(())
+}
+
+/// Set the seriousness deposit required from open job posters for a token (None for native XLM)
+pub fn set_seriousness_deposit(env: &Env, token: Option<Address>, amount: i128, caller: Address) -> Result<(), Error> {
+    admin::require_role(env, &caller, Role::Treasurer)?;
+    if amount < 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::SeriousnessDeposit(token_key), &amount);
+    Ok(())
+}
+
+/// Get the seriousness deposit required from open job posters for a token
+pub fn get_seriousness_deposit(env: &Env, token: Option<Address>) -> i128 {
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .get(&DataKey::SeriousnessDeposit(token_key))
+        .unwrap_or(0)
+}
+
+/// Get the seriousness deposit held for an open job
+pub fn get_seriousness_deposit_held(env: &Env, escrow_id: u32) -> i128 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&DataKey::SeriousnessDepositHeld(escrow_id))
+        .unwrap_or(0)
+}
+
+/// Lock the seriousness deposit when an open job is posted, unless `collect` is false because a
+/// batch already paid it in
+pub fn lock_seriousness_deposit(
+    env: &Env,
+    escrow_id: u32,
+    depositor: &Address,
+    token: Option<&Address>,
+    collect: bool,
+) -> Result<(), Error> {
+    let deposit = get_seriousness_deposit(env, token.cloned());
+    if deposit <= 0 {
+        return Ok(());
+    }
+
+    if collect {
+        escrow_core::transfer(env, token, depositor, &env.current_contract_address(), deposit);
+    }
+    escrow_core::update_escrowed_amount(env, token, deposit)?;
+    env.storage()
+        .instance()
+        .set(&DataKey::SeriousnessDepositHeld(escrow_id), &deposit);
+    Ok(())
+}
+
+/// Return the seriousness deposit to the depositor after a hire or a proper close
+pub fn return_seriousness_deposit(env: &Env, escrow_id: u32, escrow: &EscrowData) -> Result<(), Error> {
+    let key = DataKey::SeriousnessDepositHeld(escrow_id);
+    let Some(deposit) = env.storage().instance().get::<DataKey, i128>(&key) else {
+        return Ok(());
+    };
+
+    env.storage().instance().remove(&key);
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -deposit)?;
+    let destination = refund_system::refund_destination(env, escrow_id, escrow);
+    escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &destination, deposit);
+    Ok(())
+}
+
+/// Forfeit the seriousness deposit of an abandoned posting, shared among its applicants
+/// (or sent to the fee collector when nobody applied)
+pub fn forfeit_seriousness_deposit(env: &Env, escrow_id: u32, escrow: &EscrowData) -> Result<(), Error> {
+    let key = DataKey::SeriousnessDepositHeld(escrow_id);
+    let Some(deposit) = env.storage().instance().get::<DataKey, i128>(&key) else {
+        return Ok(());
+    };
+
+    env.storage().instance().remove(&key);
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -deposit)?;
+
+    let contract = env.current_contract_address();
+    let applications = marketplace::get_applications(env, escrow_id);
+    if applications.is_empty() {
+        let fee_collector = admin::get_fee_collector(env)?;
+        escrow_core::transfer(env, escrow.token.as_ref(), &contract, &fee_collector, deposit);
+        return Ok(());
+    }
+
+    let share = deposit / i128::from(applications.len());
+    let mut remainder = deposit - share * i128::from(applications.len());
+    for application in applications.iter() {
+        let amount = share + remainder;
+        remainder = 0;
+        if amount > 0 {
+            escrow_core::transfer(env, escrow.token.as_ref(), &contract, &application.freelancer, amount);
+        }
+    }
+    Ok(())
+}
+
+/// Forfeit the seriousness deposit of an open job that passed its deadline without a hire.
+/// Callable by anyone.
+pub fn claim_abandoned_deposit(env: &Env, escrow_id: u32) -> Result<(), Error> {
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if !escrow.is_open_job || (escrow.status != EscrowStatus::Pending && escrow.status != EscrowStatus::Expired) {
+        return Err(Error::from_contract_error(DeCentPayError::JobNotAbandoned as u32));
+    }
+    if env.ledger().timestamp() < escrow.deadline {
+        return Err(Error::from_contract_error(DeCentPayError::JobNotAbandoned as u32));
+    }
+    if get_seriousness_deposit_held(env, escrow_id) == 0 {
+        return Err(Error::from_contract_error(DeCentPayError::NoBond as u32));
+    }
+
+    forfeit_seriousness_deposit(env, escrow_id, &escrow)
+}
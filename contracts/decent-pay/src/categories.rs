@@ -0,0 +1,75 @@
+use crate::escrow_core;
+use crate::storage_types::{
+    DataKey, EscrowKey, DeCentPayError, JobCategory, ListingIndex, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error, Symbol, Vec};
+
+const MAX_SKILL_TAGS: u32 = 5;
+
+/// File a new job under its category
+pub fn set_job_category(env: &Env, escrow_id: u32, category: JobCategory) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    escrow_core::update_index(env, &ListingIndex::Category(category), escrow_id, true);
+    env.storage()
+        .instance()
+        .set(&DataKey::JobCategory(escrow_id), &category);
+}
+
+/// Tag a new job with the skills it needs
+pub fn set_skill_tags(env: &Env, escrow_id: u32, skills: Vec<Symbol>) -> Result<(), Error> {
+    if skills.len() > MAX_SKILL_TAGS {
+        return Err(Error::from_contract_error(DeCentPayError::TooManySkillTags as u32));
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::SkillTags(escrow_id), &skills);
+    Ok(())
+}
+
+/// Get the skill tags of a job
+pub fn get_skill_tags(env: &Env, escrow_id: u32) -> Vec<Symbol> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::SkillTags(escrow_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Get the category of a job
+pub fn get_job_category(env: &Env, escrow_id: u32) -> Option<JobCategory> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&DataKey::JobCategory(escrow_id))
+}
+
+/// Get a user's reputation within a job category
+pub fn get_reputation_by_category(env: &Env, user: Address, category: JobCategory) -> u32 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&DataKey::CategoryReputation(user, category))
+        .unwrap_or(0)
+}
+
+/// Add reputation points to a user's standing in a category, returning the new total
+pub fn add_category_reputation(env: &Env, user: &Address, category: JobCategory, points: u32) -> u32 {
+    let key = DataKey::CategoryReputation(user.clone(), category);
+    let new_rep = env.storage().instance().get::<DataKey, u32>(&key).unwrap_or(0) + points;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(&key, &new_rep);
+    new_rep
+}
@@ -0,0 +1,380 @@
+use crate::escrow_core;
+use crate::escrow_management;
+use crate::idle_yield;
+use crate::refund_system;
+use crate::work_lifecycle;
+use crate::storage_types::{
+    ChangeOrder, ChangeOrderStatus, DataKey, EscrowKey, EscrowData, EscrowStatus, MilestoneAdjustment, MilestoneStatus, DeCentPayError,
+    INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, String, Vec, Error};
+
+const MAX_MILESTONES: u32 = 20;
+
+/// Propose a change order; the proposer's acceptance is recorded immediately. A depositor
+/// proposing an increase pays it in now, with any platform fee increase, held until the order closes.
+pub fn propose_change_order(
+    env: &Env,
+    escrow_id: u32,
+    amount_deltas: Vec<MilestoneAdjustment>,
+    removed_milestones: Vec<u32>,
+    new_milestone_amounts: Vec<i128>,
+    new_milestone_descriptions: Vec<String>,
+    proposer: Address,
+) -> Result<u32, Error> {
+    proposer.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    let is_depositor = escrow.depositor == proposer;
+    let is_beneficiary = escrow.beneficiary.as_ref() == Some(&proposer);
+    if !is_depositor && !is_beneficiary {
+        return Err(Error::from_contract_error(DeCentPayError::Unauthorized as u32));
+    }
+
+    let order = ChangeOrder {
+        requested_by: proposer,
+        amount_deltas,
+        removed_milestones,
+        new_milestone_amounts,
+        new_milestone_descriptions,
+        depositor_accepted: is_depositor,
+        beneficiary_accepted: is_beneficiary,
+        status: ChangeOrderStatus::Proposed,
+        created_at: env.ledger().timestamp(),
+        executed_at: 0,
+    };
+    validate_change_order(env, escrow_id, &escrow, &order)?;
+
+    let order_id: u32 = env
+        .storage()
+        .instance()
+        .get(&EscrowKey::ChangeOrderCount(escrow_id))
+        .unwrap_or(0);
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ChangeOrder(escrow_id, order_id), &order);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ChangeOrderCount(escrow_id), &(order_id + 1));
+    if is_depositor {
+        hold_funding(env, escrow_id, order_id, &escrow, &order)?;
+    }
+    Ok(order_id)
+}
+
+/// Propose appending milestones worth exactly `additional_amount`, as a change order from the depositor.
+/// The amount and its platform fee are paid in now and held until the beneficiary answers.
+pub fn propose_amendment(
+    env: &Env,
+    escrow_id: u32,
+    new_milestones: Vec<(i128, String)>,
+    additional_amount: i128,
+    depositor: Address,
+) -> Result<u32, Error> {
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    let mut new_milestone_amounts = Vec::new(env);
+    let mut new_milestone_descriptions = Vec::new(env);
+    for (amount, description) in new_milestones.iter() {
+        new_milestone_amounts.push_back(amount);
+        new_milestone_descriptions.push_back(description);
+    }
+    if new_milestone_amounts.is_empty() || new_milestone_amounts.iter().sum::<i128>() != additional_amount {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    propose_change_order(
+        env,
+        escrow_id,
+        Vec::new(env),
+        Vec::new(env),
+        new_milestone_amounts,
+        new_milestone_descriptions,
+        depositor,
+    )
+}
+
+/// Accept an amendment as the beneficiary, appending its milestones with the funds held at proposal
+pub fn accept_amendment(env: &Env, escrow_id: u32, amendment_id: u32, beneficiary: Address) -> Result<(), Error> {
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    if escrow.beneficiary.as_ref() != Some(&beneficiary) {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyBeneficiary as u32));
+    }
+    accept_change_order(env, escrow_id, amendment_id, beneficiary)
+}
+
+/// Accept a proposed change order as the counterparty, executing it atomically. A depositor
+/// accepting an increase pays it in as part of the acceptance.
+pub fn accept_change_order(env: &Env, escrow_id: u32, order_id: u32, party: Address) -> Result<(), Error> {
+    party.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let mut order = get_open_change_order(env, escrow_id, order_id)?;
+
+    if escrow.depositor == party {
+        if order.depositor_accepted {
+            return Err(Error::from_contract_error(DeCentPayError::ChangeOrderAlreadyAccepted as u32));
+        }
+        order.depositor_accepted = true;
+        hold_funding(env, escrow_id, order_id, &escrow, &order)?;
+    } else if escrow.beneficiary.as_ref() == Some(&party) {
+        if order.beneficiary_accepted {
+            return Err(Error::from_contract_error(DeCentPayError::ChangeOrderAlreadyAccepted as u32));
+        }
+        order.beneficiary_accepted = true;
+    } else {
+        return Err(Error::from_contract_error(DeCentPayError::Unauthorized as u32));
+    }
+
+    // The escrow may have moved on since the proposal
+    validate_change_order(env, escrow_id, &escrow, &order)?;
+    execute_change_order(env, escrow_id, order_id, &mut escrow, &order)?;
+
+    order.status = ChangeOrderStatus::Executed;
+    order.executed_at = env.ledger().timestamp();
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ChangeOrder(escrow_id, order_id), &order);
+    Ok(())
+}
+
+/// Withdraw or decline a proposed change order (either party)
+pub fn cancel_change_order(env: &Env, escrow_id: u32, order_id: u32, party: Address) -> Result<(), Error> {
+    party.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    if escrow.depositor != party && escrow.beneficiary.as_ref() != Some(&party) {
+        return Err(Error::from_contract_error(DeCentPayError::Unauthorized as u32));
+    }
+
+    let mut order = get_open_change_order(env, escrow_id, order_id)?;
+    order.status = ChangeOrderStatus::Cancelled;
+    let held = take_funding(env, escrow_id, order_id);
+    if held > 0 {
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -held)?;
+        escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &escrow.depositor, held);
+    }
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ChangeOrder(escrow_id, order_id), &order);
+    Ok(())
+}
+
+/// Get a change order by id
+pub fn get_change_order(env: &Env, escrow_id: u32, order_id: u32) -> Option<ChangeOrder> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::ChangeOrder(escrow_id, order_id))
+}
+
+/// Get every change order recorded against an escrow, oldest first
+pub fn get_change_orders(env: &Env, escrow_id: u32) -> Vec<ChangeOrder> {
+    let count: u32 = env
+        .storage()
+        .instance()
+        .get(&EscrowKey::ChangeOrderCount(escrow_id))
+        .unwrap_or(0);
+    let mut orders = Vec::new(env);
+    for order_id in 0..count {
+        if let Some(order) = get_change_order(env, escrow_id, order_id) {
+            orders.push_back(order);
+        }
+    }
+    orders
+}
+
+fn get_open_change_order(env: &Env, escrow_id: u32, order_id: u32) -> Result<ChangeOrder, Error> {
+    let order = get_change_order(env, escrow_id, order_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::ChangeOrderNotFound as u32))?;
+    if order.status != ChangeOrderStatus::Proposed {
+        return Err(Error::from_contract_error(DeCentPayError::ChangeOrderClosed as u32));
+    }
+    Ok(order)
+}
+
+/// Get the funding the depositor paid in towards an open change order
+pub fn get_change_order_funding(env: &Env, escrow_id: u32, order_id: u32) -> i128 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::ChangeOrderFunding(escrow_id, order_id))
+        .unwrap_or(0)
+}
+
+/// Pull the increase a change order makes to the escrow, with its platform fee, from the depositor
+fn hold_funding(env: &Env, escrow_id: u32, order_id: u32, escrow: &EscrowData, order: &ChangeOrder) -> Result<(), Error> {
+    let increase = funding_delta(env, escrow_id, order);
+    let due = increase.max(0) + escrow_core::platform_fee_top_up(env, escrow_id, escrow, escrow.total_amount + increase);
+    if due <= 0 {
+        return Ok(());
+    }
+
+    escrow_core::transfer(env, escrow.token.as_ref(), &escrow.depositor, &env.current_contract_address(), due);
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), due)?;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ChangeOrderFunding(escrow_id, order_id), &due);
+    Ok(())
+}
+
+/// Remove the funding held for a change order, returning how much it was
+fn take_funding(env: &Env, escrow_id: u32, order_id: u32) -> i128 {
+    let held = get_change_order_funding(env, escrow_id, order_id);
+    env.storage()
+        .instance()
+        .remove(&EscrowKey::ChangeOrderFunding(escrow_id, order_id));
+    held
+}
+
+/// Net change in escrow value a change order would cause
+fn funding_delta(env: &Env, escrow_id: u32, order: &ChangeOrder) -> i128 {
+    let mut delta: i128 = order.amount_deltas.iter().map(|adjustment| adjustment.amount_delta).sum();
+    delta += order.new_milestone_amounts.iter().sum::<i128>();
+    for milestone_index in order.removed_milestones.iter() {
+        if let Some(milestone) = work_lifecycle::get_milestone(env, escrow_id, milestone_index) {
+            delta -= milestone.amount;
+        }
+    }
+    delta
+}
+
+fn validate_change_order(env: &Env, escrow_id: u32, escrow: &EscrowData, order: &ChangeOrder) -> Result<(), Error> {
+    if escrow.status != EscrowStatus::Pending && escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    if escrow.beneficiary.is_none() {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidEscrowStatus as u32));
+    }
+
+    if order.new_milestone_amounts.len() != order.new_milestone_descriptions.len() {
+        return Err(Error::from_contract_error(DeCentPayError::MilestoneCountMismatch as u32));
+    }
+
+    if escrow.milestone_count + order.new_milestone_amounts.len() > MAX_MILESTONES {
+        return Err(Error::from_contract_error(DeCentPayError::TooManyMilestones as u32));
+    }
+
+    if order.new_milestone_amounts.iter().any(|amount| amount <= 0) {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    // Only milestones nobody has delivered on yet can be repriced or removed
+    let changed = order
+        .amount_deltas
+        .iter()
+        .map(|adjustment| adjustment.milestone_index)
+        .chain(order.removed_milestones.iter());
+    for milestone_index in changed {
+        let milestone = work_lifecycle::get_milestone(env, escrow_id, milestone_index)
+            .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+        if milestone.status != MilestoneStatus::NotStarted && milestone.status != MilestoneStatus::Rejected {
+            return Err(Error::from_contract_error(DeCentPayError::MilestoneLocked as u32));
+        }
+    }
+
+    for (position, milestone_index) in order.removed_milestones.iter().enumerate() {
+        let position = u32::try_from(position).unwrap_or(u32::MAX);
+        if order.removed_milestones.first_index_of(milestone_index) != Some(position) {
+            return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+        }
+    }
+
+    for (position, adjustment) in order.amount_deltas.iter().enumerate() {
+        let repeated = order
+            .amount_deltas
+            .iter()
+            .take(position)
+            .any(|earlier| earlier.milestone_index == adjustment.milestone_index);
+        if repeated || order.removed_milestones.contains(adjustment.milestone_index) {
+            return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+        }
+        let milestone = work_lifecycle::get_milestone(env, escrow_id, adjustment.milestone_index)
+            .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+        if milestone.amount + adjustment.amount_delta <= 0 {
+            return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+        }
+    }
+    Ok(())
+}
+
+fn execute_change_order(
+    env: &Env,
+    escrow_id: u32,
+    order_id: u32,
+    escrow: &mut EscrowData,
+    order: &ChangeOrder,
+) -> Result<(), Error> {
+    // An increase was paid in when the depositor signed; a decrease is refunded
+    let held = take_funding(env, escrow_id, order_id);
+    let delta = funding_delta(env, escrow_id, order);
+    if delta > held {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    } else if delta < 0 {
+        idle_yield::unwind(env, escrow_id, escrow)?;
+        refund_system::return_principal(env, escrow_id, escrow, -delta)?;
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), delta)?;
+    }
+    escrow.total_amount += delta;
+    escrow_core::reprice_platform_fee(env, escrow_id, escrow, held - delta.max(0))?;
+
+    // Rewrite the milestone set; removed milestones keep their index so their history stays addressable
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    for adjustment in order.amount_deltas.iter() {
+        if let Some(mut milestone) = work_lifecycle::get_milestone(env, escrow_id, adjustment.milestone_index) {
+            milestone.amount += adjustment.amount_delta;
+            env.storage()
+                .instance()
+                .set(&DataKey::Milestone(escrow_id, adjustment.milestone_index), &milestone);
+        }
+    }
+    for milestone_index in order.removed_milestones.iter() {
+        if let Some(mut milestone) = work_lifecycle::get_milestone(env, escrow_id, milestone_index) {
+            milestone.status = MilestoneStatus::Removed;
+            env.storage()
+                .instance()
+                .set(&DataKey::Milestone(escrow_id, milestone_index), &milestone);
+        }
+        work_lifecycle::refund_revision_fee(env, escrow_id, milestone_index, escrow)?;
+    }
+    escrow_management::save_milestones(
+        env,
+        escrow_id,
+        escrow.milestone_count,
+        &order.new_milestone_amounts,
+        &order.new_milestone_descriptions,
+    );
+    escrow.milestone_count += order.new_milestone_amounts.len();
+
+    if escrow.status == EscrowStatus::InProgress {
+        work_lifecycle::complete_if_paid(env, escrow_id, escrow)?;
+    }
+    escrow_core::save_escrow(env, escrow_id, escrow);
+    Ok(())
+}
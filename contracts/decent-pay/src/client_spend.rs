@@ -0,0 +1,39 @@
+use crate::escrow_core;
+use crate::storage_types::{AccountKey, PayerBadge, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::{Address, Env};
+
+const VERIFIED_PAYER_UNITS: i128 = 1_000; // Whole token units, e.g. 1,000 XLM
+const TRUSTED_PAYER_UNITS: i128 = 10_000;
+
+/// Record an amount a depositor released to their freelancer
+pub fn record_release(env: &Env, depositor: &Address, token: Option<&Address>, amount: i128) {
+    let key = AccountKey::ClientSpend(depositor.clone(), escrow_core::token_address(env, token));
+    let spent = env.storage().instance().get::<AccountKey, i128>(&key).unwrap_or(0) + amount;
+    env.storage().instance().set(&key, &spent);
+}
+
+/// Get how much a depositor has released through escrows in a token (None for native XLM)
+pub fn get_client_spend(env: &Env, depositor: Address, token: Option<Address>) -> i128 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&AccountKey::ClientSpend(depositor, escrow_core::token_address(env, token.as_ref())))
+        .unwrap_or(0)
+}
+
+/// Get the payer badge a depositor's releases in a token (None for native XLM) have earned
+pub fn get_payer_badge(env: &Env, depositor: Address, token: Option<Address>) -> PayerBadge {
+    let scale = 10i128
+        .checked_pow(escrow_core::token_decimals(env, token.as_ref()))
+        .unwrap_or(i128::MAX);
+    let spent = get_client_spend(env, depositor, token);
+    if spent > TRUSTED_PAYER_UNITS.saturating_mul(scale) {
+        PayerBadge::TrustedPayer
+    } else if spent > VERIFIED_PAYER_UNITS.saturating_mul(scale) {
+        PayerBadge::VerifiedPayer
+    } else {
+        PayerBadge::Unverified
+    }
+}
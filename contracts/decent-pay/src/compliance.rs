@@ -0,0 +1,87 @@
+use crate::admin;
+use crate::storage_types::{DataKey, DeCentPayError, KycConfig, Role, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::{contractclient, Address, Env, Error};
+
+/// Interface expected from an external attestation (KYC) contract
+#[allow(dead_code)]
+#[contractclient(name = "AttestationClient")]
+pub trait AttestationInterface {
+    /// Whether `subject` holds a valid KYC credential
+    fn has_kyc(env: Env, subject: Address) -> bool;
+}
+
+/// Interface expected from an external sanctions/denylist screening contract
+#[allow(dead_code)]
+#[contractclient(name = "ScreeningClient")]
+pub trait ScreeningInterface {
+    /// Whether `account` is flagged and must not move funds
+    fn is_flagged(env: Env, account: Address) -> bool;
+}
+
+/// Set or clear (with `None`) the KYC attestation gate
+pub fn set_kyc_config(env: &Env, config: Option<KycConfig>, caller: Address) -> Result<(), Error> {
+    admin::require_role(env, &caller, Role::Admin)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    match config {
+        Some(config) => {
+            if config.value_threshold < 0 {
+                return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+            }
+            env.storage().instance().set(&DataKey::KycConfig, &config);
+        }
+        None => env.storage().instance().remove(&DataKey::KycConfig),
+    }
+    Ok(())
+}
+
+pub fn get_kyc_config(env: &Env) -> Option<KycConfig> {
+    env.storage().instance().get(&DataKey::KycConfig)
+}
+
+/// Require a KYC credential from `subject` when the escrow value exceeds the configured threshold
+pub fn require_kyc(env: &Env, subject: &Address, escrow_value: i128) -> Result<(), Error> {
+    let Some(config) = get_kyc_config(env) else {
+        return Ok(());
+    };
+    if escrow_value <= config.value_threshold {
+        return Ok(());
+    }
+
+    let attestation = AttestationClient::new(env, &config.attestation_contract);
+    if !attestation.has_kyc(subject) {
+        return Err(Error::from_contract_error(DeCentPayError::KycRequired as u32));
+    }
+    Ok(())
+}
+
+/// Set or clear (with `None`) the external screening contract
+pub fn set_screening_contract(env: &Env, screening_contract: Option<Address>, caller: Address) -> Result<(), Error> {
+    admin::require_role(env, &caller, Role::Admin)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    match screening_contract {
+        Some(address) => env.storage().instance().set(&DataKey::ScreeningContract, &address),
+        None => env.storage().instance().remove(&DataKey::ScreeningContract),
+    }
+    Ok(())
+}
+
+pub fn get_screening_contract(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::ScreeningContract)
+}
+
+/// Revert if the screening contract flags `account` as a counterparty of a fund movement
+pub fn require_not_flagged(env: &Env, account: &Address) -> Result<(), Error> {
+    let Some(screening_contract) = get_screening_contract(env) else {
+        return Ok(());
+    };
+
+    let screening = ScreeningClient::new(env, &screening_contract);
+    if screening.is_flagged(account) {
+        return Err(Error::from_contract_error(DeCentPayError::AddressFlagged as u32));
+    }
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use crate::escrow_core;
+use crate::work_lifecycle;
+use crate::storage_types::{
+    EscrowData, EscrowKey, EscrowStatus, Milestone, MilestoneStatus, DeCentPayError, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error};
+
+/// Require an arbiter co-signature on every milestone approval above `threshold` (0 removes it)
+pub fn set_cosign_threshold(env: &Env, escrow_id: u32, threshold: i128, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::Pending || escrow.work_started {
+        return Err(Error::from_contract_error(DeCentPayError::WorkAlreadyStarted as u32));
+    }
+
+    if threshold < 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    if threshold == 0 {
+        env.storage().instance().remove(&EscrowKey::CosignThreshold(escrow_id));
+    } else {
+        env.storage()
+            .instance()
+            .set(&EscrowKey::CosignThreshold(escrow_id), &threshold);
+    }
+    Ok(())
+}
+
+/// Get the milestone amount above which an escrow's approvals need an arbiter co-signature
+pub fn get_cosign_threshold(env: &Env, escrow_id: u32) -> Option<i128> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::CosignThreshold(escrow_id))
+}
+
+/// Check whether approving `amount` on an escrow needs an arbiter co-signature
+pub fn requires_cosign(env: &Env, escrow_id: u32, amount: i128) -> bool {
+    get_cosign_threshold(env, escrow_id).is_some_and(|threshold| amount > threshold)
+}
+
+/// Hold an approved milestone until an arbiter co-signs it
+pub fn request(env: &Env, escrow_id: u32, milestone_index: u32) {
+    env.storage()
+        .instance()
+        .set(&EscrowKey::CosignPending(escrow_id, milestone_index), &true);
+}
+
+/// Check whether an approved milestone is waiting for an arbiter co-signature
+pub fn is_cosign_pending(env: &Env, escrow_id: u32, milestone_index: u32) -> bool {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::CosignPending(escrow_id, milestone_index))
+        .unwrap_or(false)
+}
+
+/// Co-sign a milestone the depositor approved, paying it out. Any of the escrow's arbiters can
+/// co-sign, or any platform arbiter when it has none.
+pub fn cosign_milestone(env: &Env, escrow_id: u32, milestone_index: u32, arbiter: Address) -> Result<(), Error> {
+    arbiter.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let milestone = check_cosign(env, escrow_id, milestone_index, &escrow, &arbiter)?;
+
+    env.storage()
+        .instance()
+        .remove(&EscrowKey::CosignPending(escrow_id, milestone_index));
+    work_lifecycle::pay_approved(env, escrow_id, milestone_index, escrow, milestone)
+}
+
+/// Check an arbiter may co-sign a held approval, returning the milestone
+pub fn check_cosign(
+    env: &Env,
+    escrow_id: u32,
+    milestone_index: u32,
+    escrow: &EscrowData,
+    arbiter: &Address,
+) -> Result<Milestone, Error> {
+    let is_arbiter = if escrow.arbiters.is_empty() {
+        escrow_core::is_authorized_arbiter(env, arbiter.clone())
+    } else {
+        escrow.arbiters.contains(arbiter)
+    };
+    if !is_arbiter || *arbiter == escrow.depositor || escrow.beneficiary.as_ref() == Some(arbiter) {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyArbiter as u32));
+    }
+
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    // A dispute raised while the approval waited takes precedence
+    let milestone = work_lifecycle::get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    if !is_cosign_pending(env, escrow_id, milestone_index)
+        || (milestone.status != MilestoneStatus::Submitted
+            && milestone.status != MilestoneStatus::NotStarted
+            && milestone.status != MilestoneStatus::PartiallyApproved)
+    {
+        return Err(Error::from_contract_error(DeCentPayError::CosignNotRequested as u32));
+    }
+    Ok(milestone)
+}
@@ -0,0 +1,96 @@
+use crate::achievements;
+use crate::escrow_core;
+use crate::storage_types::{
+    AccountKey, Achievement, AchievementRecord, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env};
+
+const EARNINGS_CREDENTIAL_UNITS: i128 = 10_000; // Whole token units, e.g. $10k in a USD stablecoin
+const ARBITRATION_CREDENTIAL_DISPUTES: u32 = 10;
+
+/// Issue a credential to `holder`. Credentials are bound to the address they were earned by:
+/// there is no way to transfer or revoke them, and each is issued at most once.
+pub fn issue(env: &Env, holder: &Address, achievement: Achievement, escrow_id: u32) {
+    let key = AccountKey::Credential(holder.clone(), achievement);
+    if env.storage().instance().has(&key) {
+        return;
+    }
+    env.storage().instance().set(
+        &key,
+        &AchievementRecord {
+            achievement,
+            escrow_id,
+            earned_at: env.ledger().timestamp(),
+        },
+    );
+}
+
+/// Get the credential `holder` was issued for an achievement, including achievements earned
+/// before credentials were keyed by holder
+pub fn get_credential(env: &Env, holder: Address, achievement: Achievement) -> Option<AchievementRecord> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&AccountKey::Credential(holder.clone(), achievement))
+        .or_else(|| {
+            achievements::get_achievements(env, holder)
+                .iter()
+                .find(|record| record.achievement == achievement)
+        })
+}
+
+/// Check if `holder` holds a credential, for wallets and contracts gating on it
+pub fn has_credential(env: &Env, holder: Address, achievement: Achievement) -> bool {
+    get_credential(env, holder, achievement).is_some()
+}
+
+/// Record a payment credited to a freelancer, crediting them once their lifetime earnings in
+/// the token reach 10,000 whole units
+pub fn on_payment_earned(env: &Env, freelancer: &Address, token: Option<&Address>, amount: i128, escrow_id: u32) {
+    let key = AccountKey::TotalEarned(freelancer.clone(), escrow_core::token_address(env, token));
+    let earned = env.storage().instance().get::<AccountKey, i128>(&key).unwrap_or(0) + amount;
+    env.storage().instance().set(&key, &earned);
+
+    let threshold = 10i128
+        .checked_pow(escrow_core::token_decimals(env, token))
+        .and_then(|scale| scale.checked_mul(EARNINGS_CREDENTIAL_UNITS))
+        .unwrap_or(i128::MAX);
+    if earned >= threshold {
+        achievements::award(env, freelancer, Achievement::TenThousandEarned, escrow_id);
+    }
+}
+
+/// Record an arbiter whose ruling settled a dispute
+pub fn on_dispute_arbitrated(env: &Env, arbiter: &Address, escrow_id: u32) {
+    let key = AccountKey::DisputesArbitrated(arbiter.clone());
+    let count = env.storage().instance().get::<AccountKey, u32>(&key).unwrap_or(0) + 1;
+    env.storage().instance().set(&key, &count);
+
+    if count >= ARBITRATION_CREDENTIAL_DISPUTES {
+        achievements::award(env, arbiter, Achievement::TenDisputesArbitrated, escrow_id);
+    }
+}
+
+/// Get a freelancer's lifetime earnings in a token (None for native XLM)
+pub fn get_total_earned(env: &Env, freelancer: Address, token: Option<Address>) -> i128 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&AccountKey::TotalEarned(freelancer, escrow_core::token_address(env, token.as_ref())))
+        .unwrap_or(0)
+}
+
+/// Get how many disputes an arbiter's ruling has settled
+pub fn get_disputes_arbitrated(env: &Env, arbiter: Address) -> u32 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&AccountKey::DisputesArbitrated(arbiter))
+        .unwrap_or(0)
+}
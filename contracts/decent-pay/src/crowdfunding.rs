@@ -0,0 +1,190 @@
+use crate::compliance;
+use crate::escrow_core;
+use crate::escrow_management;
+use crate::refund_system;
+use crate::work_lifecycle;
+use crate::storage_types::{
+    DeCentPayError, EscrowData, EscrowKey, EscrowStatus, MilestoneStatus, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error, Vec};
+
+/// Contribute towards the deposit of an escrow still in Funding. Anyone may chip in; the escrow
+/// opens as Pending once the total is covered, and later refunds are shared out pro-rata.
+pub fn contribute(env: &Env, escrow_id: u32, amount: i128, contributor: Address) -> Result<(), Error> {
+    contributor.require_auth();
+    add_contribution(env, escrow_id, amount, contributor)
+}
+
+/// Pay `amount` in towards an escrow still in Funding for a contributor who already authorized it
+pub fn add_contribution(env: &Env, escrow_id: u32, amount: i128, contributor: Address) -> Result<(), Error> {
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.status != EscrowStatus::Funding {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidEscrowStatus as u32));
+    }
+
+    compliance::require_not_flagged(env, &contributor)?;
+
+    let funded = escrow_management::get_funded_amount(env, escrow_id, &escrow) + amount;
+    if amount <= 0 || funded > escrow.total_amount {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    escrow_core::transfer(env, escrow.token.as_ref(), &contributor, &env.current_contract_address(), amount);
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), amount)?;
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    let previous = get_contribution(env, escrow_id, contributor.clone());
+    if previous == 0 {
+        let mut contributors = get_contributors(env, escrow_id);
+        contributors.push_back(contributor.clone());
+        env.storage()
+            .instance()
+            .set(&EscrowKey::Contributors(escrow_id), &contributors);
+    }
+    env.storage()
+        .instance()
+        .set(&EscrowKey::Contribution(escrow_id, contributor), &(previous + amount));
+
+    if funded < escrow.total_amount {
+        env.storage()
+            .instance()
+            .set(&EscrowKey::FundedAmount(escrow_id), &funded);
+        return Ok(());
+    }
+
+    // Fully funded; the posting costs were paid in by the depositor at creation
+    env.storage().instance().remove(&EscrowKey::FundedAmount(escrow_id));
+    escrow.status = EscrowStatus::Pending;
+    escrow_core::save_escrow(env, escrow_id, &escrow);
+    Ok(())
+}
+
+/// Vote as a contributor to approve a submitted milestone. It is approved and paid once
+/// contributors holding more than half of the funding agree.
+pub fn vote_approve_milestone(env: &Env, escrow_id: u32, milestone_index: u32, contributor: Address) -> Result<(), Error> {
+    contributor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if get_contribution(env, escrow_id, contributor.clone()) == 0 {
+        return Err(Error::from_contract_error(DeCentPayError::Unauthorized as u32));
+    }
+
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let milestone = work_lifecycle::get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    if milestone.status != MilestoneStatus::Submitted {
+        return Err(Error::from_contract_error(DeCentPayError::MilestoneNotSubmitted as u32));
+    }
+
+    let mut votes = get_approval_votes(env, escrow_id, milestone_index);
+    if votes.contains(&contributor) {
+        return Err(Error::from_contract_error(DeCentPayError::AlreadyVoted as u32));
+    }
+    votes.push_back(contributor);
+
+    let in_favour: i128 = votes
+        .iter()
+        .map(|voter| get_contribution(env, escrow_id, voter))
+        .sum();
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    if in_favour * 2 <= total_contributed(env, escrow_id) {
+        env.storage()
+            .instance()
+            .set(&EscrowKey::ApprovalVotes(escrow_id, milestone_index), &votes);
+        return Ok(());
+    }
+
+    env.storage()
+        .instance()
+        .remove(&EscrowKey::ApprovalVotes(escrow_id, milestone_index));
+    work_lifecycle::approve_and_pay(env, escrow_id, milestone_index, escrow, milestone)
+}
+
+/// Get the funders of an escrow, in order of first contribution
+pub fn get_contributors(env: &Env, escrow_id: u32) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&EscrowKey::Contributors(escrow_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Get how much an address has contributed to an escrow
+pub fn get_contribution(env: &Env, escrow_id: u32, contributor: Address) -> i128 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::Contribution(escrow_id, contributor))
+        .unwrap_or(0)
+}
+
+/// Get the contributors who have voted to approve a milestone so far
+pub fn get_approval_votes(env: &Env, escrow_id: u32, milestone_index: u32) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&EscrowKey::ApprovalVotes(escrow_id, milestone_index))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Share a refund of `amount` among the contributors of a crowdfunded escrow in proportion to
+/// what they put in, the last one taking the rounding remainder. The primary depositor's share
+/// goes to their refund destination. Returns false, moving nothing, if only the depositor paid in.
+/// Fails, moving nothing, if any contributor is flagged by the screening contract.
+pub fn refund_contributors(env: &Env, escrow_id: u32, escrow: &EscrowData, amount: i128) -> Result<bool, Error> {
+    let contributors = get_contributors(env, escrow_id);
+    if contributors.iter().all(|contributor| contributor == escrow.depositor) {
+        return Ok(false);
+    }
+    screen_contributors(env, escrow_id)?;
+
+    let total = total_contributed(env, escrow_id);
+    let last = contributors.len() - 1;
+    let mut remaining = amount;
+    for (position, contributor) in contributors.iter().enumerate() {
+        let share = if u32::try_from(position).unwrap_or(u32::MAX) == last {
+            remaining
+        } else {
+            amount * get_contribution(env, escrow_id, contributor.clone()) / total
+        };
+        remaining -= share;
+        let destination = if contributor == escrow.depositor {
+            refund_system::refund_destination(env, escrow_id, escrow)
+        } else {
+            contributor
+        };
+        if share > 0 {
+            escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &destination, share);
+        }
+    }
+    Ok(true)
+}
+
+/// Require that no contributor to an escrow is flagged, so a refund can be shared out in full
+pub fn screen_contributors(env: &Env, escrow_id: u32) -> Result<(), Error> {
+    for contributor in get_contributors(env, escrow_id).iter() {
+        compliance::require_not_flagged(env, &contributor)?;
+    }
+    Ok(())
+}
+
+fn total_contributed(env: &Env, escrow_id: u32) -> i128 {
+    get_contributors(env, escrow_id)
+        .iter()
+        .map(|contributor| get_contribution(env, escrow_id, contributor))
+        .sum()
+}
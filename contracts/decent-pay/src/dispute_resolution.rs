@@ -0,0 +1,418 @@
+use crate::admin;
+use crate::credentials;
+use crate::escalation;
+use crate::escrow_core;
+use crate::marketplace;
+use crate::milestone_log;
+use crate::ratings;
+use crate::refund_system;
+use crate::reputation;
+use crate::work_lifecycle;
+use crate::storage_types::{
+    ActivityAction, ArbiterVote, ArbitrationDeposit, ArbitrationFee, ConfigKey, DisputeEvidence, DisputeRuling, EscrowData, EscrowKey,
+    EscrowStatus, Milestone, MilestoneStatus, Role, SlashReason, DeCentPayError, DAY_IN_SECONDS, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, BytesN, Env, String, Vec, Error};
+
+const DEFAULT_EVIDENCE_WINDOW: u64 = 7 * DAY_IN_SECONDS;
+const MAX_DISPUTE_EVIDENCE: u32 = 20;
+
+/// Arbiter vote on a disputed milestone. Once the milestone's confirmation threshold agrees on
+/// a ruling the funds move and the escrow leaves `Disputed`. Panels without arbiters fall back
+/// to the appointed platform arbiter, or any platform arbiter while none is appointed.
+/// Check an arbiter may still vote on a disputed milestone, returning the milestone and the
+/// number of agreeing votes a ruling needs
+pub fn check_can_rule(
+    env: &Env,
+    escrow_id: u32,
+    milestone_index: u32,
+    escrow: &EscrowData,
+    arbiter: &Address,
+) -> Result<(Milestone, u32), Error> {
+    if escrow.status != EscrowStatus::Disputed {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let milestone = work_lifecycle::get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    if milestone.status != MilestoneStatus::Disputed {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidMilestone as u32));
+    }
+
+    // Escalated disputes are ruled on through rule_escalation instead
+    if escalation::get_escalation(env, escrow_id).is_some() {
+        return Err(Error::from_contract_error(DeCentPayError::DisputeEscalated as u32));
+    }
+
+    let (is_arbiter, required) = dispute_arbiter(env, escrow_id, milestone_index, arbiter)?;
+    if !is_arbiter {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyArbiter as u32));
+    }
+
+    if get_dispute_votes(env, escrow_id, milestone_index).iter().any(|vote| vote.arbiter == *arbiter) {
+        return Err(Error::from_contract_error(DeCentPayError::AlreadyVoted as u32));
+    }
+    Ok((milestone, required))
+}
+
+pub fn resolve_dispute(
+    env: &Env,
+    escrow_id: u32,
+    milestone_index: u32,
+    ruling: DisputeRuling,
+    arbiter: Address,
+) -> Result<(), Error> {
+    arbiter.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let (milestone, required) = check_can_rule(env, escrow_id, milestone_index, &escrow, &arbiter)?;
+
+    let beneficiary_amount = match ruling {
+        DisputeRuling::Release => milestone.amount,
+        DisputeRuling::Refund => 0,
+        DisputeRuling::Split(amount) => amount,
+        DisputeRuling::SplitRemainder(beneficiary_bp) if beneficiary_bp <= 10000 => {
+            milestone.amount * i128::from(beneficiary_bp) / 10000
+        }
+        DisputeRuling::SplitRemainder(_) => -1,
+    };
+    if beneficiary_amount < 0 || beneficiary_amount > milestone.amount {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    let mut votes = get_dispute_votes(env, escrow_id, milestone_index);
+    milestone_log::record_activity(env, escrow_id, &arbiter, ActivityAction::DisputeVoteCast(milestone_index), None);
+    votes.push_back(ArbiterVote {
+        arbiter,
+        ruling: ruling.clone(),
+    });
+
+    let agreeing = votes.iter().filter(|vote| vote.ruling == ruling).count();
+    if u32::try_from(agreeing).unwrap_or(u32::MAX) < required {
+        env.storage()
+            .instance()
+            .set(&EscrowKey::DisputeVotes(escrow_id, milestone_index), &votes);
+        return Ok(());
+    }
+
+    pay_arbitration_fee(env, escrow_id, &escrow, milestone.amount, &votes)?;
+    for vote in votes.iter().filter(|vote| vote.ruling == ruling) {
+        credentials::on_dispute_arbitrated(env, &vote.arbiter, escrow_id);
+    }
+
+    if let Some(beneficiary) = &escrow.beneficiary {
+        match ruling {
+            DisputeRuling::Release => {
+                ratings::record_client_outcome(env, &escrow.depositor, |record| record.disputes_lost += 1);
+                reputation::slash(env, escrow_id, &escrow.depositor, SlashReason::DisputeLost);
+            }
+            DisputeRuling::Refund => {
+                ratings::record_adverse_outcome(env, beneficiary, |record| record.disputes_lost += 1);
+                reputation::slash(env, escrow_id, beneficiary, SlashReason::DisputeLost);
+                marketplace::burn_reputation_stake(env, escrow_id, beneficiary);
+            }
+            DisputeRuling::Split(_) | DisputeRuling::SplitRemainder(_) => {}
+        }
+    }
+    match ruling {
+        DisputeRuling::SplitRemainder(beneficiary_bp) => {
+            work_lifecycle::split_remaining(env, escrow_id, milestone_index, beneficiary_bp)
+        }
+        _ => work_lifecycle::resolve_disputed_milestone(env, escrow_id, milestone_index, beneficiary_amount),
+    }
+}
+
+/// Vote to settle a disputed escrow as a whole: `beneficiary_bp` of everything still unpaid goes
+/// to the beneficiary and the rest back to the depositor, clawing the depositor's share of an
+/// already approved disputed milestone back from the retainage holdback
+pub fn resolve_with_split(env: &Env, escrow_id: u32, beneficiary_bp: u32, arbiter: Address) -> Result<(), Error> {
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let milestone_index = (0..escrow.milestone_count)
+        .find(|milestone_index| {
+            work_lifecycle::get_milestone(env, escrow_id, *milestone_index)
+                .is_some_and(|milestone| milestone.status == MilestoneStatus::Disputed)
+        })
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+
+    resolve_dispute(env, escrow_id, milestone_index, DisputeRuling::SplitRemainder(beneficiary_bp), arbiter)
+}
+
+/// Whether `arbiter` may rule on a disputed milestone, and how many agreeing votes a ruling needs
+fn dispute_arbiter(env: &Env, escrow_id: u32, milestone_index: u32, arbiter: &Address) -> Result<(bool, u32), Error> {
+    let (panel, required) = ruling_panel(env, escrow_id, milestone_index)?;
+    if panel.is_empty() {
+        Ok((escrow_core::is_authorized_arbiter(env, arbiter.clone()), 1))
+    } else {
+        Ok((panel.contains(arbiter), required))
+    }
+}
+
+/// Get the panel appointed to rule on a disputed milestone and how many of it must agree: the
+/// milestone's arbiters, or the platform arbiter appointed to it when it has none. Empty while
+/// neither exists.
+pub fn ruling_panel(env: &Env, escrow_id: u32, milestone_index: u32) -> Result<(Vec<Address>, u32), Error> {
+    let panel = work_lifecycle::get_milestone_arbiters(env, escrow_id, milestone_index)?;
+    if !panel.arbiters.is_empty() {
+        return Ok((panel.arbiters, panel.required_confirmations.max(1)));
+    }
+    let mut appointed = Vec::new(env);
+    if let Some(arbiter) = get_appointed_arbiter(env, escrow_id, milestone_index) {
+        appointed.push_back(arbiter);
+    }
+    Ok((appointed, 1))
+}
+
+/// Attach evidence to a disputed milestone. Open to both parties and the milestone's arbiters
+/// until the evidence window after the dispute was raised closes.
+pub fn submit_dispute_evidence(
+    env: &Env,
+    escrow_id: u32,
+    milestone_index: u32,
+    evidence_hash: BytesN<32>,
+    uri: String,
+    submitter: Address,
+) -> Result<(), Error> {
+    submitter.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    if escrow.status != EscrowStatus::Disputed {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let milestone = work_lifecycle::get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    if milestone.status != MilestoneStatus::Disputed {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidMilestone as u32));
+    }
+
+    let is_party = escrow.depositor == submitter || escrow.beneficiary.as_ref() == Some(&submitter);
+    if !is_party && !dispute_arbiter(env, escrow_id, milestone_index, &submitter)?.0 {
+        return Err(Error::from_contract_error(DeCentPayError::Unauthorized as u32));
+    }
+
+    if env.ledger().timestamp() > milestone.disputed_at + get_evidence_window(env) {
+        return Err(Error::from_contract_error(DeCentPayError::EvidenceWindowClosed as u32));
+    }
+
+    let mut evidence = get_dispute_evidence(env, escrow_id, milestone_index);
+    if evidence.len() >= MAX_DISPUTE_EVIDENCE {
+        return Err(Error::from_contract_error(DeCentPayError::TooMuchEvidence as u32));
+    }
+    evidence.push_back(DisputeEvidence {
+        submitted_by: submitter,
+        evidence_hash,
+        uri,
+        submitted_at: env.ledger().timestamp(),
+    });
+    escrow_core::set_persistent(env, &EscrowKey::DisputeEvidence(escrow_id, milestone_index), &evidence);
+    Ok(())
+}
+
+/// Get the evidence submitted on a disputed milestone, in submission order
+pub fn get_dispute_evidence(env: &Env, escrow_id: u32, milestone_index: u32) -> Vec<DisputeEvidence> {
+    escrow_core::get_persistent(env, &EscrowKey::DisputeEvidence(escrow_id, milestone_index)).unwrap_or(Vec::new(env))
+}
+
+/// Set how many seconds after a dispute is raised evidence can be submitted
+pub fn set_evidence_window(env: &Env, seconds: u64, caller: Address) -> Result<(), Error> {
+    admin::require_role(env, &caller, Role::Moderator)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(&ConfigKey::EvidenceWindow, &seconds);
+    Ok(())
+}
+
+/// Get the evidence window applied to disputes
+pub fn get_evidence_window(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&ConfigKey::EvidenceWindow)
+        .unwrap_or(DEFAULT_EVIDENCE_WINDOW)
+}
+
+/// Appoint the next arbiter from the platform pool, round-robin, to a disputed milestone
+/// whose escrow has no arbiter panel, so the dispute has someone to rule on it
+pub fn appoint_platform_arbiter(env: &Env, escrow_id: u32, milestone_index: u32, party: Address) -> Result<Address, Error> {
+    party.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    if escrow.depositor != party && escrow.beneficiary.as_ref() != Some(&party) {
+        return Err(Error::from_contract_error(DeCentPayError::Unauthorized as u32));
+    }
+    if escrow.status != EscrowStatus::Disputed {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let milestone = work_lifecycle::get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    if milestone.status != MilestoneStatus::Disputed {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidMilestone as u32));
+    }
+    if !work_lifecycle::get_milestone_arbiters(env, escrow_id, milestone_index)?.arbiters.is_empty()
+        || get_appointed_arbiter(env, escrow_id, milestone_index).is_some()
+    {
+        return Err(Error::from_contract_error(DeCentPayError::ArbiterAlreadyAppointed as u32));
+    }
+
+    let arbiter = draw_pool_arbiter(env, &escrow)?;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::AppointedArbiter(escrow_id, milestone_index), &arbiter);
+    Ok(arbiter)
+}
+
+/// Draw the next arbiter from the platform pool, round-robin, skipping the escrow's parties
+pub fn draw_pool_arbiter(env: &Env, escrow: &EscrowData) -> Result<Address, Error> {
+    // Walk the pool from the cursor, skipping the parties themselves
+    let pool = admin::get_arbiter_pool(env);
+    let cursor: u32 = env.storage().instance().get(&ConfigKey::NextPoolArbiter).unwrap_or(0);
+    let (offset, arbiter) = (0..pool.len())
+        .filter_map(|offset| {
+            pool.get((cursor + offset) % pool.len())
+                .map(|candidate| (offset, candidate))
+        })
+        .find(|(_, candidate)| *candidate != escrow.depositor && escrow.beneficiary.as_ref() != Some(candidate))
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoArbiterPool as u32))?;
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&ConfigKey::NextPoolArbiter, &((cursor + offset + 1) % pool.len()));
+    Ok(arbiter)
+}
+
+/// Get the platform arbiter appointed to a disputed milestone
+pub fn get_appointed_arbiter(env: &Env, escrow_id: u32, milestone_index: u32) -> Option<Address> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::AppointedArbiter(escrow_id, milestone_index))
+}
+
+/// Get the arbiter votes cast so far on a disputed milestone
+pub fn get_dispute_votes(env: &Env, escrow_id: u32, milestone_index: u32) -> Vec<ArbiterVote> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::DisputeVotes(escrow_id, milestone_index))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Amount held up front for an arbitration fee on an escrow of `total_amount`
+pub fn arbitration_hold(total_amount: i128, fee: &ArbitrationFee) -> i128 {
+    match fee {
+        ArbitrationFee::None => 0,
+        ArbitrationFee::BasisPoints(fee_bp) => total_amount * i128::from(*fee_bp) / 10000,
+        ArbitrationFee::Flat(amount) => *amount,
+    }
+}
+
+/// Hold the fee paid to arbiters who vote on a dispute from the depositor of a newly created
+/// escrow until it closes
+pub fn hold_arbitration_fee(env: &Env, escrow_id: u32, escrow: &EscrowData, fee: ArbitrationFee) -> Result<(), Error> {
+    match fee {
+        ArbitrationFee::None => return Ok(()),
+        ArbitrationFee::BasisPoints(fee_bp) if fee_bp > 10000 => {
+            return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+        }
+        ArbitrationFee::Flat(amount) if amount < 0 => {
+            return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+        }
+        _ => {}
+    }
+
+    let held = arbitration_hold(escrow.total_amount, &fee);
+    if held > 0 {
+        escrow_core::transfer(env, escrow.token.as_ref(), &escrow.depositor, &env.current_contract_address(), held);
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), held)?;
+    }
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ArbitrationDeposit(escrow_id), &ArbitrationDeposit { fee, held });
+    Ok(())
+}
+
+/// Get the arbitration fee set on an escrow and how much of it is still held
+pub fn get_arbitration_deposit(env: &Env, escrow_id: u32) -> Option<ArbitrationDeposit> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::ArbitrationDeposit(escrow_id))
+}
+
+/// Split the fee for a resolved dispute evenly among the arbiters who voted on it
+fn pay_arbitration_fee(env: &Env, escrow_id: u32, escrow: &EscrowData, disputed_amount: i128, votes: &Vec<ArbiterVote>) -> Result<(), Error> {
+    let Some(mut deposit) = get_arbitration_deposit(env, escrow_id) else {
+        return Ok(());
+    };
+
+    let fee = match deposit.fee {
+        ArbitrationFee::BasisPoints(_) => arbitration_hold(disputed_amount, &deposit.fee),
+        _ => deposit.held,
+    }
+    .min(deposit.held);
+    let voter_count = i128::from(votes.len());
+    if fee <= 0 || voter_count == 0 {
+        return Ok(());
+    }
+
+    deposit.held -= fee;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ArbitrationDeposit(escrow_id), &deposit);
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -fee)?;
+
+    let share = fee / voter_count;
+    let mut paid = 0;
+    for (position, vote) in votes.iter().enumerate() {
+        // The last voter takes the rounding remainder
+        let amount = if i128::try_from(position).unwrap_or(i128::MAX) + 1 == voter_count {
+            fee - paid
+        } else {
+            share
+        };
+        if amount > 0 {
+            escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &vote.arbiter, amount);
+        }
+        paid += amount;
+    }
+    Ok(())
+}
+
+/// Return whatever arbitration fee is still held to the depositor once the escrow closes
+pub fn refund_arbitration_fee(env: &Env, escrow_id: u32, escrow: &EscrowData) -> Result<(), Error> {
+    let Some(deposit) = get_arbitration_deposit(env, escrow_id) else {
+        return Ok(());
+    };
+
+    env.storage()
+        .instance()
+        .remove(&EscrowKey::ArbitrationDeposit(escrow_id));
+    if deposit.held > 0 {
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -deposit.held)?;
+        let destination = refund_system::refund_destination(env, escrow_id, escrow);
+        escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &destination, deposit.held);
+    }
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use crate::credentials;
+use crate::dispute_resolution;
+use crate::escrow_core;
+use crate::marketplace;
+use crate::ratings;
+use crate::refund_system;
+use crate::reputation;
+use crate::work_lifecycle;
+use crate::storage_types::{
+    ArbiterVote, DisputeRuling, EscrowKey, Escalation, EscrowData, EscrowStatus, MilestoneStatus, SlashReason, DeCentPayError, DAY_IN_SECONDS,
+    INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error, Vec};
+
+const ESCALATION_STAKE_BP: i128 = 2000; // 20% of the disputed milestone
+const ESCALATION_RESPONSE_WINDOW: u64 = 3 * DAY_IN_SECONDS;
+
+/// Escalate a disputed milestone by depositing a stake the other side must match
+pub fn escalate_dispute(env: &Env, escrow_id: u32, milestone_index: u32, party: Address) -> Result<(), Error> {
+    party.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != party && escrow.beneficiary.as_ref() != Some(&party) {
+        return Err(Error::from_contract_error(DeCentPayError::Unauthorized as u32));
+    }
+
+    if escrow.status != EscrowStatus::Disputed {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let escalation = match get_escalation(env, escrow_id) {
+        None => {
+            let milestone = work_lifecycle::get_milestone(env, escrow_id, milestone_index)
+                .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+            if milestone.status != MilestoneStatus::Disputed {
+                return Err(Error::from_contract_error(DeCentPayError::InvalidMilestone as u32));
+            }
+            Escalation {
+                milestone_index,
+                initiator: party.clone(),
+                stake: milestone.amount * ESCALATION_STAKE_BP / 10000,
+                respond_by: env.ledger().timestamp() + ESCALATION_RESPONSE_WINDOW,
+                matched: false,
+            }
+        }
+        Some(mut escalation) => {
+            if escalation.matched || escalation.initiator == party || escalation.milestone_index != milestone_index {
+                return Err(Error::from_contract_error(DeCentPayError::AlreadyEscalated as u32));
+            }
+            if env.ledger().timestamp() > escalation.respond_by {
+                return Err(Error::from_contract_error(DeCentPayError::ResponseWindowClosed as u32));
+            }
+            escalation.matched = true;
+            escalation
+        }
+    };
+
+    if escalation.stake > 0 {
+        escrow_core::transfer(env, escrow.token.as_ref(), &party, &env.current_contract_address(), escalation.stake);
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), escalation.stake)?;
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::Escalation(escrow_id), &escalation);
+    Ok(())
+}
+
+/// Get the escalation open on an escrow, if any
+pub fn get_escalation(env: &Env, escrow_id: u32) -> Option<Escalation> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::Escalation(escrow_id))
+}
+
+/// Vote on the final ruling of a matched escalation. Only the panel appointed to the disputed
+/// milestone can rule, and the pot moves once its confirmation threshold agrees.
+pub fn rule_escalation(env: &Env, escrow_id: u32, arbiter: Address, beneficiary_wins: bool) -> Result<(), Error> {
+    arbiter.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escalation = get_escalation(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NotEscalated as u32))?;
+    if !escalation.matched {
+        return Err(Error::from_contract_error(DeCentPayError::EscalationNotMatched as u32));
+    }
+
+    let (panel, required) = dispute_resolution::ruling_panel(env, escrow_id, escalation.milestone_index)?;
+    if !panel.contains(&arbiter) {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyArbiter as u32));
+    }
+
+    let mut votes = get_escalation_votes(env, escrow_id);
+    if votes.iter().any(|vote| vote.arbiter == arbiter) {
+        return Err(Error::from_contract_error(DeCentPayError::AlreadyVoted as u32));
+    }
+    let ruling = if beneficiary_wins { DisputeRuling::Release } else { DisputeRuling::Refund };
+    votes.push_back(ArbiterVote {
+        arbiter,
+        ruling: ruling.clone(),
+    });
+
+    let agreeing = votes.iter().filter(|vote| vote.ruling == ruling).count();
+    if u32::try_from(agreeing).unwrap_or(u32::MAX) < required {
+        env.storage()
+            .instance()
+            .set(&EscrowKey::EscalationVotes(escrow_id), &votes);
+        return Ok(());
+    }
+
+    settle_escalation(env, escrow_id, &escalation, beneficiary_wins)?;
+    for vote in votes.iter().filter(|vote| vote.ruling == ruling) {
+        credentials::on_dispute_arbitrated(env, &vote.arbiter, escrow_id);
+    }
+    Ok(())
+}
+
+/// Get the panel votes cast so far on a matched escalation
+pub fn get_escalation_votes(env: &Env, escrow_id: u32) -> Vec<ArbiterVote> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::EscalationVotes(escrow_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Award the escalation to its initiator when the other side failed to match in time
+pub fn claim_escalation_default(env: &Env, escrow_id: u32) -> Result<(), Error> {
+    let escalation = get_escalation(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NotEscalated as u32))?;
+    if escalation.matched {
+        return Err(Error::from_contract_error(DeCentPayError::AlreadyEscalated as u32));
+    }
+    if env.ledger().timestamp() <= escalation.respond_by {
+        return Err(Error::from_contract_error(DeCentPayError::ResponseWindowOpen as u32));
+    }
+
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let beneficiary_wins = escrow.beneficiary.as_ref() == Some(&escalation.initiator);
+    settle_escalation(env, escrow_id, &escalation, beneficiary_wins)
+}
+
+/// Pay the pot to the winner, settle the milestone in their favour and record the loss
+fn settle_escalation(env: &Env, escrow_id: u32, escalation: &Escalation, beneficiary_wins: bool) -> Result<(), Error> {
+    let escrow: EscrowData = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let beneficiary = escrow
+        .beneficiary
+        .clone()
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotActive as u32))?;
+    let milestone = work_lifecycle::get_milestone(env, escrow_id, escalation.milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+
+    env.storage().instance().remove(&EscrowKey::Escalation(escrow_id));
+    env.storage().instance().remove(&EscrowKey::EscalationVotes(escrow_id));
+
+    let winner = if beneficiary_wins {
+        beneficiary.clone()
+    } else {
+        refund_system::refund_destination(env, escrow_id, &escrow)
+    };
+    let pot = if escalation.matched { escalation.stake * 2 } else { escalation.stake };
+    if pot > 0 {
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -pot)?;
+        escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &winner, pot);
+    }
+
+    if beneficiary_wins {
+        ratings::record_client_outcome(env, &escrow.depositor, |record| record.disputes_lost += 1);
+        reputation::slash(env, escrow_id, &escrow.depositor, SlashReason::DisputeLost);
+        work_lifecycle::resolve_disputed_milestone(env, escrow_id, escalation.milestone_index, milestone.amount)
+    } else {
+        ratings::record_adverse_outcome(env, &beneficiary, |record| record.disputes_lost += 1);
+        reputation::slash(env, escrow_id, &beneficiary, SlashReason::DisputeLost);
+        marketplace::burn_reputation_stake(env, escrow_id, &beneficiary);
+        work_lifecycle::resolve_disputed_milestone(env, escrow_id, escalation.milestone_index, 0)
+    }
+}
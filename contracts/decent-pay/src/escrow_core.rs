@@ -1,8 +1,19 @@
 use crate::admin;
+use crate::bonds;
+use crate::dispute_resolution;
+use crate::leaderboard;
+use crate::referrals;
+use crate::retainage;
 use crate::storage_types::{
-    DataKey, EscrowData, DeCentPayError, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+    AccountKey, ConfigKey, DataKey, EscrowData, EscrowKey, EscrowOptions, EscrowQuote, EscrowStatus, DeCentPayError, FeeQuote, ListingIndex, TokenInfo,
+    INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD,
 };
-use soroban_sdk::{Address, Env, Vec, Error};
+use soroban_sdk::{token, Address, Env, IntoVal, String, TryFromVal, Val, Vec, Error};
+
+// Native XLM Stellar Asset Contract (SAC) address for testnet, used until the owner sets one
+const NATIVE_TOKEN_ADDRESS: &str = "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC";
+const NATIVE_TOKEN_DECIMALS: u32 = 7;
+pub const INDEX_PAGE_SIZE: u32 = 100; // Escrow ids per listing index page
 
 // Helper functions for escrow operations
 #[allow(dead_code)]
@@ -38,10 +49,78 @@ pub fn save_escrow(env: &Env, escrow_id: u32, escrow_data: &EscrowData) {
     env.storage()
         .instance()
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    let previous = get_escrow(env, escrow_id);
     env.storage()
         .instance()
         .set(&DataKey::Escrow(escrow_id), escrow_data);
+
+    // Keep the listing indexes in step with the escrow's status
+    let previous_status = previous.as_ref().map(|escrow| escrow.status.clone());
+    if previous_status.as_ref() != Some(&escrow_data.status) {
+        if let Some(status) = previous_status {
+            update_index(env, &ListingIndex::Status(status), escrow_id, false);
+        }
+        update_index(env, &ListingIndex::Status(escrow_data.status.clone()), escrow_id, true);
+    }
+    let was_open = previous.as_ref().is_some_and(is_listed_open_job);
+    if was_open != is_listed_open_job(escrow_data) {
+        update_index(env, &ListingIndex::OpenJobs, escrow_id, !was_open);
+    }
+}
+
+pub fn is_listed_open_job(escrow: &EscrowData) -> bool {
+    escrow.is_open_job && escrow.status == EscrowStatus::Pending
+}
+
+/// Add or remove an escrow id in a listing index, keeping its page sorted
+pub fn update_index(env: &Env, index: &ListingIndex, escrow_id: u32, present: bool) {
+    let key = ConfigKey::IndexPage(index.clone(), escrow_id / INDEX_PAGE_SIZE);
+    let mut page: Vec<u32> = get_persistent(env, &key).unwrap_or(Vec::new(env));
+    match (page.binary_search(escrow_id), present) {
+        (Err(position), true) => page.insert(position, escrow_id),
+        (Ok(position), false) => {
+            page.remove(position);
+        }
+        _ => return,
+    }
+    if page.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        set_persistent(env, &key, &page);
     }
+}
+
+/// Get up to `limit` escrow ids in a listing index from `start_id` upwards, ascending
+pub fn get_index(env: &Env, index: &ListingIndex, start_id: u32, limit: u32) -> Vec<u32> {
+    let mut ids = Vec::new(env);
+    for page_number in start_id / INDEX_PAGE_SIZE..=get_next_escrow_id(env) / INDEX_PAGE_SIZE {
+        let page: Vec<u32> = get_persistent(env, &ConfigKey::IndexPage(index.clone(), page_number)).unwrap_or(Vec::new(env));
+        for escrow_id in page.iter().filter(|escrow_id| *escrow_id >= start_id) {
+            if ids.len() >= limit {
+                return ids;
+            }
+            ids.push_back(escrow_id);
+        }
+    }
+    ids
+}
+
+/// Read a log or index entry from persistent storage, extending its TTL when present
+pub fn get_persistent<K: IntoVal<Env, Val>, V: TryFromVal<Env, Val>>(env: &Env, key: &K) -> Option<V> {
+    let value = env.storage().persistent().get(key)?;
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    Some(value)
+}
+
+/// Write a log or index entry to persistent storage, each under its own key and TTL
+pub fn set_persistent<K: IntoVal<Env, Val>, V: IntoVal<Env, Val>>(env: &Env, key: &K, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
 
 pub fn get_reputation(env: &Env, user: Address) -> u32 {
     env.storage()
@@ -53,6 +132,16 @@ pub fn get_reputation(env: &Env, user: Address) -> u32 {
         .unwrap_or(0)
     }
 
+pub fn set_reputation(env: &Env, user: Address, reputation: u32) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::Reputation(user.clone()), &reputation);
+    leaderboard::record_reputation(env, &user, reputation);
+}
+
 pub fn get_escrow(env: &Env, escrow_id: u32) -> Option<EscrowData> {
     env.storage()
         .instance()
@@ -67,6 +156,22 @@ pub fn require_valid_escrow(env: &Env, escrow_id: u32) -> Result<(), Error> {
     Ok(())
 }
 
+/// Check an escrow exists and may change state: the contract is not paused and the escrow not frozen
+pub fn require_mutable_escrow(env: &Env, escrow_id: u32) -> Result<(), Error> {
+    require_valid_escrow(env, escrow_id)?;
+    admin::require_not_paused(env)?;
+    require_not_frozen(env, escrow_id)
+}
+
+/// Refunds stay open while the contract is paused, but not on frozen escrows or mid-migration
+pub fn require_not_frozen(env: &Env, escrow_id: u32) -> Result<(), Error> {
+    admin::require_migrated(env)?;
+    if admin::is_escrow_frozen(env, escrow_id) {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowFrozen as u32));
+    }
+    Ok(())
+}
+
 pub fn add_user_escrow(env: &Env, user: Address, escrow_id: u32) {
     env.storage()
         .instance()
@@ -84,6 +189,20 @@ pub fn add_user_escrow(env: &Env, user: Address, escrow_id: u32) {
         .set(&DataKey::UserEscrows(user), &escrows);
     }
 
+/// Record an escrow in one of a user's per-role indexes, keeping it ascending
+pub fn add_role_escrow(env: &Env, key: &AccountKey, escrow_id: u32) {
+    let mut escrows = get_role_escrows(env, key);
+    if let Err(position) = escrows.binary_search(escrow_id) {
+        escrows.insert(position, escrow_id);
+        env.storage().instance().set(key, &escrows);
+    }
+}
+
+/// Get the escrow ids in one of a user's per-role indexes, ascending
+pub fn get_role_escrows(env: &Env, key: &AccountKey) -> Vec<u32> {
+    env.storage().instance().get(key).unwrap_or(Vec::new(env))
+}
+
 pub fn get_user_escrows(env: &Env, user: Address) -> Vec<u32> {
     env.storage()
         .instance()
@@ -94,14 +213,94 @@ pub fn get_user_escrows(env: &Env, user: Address) -> Vec<u32> {
         .unwrap_or(Vec::new(&env))
     }
 
-pub fn calculate_fee(env: &Env, amount: i128) -> i128 {
-    let fee_bp = admin::get_platform_fee_bp(env);
+/// Platform fee a depositor pays on an escrow of `amount`; exempt depositors pay none
+pub fn calculate_fee(env: &Env, amount: i128, token: Option<&Address>, depositor: &Address) -> i128 {
+    if admin::is_fee_exempt(env, depositor) {
+        return 0;
+    }
+    scheduled_fee(env, amount, token)
+}
+
+/// Platform fee on an escrow of `amount`, after per-token and volume-tier overrides
+fn scheduled_fee(env: &Env, amount: i128, token: Option<&Address>) -> i128 {
+    let fee_bp = admin::get_fee_bp(env, token, amount);
     if fee_bp == 0 {
         return 0;
     }
     (amount * fee_bp as i128) / 10000
     }
 
+/// Quote the fees and net payout for a prospective escrow of `amount`
+pub fn quote_fees(env: &Env, amount: i128, token: Option<Address>, escrow_options: EscrowOptions) -> FeeQuote {
+    let platform_fee = scheduled_fee(env, amount, token.as_ref());
+    let arbiter_fee = dispute_resolution::arbitration_hold(amount, &escrow_options.arbitration_fee);
+    let discount = match &escrow_options.depositor {
+        Some(depositor) if admin::is_fee_exempt(env, depositor) => platform_fee,
+        _ => 0,
+    };
+    // Arbiters are paid from the depositor's hold, so only retainage comes out of the beneficiary's payouts
+    let retainage = retainage::retained_share(amount, escrow_options.retainage_bp);
+    let (posting_fee, seriousness_deposit) = if escrow_options.is_open_job {
+        (admin::get_posting_fee(env, token.clone()), bonds::get_seriousness_deposit(env, token))
+    } else {
+        (0, 0)
+    };
+
+    FeeQuote {
+        amount,
+        platform_fee,
+        arbiter_fee,
+        discount,
+        posting_fee,
+        seriousness_deposit,
+        depositor_total: amount + platform_fee - discount + posting_fee + seriousness_deposit + arbiter_fee,
+        retainage,
+        beneficiary_net: amount - retainage,
+    }
+}
+
+/// Check an escrow of `total_amount` splits into positive `milestones` that add up to it
+pub fn validate_milestones(total_amount: i128, milestones: &Vec<i128>) -> Result<(), Error> {
+    if total_amount <= 0 || milestones.iter().any(|amount| amount <= 0) {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    if milestones.iter().sum::<i128>() != total_amount {
+        return Err(Error::from_contract_error(DeCentPayError::MilestoneTotalMismatch as u32));
+    }
+    Ok(())
+}
+
+/// Dry-run a funded escrow of `total_amount` split into `milestones`, withholding retainage from
+/// each payout the way releasing it would
+pub fn quote_escrow(
+    env: &Env,
+    total_amount: i128,
+    token: Option<Address>,
+    milestones: Vec<i128>,
+    escrow_options: EscrowOptions,
+) -> Result<EscrowQuote, Error> {
+    validate_milestones(total_amount, &milestones)?;
+
+    let retainage_bp = escrow_options.retainage_bp;
+    let fees = quote_fees(env, total_amount, token, escrow_options);
+    let mut milestone_payouts = Vec::new(env);
+    let mut retainage = 0;
+    for amount in milestones.iter() {
+        let retained = retainage::retained_share(amount, retainage_bp);
+        retainage += retained;
+        milestone_payouts.push_back(amount - retained);
+    }
+
+    Ok(EscrowQuote {
+        platform_fee: fees.platform_fee,
+        milestone_payouts,
+        retainage,
+        beneficiary_total: total_amount - retainage,
+        depositor_total: fees.depositor_total,
+    })
+}
+
 #[allow(dead_code)]
 pub fn is_authorized_arbiter(env: &Env, arbiter: Address) -> bool {
     env.storage()
@@ -126,3 +325,225 @@ pub fn is_whitelisted_token(env: &Env, token: Option<Address>) -> bool {
         .unwrap_or(false)
 }
 
+/// Record a whitelisted token's symbol and decimals from its contract
+pub fn snapshot_token_info(env: &Env, token: &Address) {
+    let token_client = token::Client::new(env, token);
+    let info = TokenInfo {
+        symbol: token_client.symbol(),
+        decimals: token_client.decimals(),
+    };
+    env.storage()
+        .instance()
+        .set(&ConfigKey::TokenInfo(token.clone()), &info);
+}
+
+/// Get the symbol and decimals recorded when a token was whitelisted
+pub fn get_token_info(env: &Env, token: Address) -> Option<TokenInfo> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&ConfigKey::TokenInfo(token))
+}
+
+/// Decimals of an escrow token (native XLM when `None`), falling back to the native precision when unknown
+pub fn token_decimals(env: &Env, token: Option<&Address>) -> u32 {
+    token
+        .and_then(|token| get_token_info(env, token.clone()))
+        .map_or(NATIVE_TOKEN_DECIMALS, |info| info.decimals)
+}
+
+/// Get the native XLM Stellar Asset Contract address for this network
+pub fn get_native_token(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&ConfigKey::NativeToken)
+        .unwrap_or_else(|| Address::from_string(&String::from_str(env, NATIVE_TOKEN_ADDRESS)))
+}
+
+/// Resolve an escrow token to its contract address (the native XLM SAC when `None`)
+pub fn token_address(env: &Env, token: Option<&Address>) -> Address {
+    token.cloned().unwrap_or_else(|| get_native_token(env))
+}
+
+/// Transfer `amount` of `token` (native XLM when `None`) between two addresses
+pub fn transfer(env: &Env, token: Option<&Address>, from: &Address, to: &Address, amount: i128) {
+    let token_client = token::Client::new(env, &token_address(env, token));
+    token_client.transfer(from, to, &amount);
+}
+
+/// Adjust the escrowed amount tracked for `token` (native XLM when `None`) by `delta`, refusing
+/// to take it below zero
+pub fn update_escrowed_amount(env: &Env, token: Option<&Address>, delta: i128) -> Result<(), Error> {
+    let escrowed = get_escrowed_amount(env, token.cloned()) + delta;
+    if escrowed < 0 {
+        return Err(Error::from_contract_error(DeCentPayError::AccountingUnderflow as u32));
+    }
+
+    let token_key = token.cloned().unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .set(&DataKey::EscrowedAmount(token_key), &escrowed);
+    Ok(())
+}
+
+/// Get the total the contract holds in escrow for `token` (native XLM when `None`)
+pub fn get_escrowed_amount(env: &Env, token: Option<Address>) -> i128 {
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&DataKey::EscrowedAmount(token_key))
+        .unwrap_or(0)
+}
+
+/// Hold the escrow's platform fee from the depositor until work starts, settling any difference
+/// from what is already held when the fee changes before then. `collect` is false when the fee
+/// was already paid in alongside the deposit.
+pub fn hold_platform_fee(env: &Env, escrow_id: u32, escrow: &EscrowData, collect: bool) -> Result<(), Error> {
+    let held = get_platform_fee_held(env, escrow_id);
+    let difference = escrow.platform_fee - held;
+    if !collect {
+        // Already in the contract
+    } else if difference > 0 {
+        transfer(env, escrow.token.as_ref(), &escrow.depositor, &env.current_contract_address(), difference);
+    } else if difference < 0 {
+        transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &escrow.depositor, -difference);
+    }
+    update_escrowed_amount(env, escrow.token.as_ref(), difference)?;
+
+    if escrow.platform_fee > 0 {
+        env.storage()
+            .instance()
+            .set(&EscrowKey::PlatformFeeHeld(escrow_id), &escrow.platform_fee);
+    } else {
+        env.storage().instance().remove(&EscrowKey::PlatformFeeHeld(escrow_id));
+    }
+    Ok(())
+}
+
+/// Collect the platform fee once work starts, less the referrer's share. Escrows holding no fee
+/// (created before fees were held) book it against the deposit as before.
+pub fn collect_platform_fee(env: &Env, escrow_id: u32, escrow: &EscrowData) -> Result<(), Error> {
+    if escrow.platform_fee <= 0 {
+        return Ok(());
+    }
+
+    let held = get_platform_fee_held(env, escrow_id);
+    if held > 0 {
+        env.storage().instance().remove(&EscrowKey::PlatformFeeHeld(escrow_id));
+        update_escrowed_amount(env, escrow.token.as_ref(), -held)?;
+    }
+
+    book_platform_fee(env, escrow_id, escrow.token.as_ref(), escrow.platform_fee);
+    Ok(())
+}
+
+/// Platform fee the depositor must pay in on top of the deposit when an escrow's total changes to
+/// `new_total`
+pub fn platform_fee_top_up(env: &Env, escrow_id: u32, escrow: &EscrowData, new_total: i128) -> i128 {
+    let fee = calculate_fee(env, new_total, escrow.token.as_ref(), &escrow.depositor);
+    if escrow.work_started {
+        (fee - escrow.platform_fee).max(0)
+    } else if holds_platform_fee(env, escrow_id, escrow) {
+        (fee - get_platform_fee_held(env, escrow_id)).max(0)
+    } else {
+        0
+    }
+}
+
+/// Reprice the platform fee after an escrow's total changed. Any increase is paid out of `prepaid`,
+/// which the depositor already paid in and which is counted as escrowed; the rest goes back to them.
+/// A fee already collected is not refunded when the total shrinks.
+pub fn reprice_platform_fee(env: &Env, escrow_id: u32, escrow: &mut EscrowData, prepaid: i128) -> Result<(), Error> {
+    let fee = calculate_fee(env, escrow.total_amount, escrow.token.as_ref(), &escrow.depositor);
+    let held = get_platform_fee_held(env, escrow_id);
+    let (charged, held_change) = if escrow.work_started {
+        let increase = (fee - escrow.platform_fee).max(0);
+        escrow.platform_fee += increase;
+        (increase, 0)
+    } else if holds_platform_fee(env, escrow_id, escrow) {
+        escrow.platform_fee = fee;
+        if fee > 0 {
+            env.storage()
+                .instance()
+                .set(&EscrowKey::PlatformFeeHeld(escrow_id), &fee);
+        } else {
+            env.storage().instance().remove(&EscrowKey::PlatformFeeHeld(escrow_id));
+        }
+        (fee - held, fee - held)
+    } else {
+        escrow.platform_fee = fee;
+        (0, 0)
+    };
+
+    let refund = prepaid - charged;
+    if refund < 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+    if escrow.work_started && charged > 0 {
+        book_platform_fee(env, escrow_id, escrow.token.as_ref(), charged);
+    }
+    update_escrowed_amount(env, escrow.token.as_ref(), held_change - prepaid)?;
+    if refund > 0 {
+        transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &escrow.depositor, refund);
+    }
+    Ok(())
+}
+
+/// Whether an escrow whose work has not started holds its platform fee on top of the deposit.
+/// Escrows created before fees were held book theirs against the deposit instead.
+fn holds_platform_fee(env: &Env, escrow_id: u32, escrow: &EscrowData) -> bool {
+    escrow.platform_fee == 0 || get_platform_fee_held(env, escrow_id) > 0
+}
+
+/// Book a collected platform fee, less the referrer's share
+fn book_platform_fee(env: &Env, escrow_id: u32, token: Option<&Address>, fee: i128) {
+    let token_key = token.cloned().unwrap_or_else(|| env.current_contract_address());
+    let platform_share = referrals::take_referral_share(env, escrow_id, &token_key, fee);
+    admin::accrue_fees(env, token_key, platform_share);
+}
+
+/// Return a platform fee that was never collected to the depositor
+pub fn refund_platform_fee(env: &Env, escrow_id: u32, escrow: &EscrowData) -> Result<(), Error> {
+    let held = get_platform_fee_held(env, escrow_id);
+    if held <= 0 {
+        return Ok(());
+    }
+
+    env.storage().instance().remove(&EscrowKey::PlatformFeeHeld(escrow_id));
+    update_escrowed_amount(env, escrow.token.as_ref(), -held)?;
+    transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &escrow.depositor, held);
+    Ok(())
+}
+
+/// Get the platform fee held for an escrow whose work has not started
+pub fn get_platform_fee_held(env: &Env, escrow_id: u32) -> i128 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::PlatformFeeHeld(escrow_id))
+        .unwrap_or(0)
+}
+
+/// Check that a depositor may post an escrow of `total_amount` under the posting gate.
+/// Either the reputation or the completed-escrow requirement is enough.
+pub fn meets_posting_gate(env: &Env, depositor: &Address, total_amount: i128) -> bool {
+    let Some(gate) = admin::get_posting_gate(env) else {
+        return true;
+    };
+    if total_amount <= gate.value_threshold {
+        return true;
+    }
+
+    let completed: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::CompletedEscrows(depositor.clone()))
+        .unwrap_or(0);
+    get_reputation(env, depositor.clone()) >= gate.min_reputation
+        || completed >= gate.min_completed_escrows
+}
@@ -1,10 +1,36 @@
 use crate::admin;
+use crate::automation;
+use crate::bonds;
+use crate::categories;
+use crate::compliance;
+use crate::crowdfunding;
+use crate::dispute_resolution;
 use crate::escrow_core;
+use crate::marketplace;
+use crate::milestone_log;
+use crate::netting;
+use crate::referrals;
+use crate::stakes;
+use crate::team;
+use crate::work_lifecycle;
 use crate::storage_types::{
-    DataKey, EscrowData, EscrowStatus, DeCentPayError, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+    AccountKey, ActivityAction, ApplicationRequirements, Badge, DataKey, EscrowCreateOptions, EscrowCreateParams, EscrowData, EscrowKey, EscrowStatus, DeCentPayError, JobCategory, Milestone,
+    MilestoneStatus, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
 };
-use soroban_sdk::{token, Address, Env, String, Vec, Error};
+use soroban_sdk::{Address, Env, String, Vec, Error};
 
+const MAX_ARBITERS: u32 = 5;
+const MAX_BATCH_ESCROWS: u32 = 20;
+
+/// How a new escrow's deposit is paid in
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Deposit {
+    Installments, // Opens in Funding and is paid in through fund_escrow
+    Transfer,     // Pulled from the depositor at creation
+    Prepaid,      // Already pulled, with its platform fee, by a batch creation
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn create_escrow(
     env: &Env,
     depositor: Address,
@@ -18,18 +44,58 @@ pub fn create_escrow(
     duration: u32,
     project_title: String,
     project_description: String,
+    deposit: Deposit,
+    options: EscrowCreateOptions,
 ) -> Result<u32, Error> {
-    // Require auth
     depositor.require_auth();
 
+    open_escrow(
+        env,
+        depositor,
+        beneficiary,
+        arbiters,
+        required_confirmations,
+        milestone_amounts,
+        milestone_descriptions,
+        token,
+        total_amount,
+        duration,
+        project_title,
+        project_description,
+        deposit,
+        options,
+    )
+}
+
+/// Create an escrow for a depositor whose authorization was already checked
+#[allow(clippy::too_many_arguments)]
+fn open_escrow(
+    env: &Env,
+    depositor: Address,
+    beneficiary: Option<Address>,
+    arbiters: Vec<Address>,
+    required_confirmations: u32,
+    milestone_amounts: Vec<i128>,
+    milestone_descriptions: Vec<String>,
+    token: Option<Address>,
+    total_amount: i128,
+    duration: u32,
+    project_title: String,
+    project_description: String,
+    deposit: Deposit,
+    options: EscrowCreateOptions,
+) -> Result<u32, Error> {
+    let funded = deposit != Deposit::Installments;
+
     // Check if job creation is paused
+    admin::require_not_paused(env)?;
     if admin::is_job_creation_paused(env) {
         return Err(Error::from_contract_error(DeCentPayError::JobCreationPaused as u32));
     }
 
     // Validate parameters
-    if duration < 3600 || duration > 31536000 {
-        // 1 hour to 365 days
+    let bounds = admin::get_duration_bounds(env);
+    if duration < bounds.min_duration || duration > bounds.max_duration {
         return Err(Error::from_contract_error(DeCentPayError::InvalidDuration as u32));
     }
 
@@ -41,64 +107,71 @@ pub fn create_escrow(
         return Err(Error::from_contract_error(DeCentPayError::TooManyMilestones as u32));
     }
 
-    if arbiters.len() > 5 {
-        return Err(Error::from_contract_error(DeCentPayError::TooManyArbiters as u32));
-    }
+    escrow_core::validate_milestones(total_amount, &milestone_amounts)?;
 
-    if required_confirmations > arbiters.len() as u32 {
-        return Err(Error::from_contract_error(DeCentPayError::InvalidConfirmations as u32));
-    }
+    validate_arbiters(&arbiters, required_confirmations)?;
 
-    // Check token whitelist
+    // Check token whitelist; a blacklisted token is refused even if it was once whitelisted
+    if token.as_ref().is_some_and(|t| admin::is_blacklisted_token(env, t)) {
+        return Err(Error::from_contract_error(DeCentPayError::TokenBlacklisted as u32));
+    }
     if !escrow_core::is_whitelisted_token(env, token.clone()) {
         return Err(Error::from_contract_error(DeCentPayError::TokenNotWhitelisted as u32));
     }
 
+    if total_amount < admin::get_value_thresholds(env, token.clone()).min_escrow_value {
+        return Err(Error::from_contract_error(DeCentPayError::BelowMinimumEscrow as u32));
+    }
+
+    // High-value postings require an established client
+    if !escrow_core::meets_posting_gate(env, &depositor, total_amount) {
+        return Err(Error::from_contract_error(DeCentPayError::InsufficientClientReputation as u32));
+    }
+
+    // Screen both parties and require KYC above the attestation threshold
+    compliance::require_not_flagged(env, &depositor)?;
+    compliance::require_kyc(env, &depositor, total_amount)?;
+    if let Some(ben) = &beneficiary {
+        compliance::require_not_flagged(env, ben)?;
+        compliance::require_kyc(env, ben, total_amount)?;
+    }
+
     // Calculate platform fee
-    let platform_fee = escrow_core::calculate_fee(env, total_amount);
+    let platform_fee = escrow_core::calculate_fee(env, total_amount, token.as_ref(), &depositor);
 
     // Calculate deadline
-    let current_ledger = env.ledger().sequence();
-    let deadline = current_ledger + (duration as u32) / 5; // Approximate conversion
+    let now = env.ledger().timestamp();
+    let deadline = now + u64::from(duration);
 
     // Get next escrow ID
     let escrow_id = escrow_core::increment_next_escrow_id(env);
 
-    // Calculate token key first (before moving token)
-    let token_key = token.as_ref().map(|t| t.clone()).unwrap_or_else(|| env.current_contract_address());
-    
-    // Transfer funds
-    if let Some(token_addr) = &token {
-        // Transfer ERC20-like token
-        let token_client = token::Client::new(env, token_addr);
-        token_client.transfer(&depositor, &env.current_contract_address(), &total_amount);
+    let is_open_job = beneficiary.is_none();
+    if funded {
+        // Netting credit owed to the depositor by this beneficiary covers part of the funding
+        let drawn = match (&beneficiary, deposit) {
+            (Some(ben), Deposit::Transfer) => netting::draw(env, token.as_ref(), &depositor, ben, total_amount)?,
+            _ => 0,
+        };
+        let funding = total_amount - drawn;
+
+        // Transfer funds, unless fully covered by netting credit or prepaid by a batch
+        if funding > 0 && deposit == Deposit::Transfer {
+            escrow_core::transfer(env, token.as_ref(), &depositor, &env.current_contract_address(), funding);
+        }
+
+        escrow_core::update_escrowed_amount(env, token.as_ref(), total_amount)?;
     } else {
-        // Transfer native XLM using Stellar Asset Contract (SAC)
-        // Native XLM SAC address for testnet
-        let native_token_str = String::from_str(env, "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC");
-        let native_token_address = Address::from_string(&native_token_str);
-        let native_token_client = token::Client::new(env, &native_token_address);
-        native_token_client.transfer(
-            &depositor,
-            &env.current_contract_address(),
-            &total_amount,
-        );
-    }
-    
-    let current_escrowed: i128 = env
-        .storage()
-        .instance()
-        .get(&DataKey::EscrowedAmount(token_key.clone()))
-        .unwrap_or(0);
-    env.storage()
-        .instance()
-        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-    env.storage()
-        .instance()
-        .set(&DataKey::EscrowedAmount(token_key), &(current_escrowed + total_amount));
+        // The deposit arrives in installments through fund_escrow
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .instance()
+            .set(&EscrowKey::FundedAmount(escrow_id), &0i128);
+    }
 
     // Create escrow data
-    let is_open_job = beneficiary.is_none();
     let escrow_data = EscrowData {
         depositor: depositor.clone(),
         beneficiary: beneficiary.clone(),
@@ -109,45 +182,231 @@ pub fn create_escrow(
         paid_amount: 0,
         platform_fee,
         deadline,
-        status: EscrowStatus::Pending,
+        status: if funded { EscrowStatus::Pending } else { EscrowStatus::Funding },
         work_started: false,
-        created_at: current_ledger,
+        created_at: now,
         milestone_count: milestone_amounts.len() as u32,
         is_open_job,
         project_title,
         project_description,
+        bonus_paid: 0,
     };
 
-    // Save escrow
+    // Save escrow and hold its platform fee until work starts. The depositor signs here, so an
+    // escrow funded in installments by others pays its fee and posting costs up front as well.
     escrow_core::save_escrow(env, escrow_id, &escrow_data);
+    escrow_core::hold_platform_fee(env, escrow_id, &escrow_data, deposit != Deposit::Prepaid)?;
+
+    // Open jobs pay the posting fee on top of the deposit
+    if is_open_job {
+        let collect = deposit != Deposit::Prepaid;
+        marketplace::collect_posting_fee(env, escrow_id, &depositor, token.as_ref(), collect)?;
+        bonds::lock_seriousness_deposit(env, escrow_id, &depositor, token.as_ref(), collect)?;
+    }
+    apply_options(env, escrow_id, &escrow_data, options)?;
 
     // Save milestones
+    save_milestones(env, escrow_id, 0, &milestone_amounts, &milestone_descriptions);
+
+    // Add to user escrows
+    escrow_core::add_user_escrow(env, depositor.clone(), escrow_id);
+    escrow_core::add_role_escrow(env, &AccountKey::DepositorEscrows(depositor.clone()), escrow_id);
+    if let Some(ben) = &beneficiary {
+        escrow_core::add_user_escrow(env, ben.clone(), escrow_id);
+        escrow_core::add_role_escrow(env, &AccountKey::BeneficiaryEscrows(ben.clone()), escrow_id);
+    }
+    milestone_log::record_activity(env, escrow_id, &depositor, ActivityAction::Created, None);
+
+    Ok(escrow_id)
+}
+
+/// Fix the optional terms of a newly created escrow
+fn apply_options(env: &Env, escrow_id: u32, escrow: &EscrowData, options: EscrowCreateOptions) -> Result<(), Error> {
+    dispute_resolution::hold_arbitration_fee(env, escrow_id, escrow, options.arbitration_fee)?;
+    if options.min_reputation > 0 || options.min_badge != Badge::Beginner {
+        let requirements = ApplicationRequirements { min_reputation: options.min_reputation, min_badge: options.min_badge };
+        marketplace::set_application_requirements(env, escrow_id, escrow, requirements)?;
+    }
+    if let Some(referrer) = options.referrer {
+        referrals::set_referrer(env, escrow_id, escrow, referrer)?;
+    }
+    if options.application_period != 0 {
+        marketplace::set_application_deadline(env, escrow_id, escrow, options.application_period)?;
+    }
+    if options.application_stake != 0 {
+        stakes::set_application_stake(env, escrow_id, escrow, options.application_stake, options.stake_grace_period)?;
+    }
+    for (milestone_index, milestone_arbiters) in options.milestone_arbiters.iter() {
+        work_lifecycle::set_milestone_arbiters(env, escrow_id, escrow, milestone_index, milestone_arbiters)?;
+    }
+    if options.review_period != 0 {
+        automation::set_review_period(env, escrow_id, options.review_period);
+    }
+    if options.category != JobCategory::Uncategorized {
+        categories::set_job_category(env, escrow_id, options.category);
+    }
+    if !options.skill_tags.is_empty() {
+        categories::set_skill_tags(env, escrow_id, options.skill_tags)?;
+    }
+    if !options.team_splits.is_empty() {
+        team::set_team_splits(env, escrow_id, escrow, options.team_splits)?;
+    }
+    Ok(())
+}
+
+/// Create several funded escrows for one depositor, pulling each token's deposits, platform fees
+/// and open-job posting costs in a single transfer
+pub fn create_escrows_batch(env: &Env, depositor: Address, batch: Vec<EscrowCreateParams>) -> Result<Vec<u32>, Error> {
+    depositor.require_auth();
+
+    if batch.is_empty() || batch.len() > MAX_BATCH_ESCROWS {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+    }
+
+    let mut token_totals: Vec<(Option<Address>, i128)> = Vec::new(env);
+    for params in batch.iter() {
+        let mut due = params.total_amount
+            + escrow_core::calculate_fee(env, params.total_amount, params.token.as_ref(), &depositor);
+        if params.beneficiary.is_none() {
+            due += admin::get_posting_fee(env, params.token.clone())
+                + bonds::get_seriousness_deposit(env, params.token.clone());
+        }
+        match token_totals.iter().position(|(token, _)| token == params.token) {
+            Some(position) => {
+                let position = u32::try_from(position).unwrap_or(u32::MAX);
+                if let Some((token, total)) = token_totals.get(position) {
+                    token_totals.set(position, (token, total + due));
+                }
+            }
+            None => token_totals.push_back((params.token.clone(), due)),
+        }
+    }
+    for (token, total) in token_totals.iter() {
+        escrow_core::transfer(env, token.as_ref(), &depositor, &env.current_contract_address(), total);
+    }
+
+    // The depositor signed once for the whole batch
+    let mut escrow_ids = Vec::new(env);
+    for params in batch.iter() {
+        escrow_ids.push_back(open_escrow_from_params(
+            env,
+            depositor.clone(),
+            params,
+            EscrowCreateOptions::new(env),
+            Deposit::Prepaid,
+        )?);
+    }
+    Ok(escrow_ids)
+}
+
+/// Create an escrow described by `params`, with the given optional terms
+pub fn create_escrow_from_params(
+    env: &Env,
+    depositor: Address,
+    params: EscrowCreateParams,
+    options: EscrowCreateOptions,
+    deposit: Deposit,
+) -> Result<u32, Error> {
+    depositor.require_auth();
+    open_escrow_from_params(env, depositor, params, options, deposit)
+}
+
+fn open_escrow_from_params(
+    env: &Env,
+    depositor: Address,
+    params: EscrowCreateParams,
+    options: EscrowCreateOptions,
+    deposit: Deposit,
+) -> Result<u32, Error> {
+    let mut milestone_amounts = Vec::new(env);
+    let mut milestone_descriptions = Vec::new(env);
+    for (amount, description) in params.milestones.iter() {
+        milestone_amounts.push_back(amount);
+        milestone_descriptions.push_back(description);
+    }
+    open_escrow(
+        env,
+        depositor,
+        params.beneficiary,
+        params.arbiters,
+        params.required_confirmations,
+        milestone_amounts,
+        milestone_descriptions,
+        params.token,
+        params.total_amount,
+        params.duration,
+        params.project_title,
+        params.project_description,
+        deposit,
+        options,
+    )
+}
+
+/// Pay an installment towards an escrow created unfunded; it opens as Pending once fully funded
+pub fn fund_escrow(env: &Env, escrow_id: u32, amount: i128, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    crowdfunding::add_contribution(env, escrow_id, amount, depositor)
+}
+
+/// Get how much of an escrow's deposit has been paid in
+pub fn get_funded_amount(env: &Env, escrow_id: u32, escrow: &EscrowData) -> i128 {
+    env.storage()
+        .instance()
+        .get(&EscrowKey::FundedAmount(escrow_id))
+        .unwrap_or(escrow.total_amount)
+}
+
+/// Save new milestones for an escrow starting at `first_index`
+pub fn save_milestones(
+    env: &Env,
+    escrow_id: u32,
+    first_index: u32,
+    milestone_amounts: &Vec<i128>,
+    milestone_descriptions: &Vec<String>,
+) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
     for (i, (amount, description)) in milestone_amounts.iter().zip(milestone_descriptions.iter()).enumerate() {
-        let milestone = crate::storage_types::Milestone {
-            description: description.clone(),
+        let milestone = Milestone {
+            description,
             amount,
-            status: crate::storage_types::MilestoneStatus::NotStarted,
+            status: MilestoneStatus::NotStarted,
             submitted_at: 0,
             approved_at: 0,
             disputed_at: 0,
             disputed_by: None,
             dispute_reason: None,
             rejection_reason: None,
+            completed_by_client: false,
+            manifest: Vec::new(env),
+            released: 0,
+            due_at: 0,
         };
+        let index = first_index + u32::try_from(i).unwrap_or(u32::MAX);
         env.storage()
             .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        env.storage()
-            .instance()
-            .set(&DataKey::Milestone(escrow_id, i as u32), &milestone);
+            .set(&DataKey::Milestone(escrow_id, index), &milestone);
     }
+}
 
-    // Add to user escrows
-    escrow_core::add_user_escrow(env, depositor.clone(), escrow_id);
-    if let Some(ben) = &beneficiary {
-        escrow_core::add_user_escrow(env, ben.clone(), escrow_id);
+/// Validate an arbiter panel and its confirmation threshold
+pub fn validate_arbiters(arbiters: &Vec<Address>, required_confirmations: u32) -> Result<(), Error> {
+    if arbiters.len() > MAX_ARBITERS {
+        return Err(Error::from_contract_error(DeCentPayError::TooManyArbiters as u32));
     }
 
-    Ok(escrow_id)
+    if required_confirmations > arbiters.len() {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidConfirmations as u32));
+    }
+    Ok(())
 }
-
@@ -0,0 +1,88 @@
+use crate::admin;
+use crate::escrow_core;
+use crate::retainage;
+use crate::storage_types::{
+    ConfigKey, EscrowKey, EscrowStatus, DeCentPayError, Role, DAY_IN_SECONDS, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error};
+
+const DEFAULT_FINALITY_WINDOW: u64 = 7 * DAY_IN_SECONDS;
+
+/// Set how many seconds a closed escrow stays open to challenge before it can be finalized
+pub fn set_finality_window(env: &Env, seconds: u64, caller: Address) -> Result<(), Error> {
+    admin::require_role(env, &caller, Role::Admin)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(&ConfigKey::FinalityWindow, &seconds);
+    Ok(())
+}
+
+/// Get the challenge window applied before escrows become final
+pub fn get_finality_window(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&ConfigKey::FinalityWindow)
+        .unwrap_or(DEFAULT_FINALITY_WINDOW)
+}
+
+/// Record the time an escrow was released or refunded, starting its challenge window
+pub fn record_closed(env: &Env, escrow_id: u32) {
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ClosedAt(escrow_id), &env.ledger().timestamp());
+}
+
+/// Mark a closed escrow final once its challenge window has passed and nothing is still held for it
+pub fn finalize_escrow(env: &Env, escrow_id: u32) -> Result<(), Error> {
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if is_final(env, escrow_id) {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowFinal as u32));
+    }
+
+    let closed_at: u64 = env
+        .storage()
+        .instance()
+        .get(&EscrowKey::ClosedAt(escrow_id))
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotClosed as u32))?;
+    if escrow.status != EscrowStatus::Released
+        && escrow.status != EscrowStatus::Refunded
+        && escrow.status != EscrowStatus::Expired
+        && escrow.status != EscrowStatus::Cancelled
+    {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotClosed as u32));
+    }
+
+    if env.ledger().timestamp() < closed_at + get_finality_window(env) || retainage::get_retainage(env, escrow_id).is_some() {
+        return Err(Error::from_contract_error(DeCentPayError::ChallengeWindowOpen as u32));
+    }
+
+    env.storage().instance().set(&EscrowKey::Final(escrow_id), &true);
+    Ok(())
+}
+
+/// Check whether an escrow has been marked final
+pub fn is_final(env: &Env, escrow_id: u32) -> bool {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::Final(escrow_id))
+        .unwrap_or(false)
+}
+
+/// Reject privileged actions on escrows that have been marked final
+pub fn require_not_final(env: &Env, escrow_id: u32) -> Result<(), Error> {
+    if is_final(env, escrow_id) {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowFinal as u32));
+    }
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use crate::admin;
+use crate::escrow_core;
+use crate::profiles;
+use crate::refund_system;
+use crate::storage_types::{
+    ConfigKey, EscrowData, EscrowKey, EscrowStatus, Role, YieldPosition, YieldRecipient, DeCentPayError,
+    INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{contractclient, token, Address, Env, Error};
+
+/// Interface expected from a lending pool adapter holding idle escrow funds
+#[allow(dead_code)]
+#[contractclient(name = "YieldAdapterClient")]
+pub trait YieldAdapterInterface {
+    /// Credit `amount` of `token`, already transferred to the adapter, to `position`
+    fn deposit(env: Env, position: u32, token: Address, amount: i128);
+    /// Redeem all of `position` to `to`, returning the amount sent
+    fn withdraw(env: Env, position: u32, token: Address, to: Address) -> i128;
+}
+
+/// Set or clear (with `None`) the adapter idle funds may be deposited with
+pub fn set_yield_adapter(env: &Env, adapter: Option<Address>, caller: Address) -> Result<(), Error> {
+    admin::require_role(env, &caller, Role::Admin)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    match adapter {
+        Some(adapter) => env.storage().instance().set(&ConfigKey::YieldAdapter, &adapter),
+        None => env.storage().instance().remove(&ConfigKey::YieldAdapter),
+    }
+    Ok(())
+}
+
+pub fn get_yield_adapter(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&ConfigKey::YieldAdapter)
+}
+
+/// Deposit up to the unpaid balance of an escrow with the yield adapter
+pub fn deposit_idle_funds(
+    env: &Env,
+    escrow_id: u32,
+    amount: i128,
+    recipient: YieldRecipient,
+    depositor: Address,
+) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::Pending && escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let adapter = get_yield_adapter(env)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::YieldAdapterNotSet as u32))?;
+
+    if get_yield_position(env, escrow_id).is_some() {
+        return Err(Error::from_contract_error(DeCentPayError::YieldPositionOpen as u32));
+    }
+
+    if amount <= 0 || amount > escrow.total_amount - escrow.paid_amount {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    if let YieldRecipient::Split(beneficiary_bp) = recipient {
+        if beneficiary_bp > 10000 {
+            return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+        }
+    }
+
+    let token = escrow_core::token_address(env, escrow.token.as_ref());
+    escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &adapter, amount);
+    YieldAdapterClient::new(env, &adapter).deposit(&escrow_id, &token, &amount);
+
+    let position = YieldPosition {
+        adapter,
+        principal: amount,
+        recipient,
+        deposited_at: env.ledger().timestamp(),
+    };
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::YieldPosition(escrow_id), &position);
+    Ok(())
+}
+
+/// Pull an escrow's funds back from the yield adapter early (depositor or beneficiary)
+pub fn withdraw_idle_funds(env: &Env, escrow_id: u32, party: Address) -> Result<(), Error> {
+    party.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != party && escrow.beneficiary.as_ref() != Some(&party) {
+        return Err(Error::from_contract_error(DeCentPayError::Unauthorized as u32));
+    }
+
+    if get_yield_position(env, escrow_id).is_none() {
+        return Err(Error::from_contract_error(DeCentPayError::NoYieldPosition as u32));
+    }
+    unwind(env, escrow_id, &escrow)
+}
+
+/// Get the funds an escrow has deposited with the yield adapter
+pub fn get_yield_position(env: &Env, escrow_id: u32) -> Option<YieldPosition> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::YieldPosition(escrow_id))
+}
+
+/// Bring an escrow's deposited funds back into the contract before anything is paid out of it.
+/// Fails unless at least the full principal actually arrives; any yield goes to the chosen recipient.
+pub fn unwind(env: &Env, escrow_id: u32, escrow: &EscrowData) -> Result<(), Error> {
+    let Some(position) = get_yield_position(env, escrow_id) else {
+        return Ok(());
+    };
+
+    let token = escrow_core::token_address(env, escrow.token.as_ref());
+    let token_client = token::Client::new(env, &token);
+    let contract = env.current_contract_address();
+    let balance_before = token_client.balance(&contract);
+    YieldAdapterClient::new(env, &position.adapter).withdraw(&escrow_id, &token, &contract);
+    let received = token_client.balance(&contract) - balance_before;
+    if received < position.principal {
+        return Err(Error::from_contract_error(DeCentPayError::YieldShortfall as u32));
+    }
+    env.storage().instance().remove(&EscrowKey::YieldPosition(escrow_id));
+
+    let earned = received - position.principal;
+    if earned == 0 {
+        return Ok(());
+    }
+
+    let depositor_share = match position.recipient {
+        YieldRecipient::Depositor => earned,
+        YieldRecipient::Split(beneficiary_bp) => {
+            let beneficiary_share = earned * i128::from(beneficiary_bp) / 10000;
+            if let (Some(beneficiary), true) = (&escrow.beneficiary, beneficiary_share > 0) {
+                let payout_addr = profiles::payout_address(env, beneficiary);
+                escrow_core::transfer(env, escrow.token.as_ref(), &contract, &payout_addr, beneficiary_share);
+                earned - beneficiary_share
+            } else {
+                earned
+            }
+        }
+        YieldRecipient::Treasury => {
+            let fee_collector = admin::get_fee_collector(env)?;
+            escrow_core::transfer(env, escrow.token.as_ref(), &contract, &fee_collector, earned);
+            0
+        }
+    };
+    if depositor_share > 0 {
+        let destination = refund_system::refund_destination(env, escrow_id, escrow);
+        escrow_core::transfer(env, escrow.token.as_ref(), &contract, &destination, depositor_share);
+    }
+    Ok(())
+}
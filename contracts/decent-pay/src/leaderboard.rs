@@ -0,0 +1,67 @@
+use crate::storage_types::{
+    ConfigKey, LeaderboardEntry, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Vec};
+
+const LEADERBOARD_SIZE: u32 = 50;
+
+/// Re-rank a user after their reputation changed, keeping only the top scores
+pub fn record_reputation(env: &Env, user: &Address, reputation: u32) {
+    let mut leaderboard = get_entries(env);
+    let mut listed = false;
+    for (index, entry) in leaderboard.iter().enumerate() {
+        if entry.user == *user {
+            leaderboard.remove(u32::try_from(index).unwrap_or(u32::MAX));
+            listed = true;
+            break;
+        }
+    }
+    if !listed
+        && leaderboard.len() >= LEADERBOARD_SIZE
+        && leaderboard
+            .last()
+            .is_some_and(|lowest| lowest.reputation >= reputation)
+    {
+        return;
+    }
+
+    if reputation > 0 {
+        // Ties keep whoever reached the score first ahead
+        let mut position = leaderboard.len();
+        for (index, entry) in leaderboard.iter().enumerate() {
+            if entry.reputation < reputation {
+                position = u32::try_from(index).unwrap_or(u32::MAX);
+                break;
+            }
+        }
+        leaderboard.insert(
+            position,
+            LeaderboardEntry {
+                user: user.clone(),
+                reputation,
+            },
+        );
+    }
+    while leaderboard.len() > LEADERBOARD_SIZE {
+        leaderboard.pop_back();
+    }
+    env.storage()
+        .instance()
+        .set(&ConfigKey::Leaderboard, &leaderboard);
+}
+
+/// Get up to `limit` of the highest-reputation users, best first
+pub fn get_leaderboard(env: &Env, limit: u32) -> Vec<LeaderboardEntry> {
+    let leaderboard = get_entries(env);
+    leaderboard.slice(0..limit.min(leaderboard.len()))
+}
+
+fn get_entries(env: &Env) -> Vec<LeaderboardEntry> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&ConfigKey::Leaderboard)
+        .unwrap_or(Vec::new(env))
+}
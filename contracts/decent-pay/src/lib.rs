@@ -1,17 +1,47 @@
 #![no_std]
 
+mod achievements;
 mod admin;
+mod automation;
+mod bonds;
+mod categories;
+mod change_orders;
+mod client_spend;
+mod compliance;
+mod cosign;
+mod credentials;
+mod crowdfunding;
+mod dispute_resolution;
+mod escalation;
 mod escrow_core;
 mod escrow_management;
+mod finality;
+mod idle_yield;
+mod leaderboard;
 mod marketplace;
+mod migrations;
+mod milestone_log;
+mod netting;
+mod profiles;
 mod ratings;
+mod referrals;
 mod refund_system;
+mod reputation;
+mod retainage;
+mod retainer;
+mod stakes;
+mod streaming;
 mod storage_types;
+mod team;
+mod test;
+mod time_and_materials;
+mod views;
 mod work_lifecycle;
 
 pub use storage_types::*;
+use escrow_management::Deposit;
 
-use soroban_sdk::{contract, contractimpl, Address, Env, String, Vec, Error};
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, String, Symbol, Vec, Error};
 
 #[contract]
 pub struct DeCentPay;
@@ -30,6 +60,7 @@ impl DeCentPay {
 
     /// Create an escrow with token
     /// Note: Milestone amounts and descriptions are combined into tuples to reduce parameter count
+    #[allow(clippy::too_many_arguments)]
     pub fn create_escrow(
         env: Env,
         depositor: Address,
@@ -65,34 +96,291 @@ impl DeCentPay {
             duration,
             project_title,
             project_description,
+            Deposit::Transfer,
+            EscrowCreateOptions::new(&env),
         )
     }
 
+    /// Create an escrow without depositing; it stays in Funding until `fund_escrow` pays in the total
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_escrow_unfunded(
+        env: Env,
+        depositor: Address,
+        beneficiary: Option<Address>,
+        arbiters: Vec<Address>,
+        required_confirmations: u32,
+        milestones: Vec<(i128, String)>,
+        token: Option<Address>,
+        total_amount: i128,
+        duration: u32,
+        project_title: String,
+        project_description: String,
+    ) -> Result<u32, Error> {
+        let mut milestone_amounts: Vec<i128> = Vec::new(&env);
+        let mut milestone_descriptions: Vec<String> = Vec::new(&env);
+        for (amount, desc) in milestones.iter() {
+            milestone_amounts.push_back(amount);
+            milestone_descriptions.push_back(desc);
+        }
+
+        escrow_management::create_escrow(
+            &env,
+            depositor,
+            beneficiary,
+            arbiters,
+            required_confirmations,
+            milestone_amounts,
+            milestone_descriptions,
+            token,
+            total_amount,
+            duration,
+            project_title,
+            project_description,
+            Deposit::Installments,
+            EscrowCreateOptions::new(&env),
+        )
+    }
+
+    /// Create a funded escrow with optional terms that are fixed from creation on
+    pub fn create_escrow_with_options(
+        env: Env,
+        depositor: Address,
+        params: EscrowCreateParams,
+        options: EscrowCreateOptions,
+    ) -> Result<u32, Error> {
+        escrow_management::create_escrow_from_params(&env, depositor, params, options, Deposit::Transfer)
+    }
+
+    /// Create several funded escrows in one transaction, pulling each token's deposits in a single transfer
+    pub fn create_escrows_batch(env: Env, depositor: Address, batch: Vec<EscrowCreateParams>) -> Result<Vec<u32>, Error> {
+        escrow_management::create_escrows_batch(&env, depositor, batch)
+    }
+
+    /// Pay an installment towards an unfunded escrow (depositor only)
+    pub fn fund_escrow(env: Env, escrow_id: u32, amount: i128, depositor: Address) -> Result<(), Error> {
+        escrow_management::fund_escrow(&env, escrow_id, amount, depositor)
+    }
+
+    /// Contribute towards the deposit of an escrow still in Funding (anyone)
+    pub fn contribute(env: Env, escrow_id: u32, amount: i128, contributor: Address) -> Result<(), Error> {
+        crowdfunding::contribute(&env, escrow_id, amount, contributor)
+    }
+
+    /// Vote as a contributor to approve a submitted milestone; paid once a funding majority agrees
+    pub fn vote_approve_milestone(env: Env, escrow_id: u32, milestone_index: u32, contributor: Address) -> Result<(), Error> {
+        crowdfunding::vote_approve_milestone(&env, escrow_id, milestone_index, contributor)
+    }
+
+    /// Get the funders of an escrow
+    pub fn get_contributors(env: Env, escrow_id: u32) -> Vec<Address> {
+        crowdfunding::get_contributors(&env, escrow_id)
+    }
+
+    /// Get how much an address has contributed to an escrow
+    pub fn get_contribution(env: Env, escrow_id: u32, contributor: Address) -> i128 {
+        crowdfunding::get_contribution(&env, escrow_id, contributor)
+    }
+
+    /// Get how much of an escrow's deposit has been paid in
+    pub fn get_funded_amount(env: Env, escrow_id: u32) -> Result<i128, Error> {
+        let escrow = escrow_core::get_escrow(&env, escrow_id)
+            .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+        Ok(escrow_management::get_funded_amount(&env, escrow_id, &escrow))
+    }
+
+    /// Create a retainer paying `amount_per_period` for each of `num_periods` periods of `period_seconds`
+    pub fn create_retainer(
+        env: Env,
+        depositor: Address,
+        beneficiary: Address,
+        token: Option<Address>,
+        amount_per_period: i128,
+        period_seconds: u32,
+        num_periods: u32,
+    ) -> Result<u32, Error> {
+        retainer::create_retainer(&env, depositor, beneficiary, token, amount_per_period, period_seconds, num_periods)
+    }
+
+    /// Claim the payment of an ended retainer period (beneficiary only)
+    pub fn claim_retainer_period(env: Env, escrow_id: u32, period_index: u32, beneficiary: Address) -> Result<(), Error> {
+        retainer::claim_retainer_period(&env, escrow_id, period_index, beneficiary)
+    }
+
+    /// Pause a retainer so no further periods unlock (depositor only)
+    pub fn pause_retainer(env: Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+        retainer::pause_retainer(&env, escrow_id, depositor)
+    }
+
+    /// Resume a paused retainer (depositor only)
+    pub fn resume_retainer(env: Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+        retainer::resume_retainer(&env, escrow_id, depositor)
+    }
+
+    /// Get the retainer terms of an escrow
+    pub fn get_retainer(env: Env, escrow_id: u32) -> Option<Retainer> {
+        retainer::get_retainer(&env, escrow_id)
+    }
+
+    /// Create a time and materials escrow billing `hourly_rate` per logged hour against a funded `cap`
+    pub fn create_time_and_materials(
+        env: Env,
+        depositor: Address,
+        beneficiary: Address,
+        token: Option<Address>,
+        hourly_rate: i128,
+        cap: i128,
+        duration: u32,
+    ) -> Result<u32, Error> {
+        time_and_materials::create_time_and_materials(&env, depositor, beneficiary, token, hourly_rate, cap, duration)
+    }
+
+    /// Log hours worked on a time and materials escrow (beneficiary only); returns the entry's milestone index
+    pub fn log_hours(env: Env, escrow_id: u32, hours: u32, description: String, beneficiary: Address) -> Result<u32, Error> {
+        time_and_materials::log_hours(&env, escrow_id, hours, description, beneficiary)
+    }
+
+    /// Approve and pay a batch of time entries (depositor only)
+    pub fn approve_time_entries(env: Env, escrow_id: u32, entry_indexes: Vec<u32>, depositor: Address) -> Result<(), Error> {
+        time_and_materials::approve_time_entries(&env, escrow_id, entry_indexes, depositor)
+    }
+
+    /// Close a time and materials escrow and refund the unbilled cap (depositor only)
+    pub fn close_time_and_materials(env: Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+        time_and_materials::close_time_and_materials(&env, escrow_id, depositor)
+    }
+
+    /// Get the billing terms of a time and materials escrow
+    pub fn get_time_and_materials(env: Env, escrow_id: u32) -> Option<TimeAndMaterials> {
+        time_and_materials::get_time_and_materials(&env, escrow_id)
+    }
+
     /// Start work on an escrow
     pub fn start_work(env: Env, escrow_id: u32, beneficiary: Address) -> Result<(), Error> {
         work_lifecycle::start_work(&env, escrow_id, beneficiary)
     }
 
-    /// Submit a milestone
+    /// Pre-approve a milestone to be released automatically at a future unix timestamp (depositor only)
+    pub fn schedule_milestone_release(
+        env: Env,
+        escrow_id: u32,
+        milestone_index: u32,
+        release_at: u64,
+        depositor: Address,
+    ) -> Result<(), Error> {
+        automation::schedule_milestone_release(&env, escrow_id, milestone_index, release_at, depositor)
+    }
+
+    /// Cancel a scheduled milestone release
+    pub fn cancel_scheduled_release(env: Env, escrow_id: u32, milestone_index: u32, depositor: Address) -> Result<(), Error> {
+        automation::cancel_scheduled_release(&env, escrow_id, milestone_index, depositor)
+    }
+
+    /// Get the unix timestamp a milestone is scheduled to be released at
+    pub fn get_scheduled_release(env: Env, escrow_id: u32, milestone_index: u32) -> Option<u64> {
+        automation::get_scheduled_release(&env, escrow_id, milestone_index)
+    }
+
+    /// Release a scheduled milestone once its release time is reached
+    pub fn execute_scheduled_release(env: Env, escrow_id: u32, milestone_index: u32) -> Result<(), Error> {
+        automation::execute_scheduled_release(&env, escrow_id, milestone_index)
+    }
+
+    /// Auto-approve submitted milestones under a size cap and total budget after a delay (depositor only)
+    pub fn grant_standing_approval(
+        env: Env,
+        escrow_id: u32,
+        max_milestone_amount: i128,
+        budget: i128,
+        delay: u32,
+        depositor: Address,
+    ) -> Result<(), Error> {
+        automation::grant_standing_approval(&env, escrow_id, max_milestone_amount, budget, delay, depositor)
+    }
+
+    /// Revoke an escrow's standing approval
+    pub fn revoke_standing_approval(env: Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+        automation::revoke_standing_approval(&env, escrow_id, depositor)
+    }
+
+    /// Get the standing approval granted on an escrow
+    pub fn get_standing_approval(env: Env, escrow_id: u32) -> Option<StandingApproval> {
+        automation::get_standing_approval(&env, escrow_id)
+    }
+
+    /// Approve a submitted milestone under the escrow's standing approval
+    pub fn auto_approve_milestone(env: Env, escrow_id: u32, milestone_index: u32) -> Result<(), Error> {
+        automation::auto_approve_milestone(&env, escrow_id, milestone_index)
+    }
+
+    /// Pay out part of a submitted milestone and send the rest back for rework (depositor only)
+    pub fn approve_milestone_partial(
+        env: Env,
+        escrow_id: u32,
+        milestone_index: u32,
+        approved_amount: i128,
+        depositor: Address,
+    ) -> Result<(), Error> {
+        work_lifecycle::approve_milestone_partial(&env, escrow_id, milestone_index, approved_amount, depositor)
+    }
+
+    /// Get an escrow's review period in seconds
+    pub fn get_review_period(env: Env, escrow_id: u32) -> Option<u64> {
+        automation::get_review_period(&env, escrow_id)
+    }
+
+    /// Pay out a submission left unreviewed past the escrow's review period
+    pub fn auto_release_milestone(env: Env, escrow_id: u32, milestone_index: u32) -> Result<(), Error> {
+        automation::auto_release_milestone(&env, escrow_id, milestone_index)
+    }
+
+    /// Submit a milestone, optionally with the content hashes of its deliverables
     pub fn submit_milestone(
         env: Env,
         escrow_id: u32,
         milestone_index: u32,
         description: String,
+        deliverable_hashes: Option<Vec<BytesN<32>>>,
+        beneficiary: Address,
+    ) -> Result<(), Error> {
+        let manifest = work_lifecycle::hash_manifest(&env, deliverable_hashes);
+        work_lifecycle::submit_milestone(&env, escrow_id, milestone_index, beneficiary, description, manifest)
+    }
+
+    /// Submit a milestone with a manifest of delivered files
+    pub fn submit_milestone_with_manifest(
+        env: Env,
+        escrow_id: u32,
+        milestone_index: u32,
+        description: String,
+        manifest: Vec<Deliverable>,
         beneficiary: Address,
     ) -> Result<(), Error> {
-        work_lifecycle::submit_milestone(&env, escrow_id, milestone_index, beneficiary, description)
+        work_lifecycle::submit_milestone(&env, escrow_id, milestone_index, beneficiary, description, manifest)
     }
 
-    /// Resubmit a rejected milestone
+    /// Resubmit a rejected milestone, optionally with the content hashes of its deliverables
     pub fn resubmit_milestone(
         env: Env,
         escrow_id: u32,
         milestone_index: u32,
         description: String,
+        deliverable_hashes: Option<Vec<BytesN<32>>>,
+        beneficiary: Address,
+    ) -> Result<(), Error> {
+        let manifest = work_lifecycle::hash_manifest(&env, deliverable_hashes);
+        work_lifecycle::resubmit_milestone(&env, escrow_id, milestone_index, beneficiary, description, manifest)
+    }
+
+    /// Resubmit a rejected milestone with a manifest of delivered files
+    pub fn resubmit_milestone_with_manifest(
+        env: Env,
+        escrow_id: u32,
+        milestone_index: u32,
+        description: String,
+        manifest: Vec<Deliverable>,
         beneficiary: Address,
     ) -> Result<(), Error> {
-        work_lifecycle::resubmit_milestone(&env, escrow_id, milestone_index, beneficiary, description)
+        work_lifecycle::resubmit_milestone(&env, escrow_id, milestone_index, beneficiary, description, manifest)
     }
 
     /// Approve a milestone
@@ -100,6 +388,11 @@ impl DeCentPay {
         work_lifecycle::approve_milestone(&env, escrow_id, milestone_index, depositor)
     }
 
+    /// Approve and pay a milestone that was delivered without a submission (depositor only)
+    pub fn mark_milestone_complete(env: Env, escrow_id: u32, milestone_index: u32, depositor: Address) -> Result<(), Error> {
+        work_lifecycle::mark_milestone_complete(&env, escrow_id, milestone_index, depositor)
+    }
+
     /// Reject a milestone
     pub fn reject_milestone(
         env: Env,
@@ -138,143 +431,1240 @@ impl DeCentPay {
         marketplace::accept_freelancer(&env, escrow_id, depositor, freelancer)
     }
 
-    /// Refund an escrow
-    pub fn refund_escrow(env: Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
-        refund_system::refund_escrow(&env, escrow_id, depositor)
+    /// Require the freelancer to lock a performance bond (basis points of escrow value) when work starts
+    pub fn require_performance_bond(env: Env, escrow_id: u32, bond_bp: u32, depositor: Address) -> Result<(), Error> {
+        bonds::require_performance_bond(&env, escrow_id, bond_bp, depositor)
     }
 
-    /// Emergency refund after deadline
-    pub fn emergency_refund_after_deadline(env: Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
-        refund_system::emergency_refund_after_deadline(&env, escrow_id, depositor)
+    /// Get the performance bond configured for an escrow
+    pub fn get_performance_bond(env: Env, escrow_id: u32) -> Option<PerformanceBond> {
+        bonds::get_performance_bond(&env, escrow_id)
     }
 
-    /// Extend deadline
-    pub fn extend_deadline(env: Env, escrow_id: u32, extra_seconds: u32, depositor: Address) -> Result<(), Error> {
-        refund_system::extend_deadline(&env, escrow_id, depositor, extra_seconds)
+    /// Get the stake an open job requires from applicants
+    pub fn get_application_stake(env: Env, escrow_id: u32) -> Option<ApplicationStake> {
+        stakes::get_application_stake(&env, escrow_id)
     }
 
-    // View functions
-    pub fn get_escrow(env: Env, escrow_id: u32) -> Option<EscrowData> {
-        escrow_core::get_escrow(&env, escrow_id)
+    /// Get the application stake a freelancer has locked on a job
+    pub fn get_locked_stake(env: Env, escrow_id: u32, freelancer: Address) -> i128 {
+        stakes::get_locked_stake(&env, escrow_id, freelancer)
     }
 
-    pub fn get_user_escrows(env: Env, user: Address) -> Vec<u32> {
-        escrow_core::get_user_escrows(&env, user)
+    /// Claim the hired freelancer's application stake after they let the grace period pass without
+    /// starting work (depositor only)
+    pub fn slash_application_stake(env: Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+        stakes::slash_application_stake(&env, escrow_id, depositor)
     }
 
-    pub fn get_reputation(env: Env, user: Address) -> u32 {
-        escrow_core::get_reputation(&env, user)
+    /// Forfeit a locked performance bond to the depositor or return it to the beneficiary (arbiter only)
+    pub fn settle_performance_bond(env: Env, escrow_id: u32, arbiter: Address, forfeit: bool) -> Result<(), Error> {
+        bonds::settle_performance_bond(&env, escrow_id, arbiter, forfeit)
     }
 
-    // Admin functions
-    pub fn set_platform_fee_bp(env: Env, fee_bp: u32) -> Result<(), Error> {
-        admin::set_platform_fee_bp(&env, fee_bp)
+    /// Forfeit the bond of an abandoned, emergency-refunded escrow that arbiters left unsettled for
+    /// 30 days (anyone)
+    pub fn forfeit_unsettled_bond(env: Env, escrow_id: u32) -> Result<(), Error> {
+        bonds::forfeit_unsettled_bond(&env, escrow_id)
     }
 
-    pub fn set_fee_collector(env: Env, fee_collector: Address) -> Result<(), Error> {
-        admin::set_fee_collector(&env, fee_collector)
+    /// Set the smallest escrow value of a token that may require a performance bond (owner or admin)
+    pub fn set_bond_threshold(env: Env, token: Option<Address>, threshold: i128, caller: Address) -> Result<(), Error> {
+        bonds::set_bond_threshold(&env, token, threshold, caller)
     }
 
-    pub fn set_owner(env: Env, new_owner: Address) -> Result<(), Error> {
-        admin::set_owner(&env, new_owner)
+    /// Get the smallest escrow value of a token that may require a performance bond
+    pub fn get_bond_threshold(env: Env, token: Option<Address>) -> i128 {
+        bonds::get_bond_threshold(&env, token)
     }
 
-    pub fn whitelist_token(env: Env, token: Address) -> Result<(), Error> {
-        admin::require_owner(&env)?;
-        env.storage()
-            .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        env.storage()
-            .instance()
-            .set(&DataKey::WhitelistedToken(token.clone()), &true);
-        Ok(())
+    /// Withhold a share of every payout until a warranty period after the escrow closes (depositor only, before work starts)
+    pub fn set_retainage(
+        env: Env,
+        escrow_id: u32,
+        retainage_bp: u32,
+        warranty_period: u32,
+        depositor: Address,
+    ) -> Result<(), Error> {
+        retainage::set_retainage(&env, escrow_id, retainage_bp, warranty_period, depositor)
     }
 
-    pub fn authorize_arbiter(env: Env, arbiter: Address) -> Result<(), Error> {
-        admin::require_owner(&env)?;
-        env.storage()
-            .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        env.storage()
-            .instance()
-            .set(&DataKey::AuthorizedArbiter(arbiter.clone()), &true);
-        Ok(())
+    /// Stream approved milestone payouts to the beneficiary over `duration` seconds (depositor only, before work starts)
+    pub fn set_streaming(env: Env, escrow_id: u32, duration: u32, depositor: Address) -> Result<(), Error> {
+        streaming::set_streaming(&env, escrow_id, duration, depositor)
     }
 
-    /// Pause job creation
-    pub fn pause_job_creation(env: Env) -> Result<(), Error> {
-        admin::set_job_creation_paused(&env, true)
+    /// Get the number of seconds approved payouts of an escrow vest over
+    pub fn get_streaming_duration(env: Env, escrow_id: u32) -> Option<u64> {
+        streaming::get_streaming_duration(&env, escrow_id)
     }
 
-    /// Unpause job creation
-    pub fn unpause_job_creation(env: Env) -> Result<(), Error> {
-        admin::set_job_creation_paused(&env, false)
+    /// Get the vesting stream of an approved milestone
+    pub fn get_vesting_stream(env: Env, escrow_id: u32, milestone_index: u32) -> Option<VestingStream> {
+        streaming::get_vesting_stream(&env, escrow_id, milestone_index)
     }
 
-    /// Check if job creation is paused
-    pub fn is_job_creation_paused(env: Env) -> bool {
-        admin::is_job_creation_paused(&env)
+    /// Withdraw the vested part of a streamed milestone payout (beneficiary only)
+    pub fn claim_vested(env: Env, escrow_id: u32, milestone_index: u32, beneficiary: Address) -> Result<i128, Error> {
+        streaming::claim_vested(&env, escrow_id, milestone_index, beneficiary)
     }
 
-    /// Get the contract owner
-    pub fn get_owner(env: Env) -> Result<Address, Error> {
-        admin::get_owner(&env)
+    /// Require an arbiter co-signature on milestone approvals above `threshold`, 0 to remove (depositor only, before work starts)
+    pub fn set_cosign_threshold(env: Env, escrow_id: u32, threshold: i128, depositor: Address) -> Result<(), Error> {
+        cosign::set_cosign_threshold(&env, escrow_id, threshold, depositor)
     }
 
-    /// Check if a freelancer has applied to a job
-    pub fn has_applied(env: Env, escrow_id: u32, freelancer: Address) -> bool {
-        marketplace::has_applied(&env, escrow_id, freelancer)
+    /// Get the milestone amount above which an escrow's approvals need an arbiter co-signature
+    pub fn get_cosign_threshold(env: Env, escrow_id: u32) -> Option<i128> {
+        cosign::get_cosign_threshold(&env, escrow_id)
     }
 
-    /// Get an application by escrow_id and freelancer
-    pub fn get_application(env: Env, escrow_id: u32, freelancer: Address) -> Option<Application> {
-        marketplace::get_application(&env, escrow_id, freelancer)
+    /// Check whether an approved milestone is waiting for an arbiter co-signature
+    pub fn is_cosign_pending(env: Env, escrow_id: u32, milestone_index: u32) -> bool {
+        cosign::is_cosign_pending(&env, escrow_id, milestone_index)
     }
 
-    /// Get all applications for an escrow
-    pub fn get_applications(env: Env, escrow_id: u32) -> Vec<Application> {
-        marketplace::get_applications(&env, escrow_id)
+    /// Co-sign an approved milestone as an arbiter, paying it out
+    pub fn cosign_milestone(env: Env, escrow_id: u32, milestone_index: u32, arbiter: Address) -> Result<(), Error> {
+        cosign::cosign_milestone(&env, escrow_id, milestone_index, arbiter)
     }
 
-    /// Get a milestone by escrow_id and milestone_index
-    pub fn get_milestone(env: Env, escrow_id: u32, milestone_index: u32) -> Option<Milestone> {
-        work_lifecycle::get_milestone(&env, escrow_id, milestone_index)
+    /// Get the retainage configured and held for an escrow
+    pub fn get_retainage(env: Env, escrow_id: u32) -> Option<Retainage> {
+        retainage::get_retainage(&env, escrow_id)
     }
 
-    /// Get all milestones for an escrow
-    pub fn get_milestones(env: Env, escrow_id: u32) -> Vec<Milestone> {
-        work_lifecycle::get_milestones(&env, escrow_id)
+    /// Release held retainage to the beneficiary after an undisputed warranty period
+    pub fn release_retainage(env: Env, escrow_id: u32) -> Result<(), Error> {
+        retainage::release_retainage(&env, escrow_id)
     }
 
-    /// Submit a rating for a completed escrow
-    pub fn submit_rating(
+    /// Accept a completed escrow and release its held retainage before the warranty ends (depositor only)
+    pub fn release_retention(env: Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+        retainage::release_retention(&env, escrow_id, depositor)
+    }
+
+    /// Claim back part of the held retainage for defects during the warranty period (depositor only)
+    pub fn open_defect_claim(env: Env, escrow_id: u32, reason: String, amount: i128, depositor: Address) -> Result<(), Error> {
+        retainage::open_defect_claim(&env, escrow_id, reason, amount, depositor)
+    }
+
+    /// Get the defect claim open against an escrow's retainage
+    pub fn get_defect_claim(env: Env, escrow_id: u32) -> Option<DefectClaim> {
+        retainage::get_defect_claim(&env, escrow_id)
+    }
+
+    /// Get the platform arbiter drawn to rule on a defect claim against an escrow without arbiters
+    pub fn get_defect_arbiter(env: Env, escrow_id: u32) -> Option<Address> {
+        retainage::get_defect_arbiter(&env, escrow_id)
+    }
+
+    /// Vote on how much of a defect claim to uphold; settles once the arbiters agree
+    pub fn vote_defect_claim(env: Env, escrow_id: u32, arbiter: Address, upheld_amount: i128) -> Result<(), Error> {
+        retainage::vote_defect_claim(&env, escrow_id, arbiter, upheld_amount)
+    }
+
+    /// Set the seriousness deposit required from open job posters for a token, None for native XLM
+    /// (owner, admin or treasurer)
+    pub fn set_seriousness_deposit(env: Env, token: Option<Address>, amount: i128, caller: Address) -> Result<(), Error> {
+        bonds::set_seriousness_deposit(&env, token, amount, caller)
+    }
+
+    /// Get the seriousness deposit required from open job posters for a token
+    pub fn get_seriousness_deposit(env: Env, token: Option<Address>) -> i128 {
+        bonds::get_seriousness_deposit(&env, token)
+    }
+
+    /// Get the seriousness deposit held for an open job
+    pub fn get_seriousness_deposit_held(env: Env, escrow_id: u32) -> i128 {
+        bonds::get_seriousness_deposit_held(&env, escrow_id)
+    }
+
+    /// Forfeit the seriousness deposit of an open job abandoned past its deadline
+    pub fn claim_abandoned_deposit(env: Env, escrow_id: u32) -> Result<(), Error> {
+        bonds::claim_abandoned_deposit(&env, escrow_id)
+    }
+
+    /// Send refunds for an escrow to a different address than the funding wallet (depositor only)
+    pub fn set_refund_address(env: Env, escrow_id: u32, refund_address: Option<Address>, depositor: Address) -> Result<(), Error> {
+        refund_system::set_refund_address(&env, escrow_id, refund_address, depositor)
+    }
+
+    /// Get the refund address registered for an escrow
+    pub fn get_refund_address(env: Env, escrow_id: u32) -> Option<Address> {
+        refund_system::get_refund_address(&env, escrow_id)
+    }
+
+    /// Return all unpaid funds to the depositor and close the escrow (beneficiary only)
+    pub fn return_funds(env: Env, escrow_id: u32, beneficiary: Address) -> Result<(), Error> {
+        refund_system::return_funds(&env, escrow_id, beneficiary)
+    }
+
+    /// Escalate a disputed milestone by depositing a stake, or match the other side's escalation
+    pub fn escalate_dispute(env: Env, escrow_id: u32, milestone_index: u32, party: Address) -> Result<(), Error> {
+        escalation::escalate_dispute(&env, escrow_id, milestone_index, party)
+    }
+
+    /// Get the escalation open on an escrow
+    pub fn get_escalation(env: Env, escrow_id: u32) -> Option<Escalation> {
+        escalation::get_escalation(&env, escrow_id)
+    }
+
+    /// Get the panel votes cast so far on a matched escalation
+    pub fn get_escalation_votes(env: Env, escrow_id: u32) -> Vec<ArbiterVote> {
+        escalation::get_escalation_votes(&env, escrow_id)
+    }
+
+    /// Vote on a disputed milestone as one of its arbiters; funds move once enough votes agree
+    pub fn resolve_dispute(
         env: Env,
         escrow_id: u32,
-        rating: u32,
-        review: String,
-        client: Address,
+        milestone_index: u32,
+        ruling: DisputeRuling,
+        arbiter: Address,
     ) -> Result<(), Error> {
-        ratings::submit_rating(&env, escrow_id, rating, review, client)
+        dispute_resolution::resolve_dispute(&env, escrow_id, milestone_index, ruling, arbiter)
     }
 
-    /// Get rating for an escrow
-    pub fn get_rating(env: Env, escrow_id: u32) -> Option<Rating> {
-        ratings::get_rating(&env, escrow_id)
+    /// Get the arbitration fee set on an escrow and how much of it is still held
+    pub fn get_arbitration_deposit(env: Env, escrow_id: u32) -> Option<ArbitrationDeposit> {
+        dispute_resolution::get_arbitration_deposit(&env, escrow_id)
     }
 
-    /// Get average rating for a freelancer (returns (total_rating, count))
-    pub fn get_average_rating(env: Env, freelancer: Address) -> (u32, u32) {
-        ratings::get_average_rating(&env, freelancer)
+    /// Appoint the next platform arbiter to a disputed milestone that has no arbiter panel (either party)
+    pub fn appoint_platform_arbiter(env: Env, escrow_id: u32, milestone_index: u32, party: Address) -> Result<Address, Error> {
+        dispute_resolution::appoint_platform_arbiter(&env, escrow_id, milestone_index, party)
     }
 
-    /// Get badge for a freelancer
-    pub fn get_badge(env: Env, freelancer: Address) -> Badge {
-        ratings::get_badge(&env, freelancer)
+    /// Get the platform arbiter appointed to a disputed milestone
+    pub fn get_appointed_arbiter(env: Env, escrow_id: u32, milestone_index: u32) -> Option<Address> {
+        dispute_resolution::get_appointed_arbiter(&env, escrow_id, milestone_index)
     }
 
-    /// Get completed escrows count for a user
-    pub fn get_completed_escrows(env: Env, user: Address) -> u32 {
-        ratings::get_completed_escrows(&env, user)
+    /// Vote to settle a disputed escrow as a whole, paying `beneficiary_bp` of everything unpaid to the
+    /// beneficiary and refunding the rest, with any approved disputed milestone clawed back from retainage
+    pub fn resolve_with_split(env: Env, escrow_id: u32, beneficiary_bp: u32, arbiter: Address) -> Result<(), Error> {
+        dispute_resolution::resolve_with_split(&env, escrow_id, beneficiary_bp, arbiter)
+    }
+
+    /// Get the arbiter votes cast so far on a disputed milestone
+    pub fn get_dispute_votes(env: Env, escrow_id: u32, milestone_index: u32) -> Vec<ArbiterVote> {
+        dispute_resolution::get_dispute_votes(&env, escrow_id, milestone_index)
+    }
+
+    /// Attach evidence to a disputed milestone (parties and its arbiters, within the evidence window)
+    pub fn submit_dispute_evidence(
+        env: Env,
+        escrow_id: u32,
+        milestone_index: u32,
+        evidence_hash: BytesN<32>,
+        uri: String,
+        submitter: Address,
+    ) -> Result<(), Error> {
+        dispute_resolution::submit_dispute_evidence(&env, escrow_id, milestone_index, evidence_hash, uri, submitter)
+    }
+
+    /// Get the evidence submitted on a disputed milestone
+    pub fn get_dispute_evidence(env: Env, escrow_id: u32, milestone_index: u32) -> Vec<DisputeEvidence> {
+        dispute_resolution::get_dispute_evidence(&env, escrow_id, milestone_index)
+    }
+
+    /// Set how many seconds after a dispute is raised evidence can be submitted (moderator)
+    pub fn set_evidence_window(env: Env, seconds: u64, caller: Address) -> Result<(), Error> {
+        dispute_resolution::set_evidence_window(&env, seconds, caller)
+    }
+
+    /// Get the evidence window applied to disputes
+    pub fn get_evidence_window(env: Env) -> u64 {
+        dispute_resolution::get_evidence_window(&env)
+    }
+
+    /// Vote on the final ruling of a matched escalation (the disputed milestone's appointed panel only)
+    pub fn rule_escalation(env: Env, escrow_id: u32, arbiter: Address, beneficiary_wins: bool) -> Result<(), Error> {
+        escalation::rule_escalation(&env, escrow_id, arbiter, beneficiary_wins)
+    }
+
+    /// Award an unmatched escalation to its initiator once the response window has closed
+    pub fn claim_escalation_default(env: Env, escrow_id: u32) -> Result<(), Error> {
+        escalation::claim_escalation_default(&env, escrow_id)
+    }
+
+    /// Set or clear the lending pool adapter idle escrow funds may be deposited with (owner or admin)
+    pub fn set_yield_adapter(env: Env, adapter: Option<Address>, caller: Address) -> Result<(), Error> {
+        idle_yield::set_yield_adapter(&env, adapter, caller)
+    }
+
+    /// Get the configured yield adapter
+    pub fn get_yield_adapter(env: Env) -> Option<Address> {
+        idle_yield::get_yield_adapter(&env)
+    }
+
+    /// Deposit part of an escrow's unpaid balance with the yield adapter (depositor only)
+    pub fn deposit_idle_funds(
+        env: Env,
+        escrow_id: u32,
+        amount: i128,
+        recipient: YieldRecipient,
+        depositor: Address,
+    ) -> Result<(), Error> {
+        idle_yield::deposit_idle_funds(&env, escrow_id, amount, recipient, depositor)
+    }
+
+    /// Withdraw an escrow's deposited funds and settle the accrued yield
+    pub fn withdraw_idle_funds(env: Env, escrow_id: u32, party: Address) -> Result<(), Error> {
+        idle_yield::withdraw_idle_funds(&env, escrow_id, party)
+    }
+
+    /// Get the funds an escrow has deposited with the yield adapter
+    pub fn get_yield_position(env: Env, escrow_id: u32) -> Option<YieldPosition> {
+        idle_yield::get_yield_position(&env, escrow_id)
+    }
+
+    /// Set how many seconds closed escrows stay open to challenge before they can be finalized (owner or admin)
+    pub fn set_finality_window(env: Env, seconds: u64, caller: Address) -> Result<(), Error> {
+        finality::set_finality_window(&env, seconds, caller)
+    }
+
+    /// Get the challenge window applied before escrows become final
+    pub fn get_finality_window(env: Env) -> u64 {
+        finality::get_finality_window(&env)
+    }
+
+    /// Mark a closed escrow final once its challenge window has passed
+    pub fn finalize_escrow(env: Env, escrow_id: u32) -> Result<(), Error> {
+        finality::finalize_escrow(&env, escrow_id)
+    }
+
+    /// Check whether an escrow is final
+    pub fn is_final(env: Env, escrow_id: u32) -> bool {
+        finality::is_final(&env, escrow_id)
+    }
+
+    /// Opt in or out of netting settlements with a counterparty
+    pub fn set_netting(env: Env, counterparty: Address, enabled: bool, party: Address) {
+        netting::set_netting(&env, counterparty, enabled, party);
+    }
+
+    /// Check whether netting is active between two parties
+    pub fn is_netting_active(env: Env, party: Address, counterparty: Address) -> bool {
+        netting::is_netting_active(&env, &party, &counterparty)
+    }
+
+    /// Get the netting credit owed to a party from escrows with a counterparty
+    pub fn get_netting_balance(env: Env, party: Address, counterparty: Address, token: Option<Address>) -> i128 {
+        netting::get_netting_balance(&env, party, counterparty, token)
+    }
+
+    /// Pay out the netting balances between two parties in one settlement
+    pub fn settle_netting(env: Env, party: Address, counterparty: Address, token: Option<Address>) -> Result<(), Error> {
+        netting::settle_netting(&env, party, counterparty, token)
+    }
+
+    /// Refund an escrow
+    pub fn refund_escrow(env: Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+        refund_system::refund_escrow(&env, escrow_id, depositor)
+    }
+
+    /// Emergency refund after deadline
+    pub fn emergency_refund_after_deadline(env: Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+        refund_system::emergency_refund_after_deadline(&env, escrow_id, depositor)
+    }
+
+    /// Extend deadline
+    pub fn extend_deadline(env: Env, escrow_id: u32, extra_seconds: u32, depositor: Address) -> Result<(), Error> {
+        refund_system::extend_deadline(&env, escrow_id, depositor, extra_seconds)
+    }
+
+    /// Give a milestone a due date in seconds from now (0 clears it); needs the beneficiary too once work has started
+    pub fn set_milestone_due_date(
+        env: Env,
+        escrow_id: u32,
+        milestone_index: u32,
+        due_in: u32,
+        depositor: Address,
+    ) -> Result<(), Error> {
+        refund_system::set_milestone_due_date(&env, escrow_id, milestone_index, due_in, depositor)
+    }
+
+    /// Check whether a milestone is past its due date without having been delivered
+    pub fn is_milestone_overdue(env: Env, escrow_id: u32, milestone_index: u32) -> bool {
+        refund_system::is_milestone_overdue(&env, escrow_id, milestone_index)
+    }
+
+    /// Refund an overdue milestone to the depositor without waiting for the emergency window
+    pub fn refund_overdue_milestone(env: Env, escrow_id: u32, milestone_index: u32, depositor: Address) -> Result<(), Error> {
+        refund_system::refund_overdue_milestone(&env, escrow_id, milestone_index, depositor)
+    }
+
+    // View functions
+    pub fn get_escrow(env: Env, escrow_id: u32) -> Option<EscrowData> {
+        escrow_core::get_escrow(&env, escrow_id)
+    }
+
+    /// Quote platform fee, arbiter fee, discounts, and net payout before creating an escrow
+    pub fn quote_fees(env: Env, amount: i128, token: Option<Address>, escrow_options: EscrowOptions) -> FeeQuote {
+        escrow_core::quote_fees(&env, amount, token, escrow_options)
+    }
+
+    /// Dry-run the platform fee, per-milestone payouts and depositor total of an escrow before creating it
+    pub fn quote_escrow(
+        env: Env,
+        total_amount: i128,
+        token: Option<Address>,
+        milestones: Vec<i128>,
+        escrow_options: EscrowOptions,
+    ) -> Result<EscrowQuote, Error> {
+        escrow_core::quote_escrow(&env, total_amount, token, milestones, escrow_options)
+    }
+
+    /// Get deadline and emergency-refund status for an escrow
+    pub fn get_time_status(env: Env, escrow_id: u32) -> Result<TimeStatus, Error> {
+        refund_system::get_time_status(&env, escrow_id)
+    }
+
+    pub fn get_user_escrows(env: Env, user: Address) -> Vec<u32> {
+        escrow_core::get_user_escrows(&env, user)
+    }
+
+    pub fn get_reputation(env: Env, user: Address) -> u32 {
+        escrow_core::get_reputation(&env, user)
+    }
+
+    /// Get a user's reputation decayed for inactivity, capped at the lifetime score from `get_reputation`
+    pub fn get_effective_reputation(env: Env, user: Address) -> u32 {
+        reputation::get_effective_reputation(&env, user)
+    }
+
+    /// Get up to `limit` of the highest-reputation users, best first (at most 50)
+    pub fn get_leaderboard(env: Env, limit: u32) -> Vec<LeaderboardEntry> {
+        leaderboard::get_leaderboard(&env, limit)
+    }
+
+    /// Set the reputation points deducted for losing a dispute or abandoning work (moderator)
+    pub fn set_slash_points(env: Env, reason: SlashReason, points: u32, caller: Address) -> Result<(), Error> {
+        reputation::set_slash_points(&env, reason, points, caller)
+    }
+
+    /// Get the reputation points deducted for a slashing reason
+    pub fn get_slash_points(env: Env, reason: SlashReason) -> u32 {
+        reputation::get_slash_points(&env, reason)
+    }
+
+    /// Get the most recent reputation slashes against a user and why they happened
+    pub fn get_slash_history(env: Env, user: Address) -> Vec<ReputationSlash> {
+        reputation::get_slash_history(&env, user)
+    }
+
+    // Admin functions
+    pub fn set_platform_fee_bp(env: Env, fee_bp: u32, caller: Address) -> Result<(), Error> {
+        admin::set_platform_fee_bp(&env, fee_bp, caller)
+    }
+
+    /// Set the fee taken from bonuses, in basis points (max 10%; owner, admin or treasurer)
+    pub fn set_tip_fee_bp(env: Env, fee_bp: u32, caller: Address) -> Result<(), Error> {
+        admin::set_tip_fee_bp(&env, fee_bp, caller)
+    }
+
+    /// Get the fee taken from bonuses, in basis points
+    pub fn get_tip_fee_bp(env: Env) -> u32 {
+        admin::get_tip_fee_bp(&env)
+    }
+
+    /// Set the share of platform fees paid to referrers, in basis points (owner, admin or treasurer)
+    pub fn set_referral_share_bp(env: Env, share_bp: u32, caller: Address) -> Result<(), Error> {
+        admin::set_referral_share_bp(&env, share_bp, caller)
+    }
+
+    /// Get the share of platform fees paid to referrers, in basis points
+    pub fn get_referral_share_bp(env: Env) -> u32 {
+        admin::get_referral_share_bp(&env)
+    }
+
+    /// Get the referrer of an escrow
+    pub fn get_referrer(env: Env, escrow_id: u32) -> Option<Address> {
+        referrals::get_referrer(&env, escrow_id)
+    }
+
+    /// Get a referrer's unclaimed earnings in a token (None for native XLM)
+    pub fn get_referral_earnings(env: Env, referrer: Address, token: Option<Address>) -> i128 {
+        referrals::get_referral_earnings(&env, referrer, token)
+    }
+
+    /// Withdraw a referrer's earnings in a token
+    pub fn claim_referral_earnings(env: Env, referrer: Address, token: Option<Address>) -> Result<i128, Error> {
+        referrals::claim_referral_earnings(&env, referrer, token)
+    }
+
+    /// Set where collected fees are sent (owner, admin or treasurer)
+    pub fn set_fee_collector(env: Env, fee_collector: Address, caller: Address) -> Result<(), Error> {
+        admin::set_fee_collector(&env, fee_collector, caller)
+    }
+
+    /// Hand the contract to a new owner (owner only; roles cannot transfer ownership)
+    pub fn set_owner(env: Env, new_owner: Address) -> Result<(), Error> {
+        admin::set_owner(&env, new_owner)
+    }
+
+    /// Delegate a role to an address (owner or admin; only the owner grants the admin role)
+    pub fn grant_role(env: Env, address: Address, role: Role, caller: Address) -> Result<(), Error> {
+        admin::grant_role(&env, address, role, caller)
+    }
+
+    /// Take a role away from an address (owner or admin; only the owner revokes the admin role)
+    pub fn revoke_role(env: Env, address: Address, role: Role, caller: Address) -> Result<(), Error> {
+        admin::revoke_role(&env, address, role, caller)
+    }
+
+    /// Check whether an address holds a role
+    pub fn has_role(env: Env, address: Address, role: Role) -> bool {
+        admin::has_role(&env, &address, role)
+    }
+
+    pub fn whitelist_token(env: Env, token: Address, caller: Address) -> Result<(), Error> {
+        admin::whitelist_token(&env, token, caller)
+    }
+
+    /// Stop accepting a token for new escrows (owner, admin or moderator)
+    pub fn remove_whitelisted_token(env: Env, token: Address, caller: Address) -> Result<(), Error> {
+        admin::remove_whitelisted_token(&env, token, caller)
+    }
+
+    /// Refuse a token for new escrows until it is whitelisted again (owner, admin or moderator)
+    pub fn blacklist_token(env: Env, token: Address, caller: Address) -> Result<(), Error> {
+        admin::blacklist_token(&env, token, caller)
+    }
+
+    /// Check whether a token is blacklisted
+    pub fn is_blacklisted_token(env: Env, token: Address) -> bool {
+        admin::is_blacklisted_token(&env, &token)
+    }
+
+    /// Page through whitelisted tokens, from position `start` in whitelisting order
+    pub fn get_whitelisted_tokens(env: Env, start: u32, limit: u32) -> Vec<Address> {
+        admin::get_whitelisted_tokens(&env, start, limit)
+    }
+
+    /// Withdraw accrued platform fees to the fee collector (fee collector only)
+    pub fn withdraw_fees(env: Env, token: Option<Address>, amount: i128) -> Result<(), Error> {
+        admin::withdraw_fees(&env, token, amount)
+    }
+
+    /// Get the platform fees accrued in a token and not yet withdrawn
+    pub fn get_accrued_fees(env: Env, token: Option<Address>) -> i128 {
+        admin::get_accrued_fees(&env, token)
+    }
+
+    /// Get the platform fees collected in a token over the contract's lifetime, including those withdrawn
+    pub fn get_collected_fees(env: Env, token: Option<Address>) -> i128 {
+        admin::get_collected_fees(&env, token)
+    }
+
+    /// Get the total held in escrow for a token (None for native XLM), to reconcile against the contract balance
+    pub fn get_escrowed_amount(env: Env, token: Option<Address>) -> i128 {
+        escrow_core::get_escrowed_amount(&env, token)
+    }
+
+    /// Set the native XLM Stellar Asset Contract address for this network (owner only, as it
+    /// changes which asset every native escrow holds)
+    pub fn set_native_token(env: Env, native_token: Address) -> Result<(), Error> {
+        admin::set_native_token(&env, native_token)
+    }
+
+    /// Get the native XLM Stellar Asset Contract address in use
+    pub fn get_native_token(env: Env) -> Address {
+        escrow_core::get_native_token(&env)
+    }
+
+    /// Set a token's minimum escrow and reputation-eligible values in its smallest unit (owner or admin)
+    pub fn set_value_thresholds(env: Env, token: Option<Address>, thresholds: ValueThresholds, caller: Address) -> Result<(), Error> {
+        admin::set_value_thresholds(&env, token, thresholds, caller)
+    }
+
+    /// Override the platform fee for escrows in a token, None for native XLM (owner, admin or treasurer)
+    pub fn set_token_fee_bp(env: Env, token: Option<Address>, fee_bp: u32, caller: Address) -> Result<(), Error> {
+        admin::set_token_fee_bp(&env, token, fee_bp, caller)
+    }
+
+    /// Get a token's platform fee override
+    pub fn get_token_fee_bp(env: Env, token: Option<Address>) -> Option<u32> {
+        admin::get_token_fee_bp(&env, token)
+    }
+
+    /// Set a token's volume-tiered platform fees, ascending by minimum escrow amount (owner, admin or treasurer)
+    pub fn set_fee_tiers(env: Env, token: Option<Address>, tiers: Vec<FeeTier>, caller: Address) -> Result<(), Error> {
+        admin::set_fee_tiers(&env, token, tiers, caller)
+    }
+
+    /// Get a token's volume-tiered platform fees
+    pub fn get_fee_tiers(env: Env, token: Option<Address>) -> Vec<FeeTier> {
+        admin::get_fee_tiers(&env, token)
+    }
+
+    /// Exempt a depositor from the platform fee, or lift the exemption (owner, admin or treasurer)
+    pub fn set_fee_exempt(env: Env, address: Address, exempt: bool, caller: Address) -> Result<(), Error> {
+        admin::set_fee_exempt(&env, address, exempt, caller)
+    }
+
+    /// Check whether a depositor is exempt from the platform fee
+    pub fn is_fee_exempt(env: Env, address: Address) -> bool {
+        admin::is_fee_exempt(&env, &address)
+    }
+
+    /// Get a token's value thresholds, defaulting from its decimals
+    pub fn get_value_thresholds(env: Env, token: Option<Address>) -> ValueThresholds {
+        admin::get_value_thresholds(&env, token)
+    }
+
+    /// Get the symbol and decimals of a whitelisted token
+    pub fn get_token_info(env: Env, token: Address) -> Option<TokenInfo> {
+        escrow_core::get_token_info(&env, token)
+    }
+
+    pub fn authorize_arbiter(env: Env, arbiter: Address, caller: Address) -> Result<(), Error> {
+        admin::authorize_arbiter(&env, arbiter, caller)
+    }
+
+    /// Remove an arbiter from the platform pool (owner, admin or moderator)
+    pub fn revoke_arbiter(env: Env, arbiter: Address, caller: Address) -> Result<(), Error> {
+        admin::revoke_arbiter(&env, arbiter, caller)
+    }
+
+    /// Get the platform arbiters disputes without a panel are appointed from
+    pub fn get_arbiter_pool(env: Env) -> Vec<Address> {
+        admin::get_arbiter_pool(&env)
+    }
+
+    /// Set the allowed escrow duration range and maximum deadline extension in seconds (owner or admin)
+    pub fn set_duration_bounds(env: Env, min_duration: u32, max_duration: u32, max_extension: u32, caller: Address) -> Result<(), Error> {
+        admin::set_duration_bounds(&env, min_duration, max_duration, max_extension, caller)
+    }
+
+    /// Upgrade the contract to already-uploaded wasm (owner only)
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        admin::upgrade(&env, new_wasm_hash)
+    }
+
+    /// Migrate storage from `from_version` to this build's layout after an upgrade (owner only).
+    /// Converts a batch of escrows per call; returns true once the migration is complete.
+    pub fn migrate(env: Env, from_version: u32, to_version: u32) -> Result<bool, Error> {
+        migrations::migrate(&env, from_version, to_version)
+    }
+
+    /// Get the storage layout version of the deployed contract
+    pub fn get_version(env: Env) -> u32 {
+        admin::get_version(&env)
+    }
+
+    /// Get the global contract configuration
+    pub fn get_config(env: Env) -> Result<Config, Error> {
+        admin::get_config(&env)
+    }
+
+    /// Pause every state change except refunds, for emergencies (owner, admin or moderator)
+    pub fn pause_contract(env: Env, caller: Address) -> Result<(), Error> {
+        admin::set_contract_paused(&env, true, caller)
+    }
+
+    /// Lift an emergency pause (owner, admin or moderator)
+    pub fn unpause_contract(env: Env, caller: Address) -> Result<(), Error> {
+        admin::set_contract_paused(&env, false, caller)
+    }
+
+    /// Check if the contract is paused
+    pub fn is_contract_paused(env: Env) -> bool {
+        admin::is_contract_paused(&env)
+    }
+
+    /// Hold a suspicious escrow, blocking every action on it including refunds (owner, admin or moderator)
+    pub fn freeze_escrow(env: Env, escrow_id: u32, caller: Address) -> Result<(), Error> {
+        admin::set_escrow_frozen(&env, escrow_id, true, caller)
+    }
+
+    /// Release a held escrow (owner, admin or moderator)
+    pub fn unfreeze_escrow(env: Env, escrow_id: u32, caller: Address) -> Result<(), Error> {
+        admin::set_escrow_frozen(&env, escrow_id, false, caller)
+    }
+
+    /// Check if an escrow is frozen
+    pub fn is_escrow_frozen(env: Env, escrow_id: u32) -> bool {
+        admin::is_escrow_frozen(&env, escrow_id)
+    }
+
+    /// Pause job creation (owner, admin or moderator)
+    pub fn pause_job_creation(env: Env, caller: Address) -> Result<(), Error> {
+        admin::set_job_creation_paused(&env, true, caller)
+    }
+
+    /// Unpause job creation (owner, admin or moderator)
+    pub fn unpause_job_creation(env: Env, caller: Address) -> Result<(), Error> {
+        admin::set_job_creation_paused(&env, false, caller)
+    }
+
+    /// Check if job creation is paused
+    pub fn is_job_creation_paused(env: Env) -> bool {
+        admin::is_job_creation_paused(&env)
+    }
+
+    /// Set the posting fee charged on open jobs for a token, None for native XLM (owner, admin or treasurer)
+    pub fn set_posting_fee(env: Env, token: Option<Address>, amount: i128, caller: Address) -> Result<(), Error> {
+        admin::set_posting_fee(&env, token, amount, caller)
+    }
+
+    /// Set whether the posting fee is refunded to the depositor on hire (owner, admin or treasurer)
+    pub fn set_posting_fee_refundable(env: Env, refundable: bool, caller: Address) -> Result<(), Error> {
+        admin::set_posting_fee_refundable(&env, refundable, caller)
+    }
+
+    /// Get the posting fee charged on open jobs for a token
+    pub fn get_posting_fee(env: Env, token: Option<Address>) -> i128 {
+        admin::get_posting_fee(&env, token)
+    }
+
+    /// Check if the posting fee is refunded on hire
+    pub fn is_posting_fee_refundable(env: Env) -> bool {
+        admin::is_posting_fee_refundable(&env)
+    }
+
+    /// Set or clear the reputation gate for posting high-value escrows (owner or admin)
+    pub fn set_posting_gate(env: Env, gate: Option<PostingGate>, caller: Address) -> Result<(), Error> {
+        admin::set_posting_gate(&env, gate, caller)
+    }
+
+    /// Get the reputation gate for posting high-value escrows
+    pub fn get_posting_gate(env: Env) -> Option<PostingGate> {
+        admin::get_posting_gate(&env)
+    }
+
+    /// Set or clear the external KYC attestation gate (owner or admin)
+    pub fn set_kyc_config(env: Env, config: Option<KycConfig>, caller: Address) -> Result<(), Error> {
+        compliance::set_kyc_config(&env, config, caller)
+    }
+
+    /// Get the external KYC attestation gate
+    pub fn get_kyc_config(env: Env) -> Option<KycConfig> {
+        compliance::get_kyc_config(&env)
+    }
+
+    /// Set or clear the external sanctions screening contract (owner or admin)
+    pub fn set_screening_contract(env: Env, screening_contract: Option<Address>, caller: Address) -> Result<(), Error> {
+        compliance::set_screening_contract(&env, screening_contract, caller)
+    }
+
+    /// Get the external sanctions screening contract
+    pub fn get_screening_contract(env: Env) -> Option<Address> {
+        compliance::get_screening_contract(&env)
+    }
+
+    /// Get the posting fee held for an open job until hire
+    pub fn get_posting_fee_held(env: Env, escrow_id: u32) -> i128 {
+        marketplace::get_posting_fee_held(&env, escrow_id)
+    }
+
+    /// Get the contract owner
+    pub fn get_owner(env: Env) -> Result<Address, Error> {
+        admin::get_owner(&env)
+    }
+
+    /// Cancel an open job that has not hired anyone and refund it (depositor only)
+    pub fn cancel_open_job(env: Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+        marketplace::cancel_open_job(&env, escrow_id, depositor)
+    }
+
+    /// Counter-offer an applicant a new escrow total and timeline (depositor only)
+    pub fn propose_terms(
+        env: Env,
+        escrow_id: u32,
+        freelancer: Address,
+        new_amount: i128,
+        new_timeline: u32,
+        depositor: Address,
+    ) -> Result<(), Error> {
+        marketplace::propose_terms(&env, escrow_id, freelancer, new_amount, new_timeline, depositor)
+    }
+
+    /// Accept a counter-offer and get hired on the revised terms
+    pub fn accept_terms(env: Env, escrow_id: u32, freelancer: Address) -> Result<(), Error> {
+        marketplace::accept_terms(&env, escrow_id, freelancer)
+    }
+
+    /// Decline a counter-offer
+    pub fn decline_terms(env: Env, escrow_id: u32, freelancer: Address) -> Result<(), Error> {
+        marketplace::decline_terms(&env, escrow_id, freelancer)
+    }
+
+    /// Offer an open job directly to a freelancer for `expires_in` seconds (depositor only)
+    pub fn invite_freelancer(
+        env: Env,
+        escrow_id: u32,
+        freelancer: Address,
+        expires_in: u32,
+        depositor: Address,
+    ) -> Result<(), Error> {
+        marketplace::invite_freelancer(&env, escrow_id, freelancer, expires_in, depositor)
+    }
+
+    /// Accept an invitation to an open job and get hired without applying
+    pub fn accept_invitation(env: Env, escrow_id: u32, freelancer: Address) -> Result<(), Error> {
+        marketplace::accept_invitation(&env, escrow_id, freelancer)
+    }
+
+    /// Get the invitation sent to a freelancer for an open job
+    pub fn get_invitation(env: Env, escrow_id: u32, freelancer: Address) -> Option<Invitation> {
+        marketplace::get_invitation(&env, escrow_id, freelancer)
+    }
+
+    /// Get the counter-offer made on a freelancer's application
+    pub fn get_negotiation(env: Env, escrow_id: u32, freelancer: Address) -> Option<Negotiation> {
+        marketplace::get_negotiation(&env, escrow_id, freelancer)
+    }
+
+    /// Decline an application with a reason (depositor only)
+    pub fn reject_application(
+        env: Env,
+        escrow_id: u32,
+        freelancer: Address,
+        reason: String,
+        depositor: Address,
+    ) -> Result<(), Error> {
+        marketplace::reject_application(&env, escrow_id, freelancer, reason, depositor)
+    }
+
+    /// Withdraw an application before being hired
+    pub fn withdraw_application(env: Env, escrow_id: u32, freelancer: Address) -> Result<(), Error> {
+        marketplace::withdraw_application(&env, escrow_id, freelancer)
+    }
+
+    /// Check if a freelancer has applied to a job
+    pub fn has_applied(env: Env, escrow_id: u32, freelancer: Address) -> bool {
+        marketplace::has_applied(&env, escrow_id, freelancer)
+    }
+
+    /// Get an application by escrow_id and freelancer
+    pub fn get_application(env: Env, escrow_id: u32, freelancer: Address) -> Option<Application> {
+        marketplace::get_application(&env, escrow_id, freelancer)
+    }
+
+    /// Get all applications for an escrow
+    pub fn get_applications(env: Env, escrow_id: u32) -> Vec<Application> {
+        marketplace::get_applications(&env, escrow_id)
+    }
+
+    /// Stake reputation points on an application as a bid guarantee
+    pub fn stake_reputation(env: Env, escrow_id: u32, freelancer: Address, points: u32) -> Result<(), Error> {
+        marketplace::stake_reputation(&env, escrow_id, freelancer, points)
+    }
+
+    /// Get the thresholds a freelancer must meet to apply to a job
+    pub fn get_application_requirements(env: Env, escrow_id: u32) -> Option<ApplicationRequirements> {
+        marketplace::get_application_requirements(&env, escrow_id)
+    }
+
+    /// Get the reputation points a freelancer staked on an application
+    pub fn get_reputation_stake(env: Env, escrow_id: u32, freelancer: Address) -> u32 {
+        marketplace::get_reputation_stake(&env, escrow_id, freelancer)
+    }
+
+    /// Save an open job to the user's watchlist
+    pub fn watch_escrow(env: Env, escrow_id: u32, user: Address) -> Result<(), Error> {
+        marketplace::watch_escrow(&env, escrow_id, user)
+    }
+
+    /// Remove a job from the user's watchlist
+    pub fn unwatch_escrow(env: Env, escrow_id: u32, user: Address) -> Result<(), Error> {
+        marketplace::unwatch_escrow(&env, escrow_id, user)
+    }
+
+    /// Get the jobs saved in a user's watchlist
+    pub fn get_watchlist(env: Env, user: Address) -> Vec<u32> {
+        marketplace::get_watchlist(&env, user)
+    }
+
+    /// Get a milestone by escrow_id and milestone_index
+    pub fn get_milestone(env: Env, escrow_id: u32, milestone_index: u32) -> Option<Milestone> {
+        work_lifecycle::get_milestone(&env, escrow_id, milestone_index)
+    }
+
+    /// Post a progress checkpoint against an unsubmitted milestone (beneficiary only)
+    pub fn post_checkpoint(
+        env: Env,
+        escrow_id: u32,
+        milestone_index: u32,
+        note: String,
+        hash: Option<BytesN<32>>,
+        beneficiary: Address,
+    ) -> Result<(), Error> {
+        milestone_log::post_checkpoint(&env, escrow_id, milestone_index, note, hash, beneficiary)
+    }
+
+    /// Get the progress checkpoints posted against a milestone
+    pub fn get_checkpoints(env: Env, escrow_id: u32, milestone_index: u32) -> Vec<Checkpoint> {
+        milestone_log::get_checkpoints(&env, escrow_id, milestone_index)
+    }
+
+    /// Comment on a milestone (parties, plus its arbiters while disputed)
+    pub fn post_comment(env: Env, escrow_id: u32, milestone_index: u32, body: String, author: Address) -> Result<(), Error> {
+        milestone_log::post_comment(&env, escrow_id, milestone_index, body, author)
+    }
+
+    /// Get the comment thread of a milestone
+    pub fn get_comments(env: Env, escrow_id: u32, milestone_index: u32) -> Vec<Comment> {
+        milestone_log::get_comments(&env, escrow_id, milestone_index)
+    }
+
+    /// Get the team an escrow's payouts are split across
+    pub fn get_team_splits(env: Env, escrow_id: u32) -> Option<Vec<(Address, u32)>> {
+        team::get_team_splits(&env, escrow_id)
+    }
+
+    /// Tip the beneficiary of a released escrow in the escrow's token (depositor only)
+    pub fn send_bonus(env: Env, escrow_id: u32, amount: i128, depositor: Address) -> Result<(), Error> {
+        work_lifecycle::send_bonus(&env, escrow_id, amount, depositor)
+    }
+
+    /// Fund extra revisions on a milestone, released to the beneficiary when it is approved (depositor only)
+    pub fn purchase_revision(env: Env, escrow_id: u32, milestone_index: u32, fee: i128, depositor: Address) -> Result<(), Error> {
+        work_lifecycle::purchase_revision(&env, escrow_id, milestone_index, fee, depositor)
+    }
+
+    /// Get the revision fee funded on a milestone
+    pub fn get_revision_fee(env: Env, escrow_id: u32, milestone_index: u32) -> i128 {
+        work_lifecycle::get_revision_fee(&env, escrow_id, milestone_index)
+    }
+
+    /// Propose a change order repricing, removing, or adding milestones (either party)
+    pub fn propose_change_order(
+        env: Env,
+        escrow_id: u32,
+        amount_deltas: Vec<MilestoneAdjustment>,
+        removed_milestones: Vec<u32>,
+        new_milestone_amounts: Vec<i128>,
+        new_milestone_descriptions: Vec<String>,
+        proposer: Address,
+    ) -> Result<u32, Error> {
+        change_orders::propose_change_order(
+            &env,
+            escrow_id,
+            amount_deltas,
+            removed_milestones,
+            new_milestone_amounts,
+            new_milestone_descriptions,
+            proposer,
+        )
+    }
+
+    /// Accept a change order as the counterparty, adjusting funding and milestones atomically
+    pub fn accept_change_order(env: Env, escrow_id: u32, order_id: u32, party: Address) -> Result<(), Error> {
+        change_orders::accept_change_order(&env, escrow_id, order_id, party)
+    }
+
+    /// Propose appending milestones worth `additional_amount` plus fee, paid in now (depositor); returns the change order id
+    pub fn propose_amendment(
+        env: Env,
+        escrow_id: u32,
+        new_milestones: Vec<(i128, String)>,
+        additional_amount: i128,
+        depositor: Address,
+    ) -> Result<u32, Error> {
+        change_orders::propose_amendment(&env, escrow_id, new_milestones, additional_amount, depositor)
+    }
+
+    /// Accept an amendment as the beneficiary, appending its milestones with the funds held at proposal
+    pub fn accept_amendment(env: Env, escrow_id: u32, amendment_id: u32, beneficiary: Address) -> Result<(), Error> {
+        change_orders::accept_amendment(&env, escrow_id, amendment_id, beneficiary)
+    }
+
+    /// Withdraw or decline a proposed change order
+    pub fn cancel_change_order(env: Env, escrow_id: u32, order_id: u32, party: Address) -> Result<(), Error> {
+        change_orders::cancel_change_order(&env, escrow_id, order_id, party)
+    }
+
+    /// Get a change order
+    pub fn get_change_order(env: Env, escrow_id: u32, order_id: u32) -> Option<ChangeOrder> {
+        change_orders::get_change_order(&env, escrow_id, order_id)
+    }
+
+    /// Get every change order recorded against an escrow
+    pub fn get_change_orders(env: Env, escrow_id: u32) -> Vec<ChangeOrder> {
+        change_orders::get_change_orders(&env, escrow_id)
+    }
+
+    /// Get the funding the depositor paid in towards an open change order
+    pub fn get_change_order_funding(env: Env, escrow_id: u32, order_id: u32) -> i128 {
+        change_orders::get_change_order_funding(&env, escrow_id, order_id)
+    }
+
+    /// Get the earlier submissions of a milestone and their rejection reasons
+    pub fn get_submission_history(env: Env, escrow_id: u32, milestone_index: u32) -> Vec<SubmissionRecord> {
+        milestone_log::get_submission_history(&env, escrow_id, milestone_index)
+    }
+
+    /// Get a page of an escrow's activity log, oldest first
+    pub fn get_escrow_activity(env: Env, escrow_id: u32, start: u32, limit: u32) -> Vec<ActivityEntry> {
+        milestone_log::get_escrow_activity(&env, escrow_id, start, limit)
+    }
+
+    /// Get the number of entries in an escrow's activity log
+    pub fn get_escrow_activity_count(env: Env, escrow_id: u32) -> u32 {
+        milestone_log::get_activity_count(&env, escrow_id)
+    }
+
+    /// Get all milestones for an escrow
+    pub fn get_milestones(env: Env, escrow_id: u32) -> Vec<Milestone> {
+        work_lifecycle::get_milestones(&env, escrow_id)
+    }
+
+    /// Get the entrypoints a caller can currently invoke on an escrow
+    pub fn get_allowed_actions(env: Env, escrow_id: u32, caller: Address) -> Vec<EscrowAction> {
+        views::get_allowed_actions(&env, escrow_id, caller)
+    }
+
+    /// Get every milestone across a user's escrows awaiting their approval or submission
+    pub fn get_pending_approvals(env: Env, user: Address) -> Vec<MilestoneRef> {
+        views::get_pending_approvals(&env, user)
+    }
+
+    /// Page through escrows from `start_id` upwards, optionally filtered by status
+    pub fn list_escrows(env: Env, start_id: u32, limit: u32, filter: EscrowStatusFilter) -> Vec<EscrowListing> {
+        views::list_escrows(&env, start_id, limit, filter)
+    }
+
+    /// Page through open jobs still taking applications
+    pub fn list_open_jobs(env: Env, start_id: u32, limit: u32) -> Vec<EscrowListing> {
+        views::list_open_jobs(&env, start_id, limit)
+    }
+
+    /// Get an escrow with its milestones, application count, open disputes and remaining balance in one call
+    pub fn get_escrow_summary(env: Env, escrow_id: u32) -> Result<EscrowSummary, Error> {
+        views::get_escrow_summary(&env, escrow_id)
+    }
+
+    /// Page through the escrows a user created as client, optionally only those in one status
+    pub fn get_escrows_as_depositor(env: Env, user: Address, start_id: u32, limit: u32, filter: EscrowStatusFilter) -> Vec<EscrowListing> {
+        views::get_escrows_as_depositor(&env, user, start_id, limit, filter)
+    }
+
+    /// Page through the escrows a user was hired on as freelancer, optionally only those in one status
+    pub fn get_escrows_as_beneficiary(env: Env, user: Address, start_id: u32, limit: u32, filter: EscrowStatusFilter) -> Vec<EscrowListing> {
+        views::get_escrows_as_beneficiary(&env, user, start_id, limit, filter)
+    }
+
+    /// Get the unix timestamp an open job stops taking applications at
+    pub fn get_application_deadline(env: Env, escrow_id: u32) -> Option<u64> {
+        marketplace::get_application_deadline(&env, escrow_id)
+    }
+
+    /// Delist an open job past its application deadline without a hire, opening it to refund (anyone)
+    pub fn expire_open_job(env: Env, escrow_id: u32) -> Result<(), Error> {
+        marketplace::expire_open_job(&env, escrow_id)
+    }
+
+    /// Get a user's active escrows, pending milestone actions, disputes, jobs awaiting hire, open
+    /// offers and invitations, and unclaimed balances
+    pub fn get_dashboard(env: Env, user: Address) -> Dashboard {
+        views::get_dashboard(&env, user)
+    }
+
+    /// Get the effective arbiter panel for disputes on a milestone
+    pub fn get_milestone_arbiters(env: Env, escrow_id: u32, milestone_index: u32) -> Result<MilestoneArbiters, Error> {
+        work_lifecycle::get_milestone_arbiters(&env, escrow_id, milestone_index)
+    }
+
+    /// Get milestone counts per status and payout progress for an escrow
+    pub fn get_progress(env: Env, escrow_id: u32) -> Result<EscrowProgress, Error> {
+        work_lifecycle::get_progress(&env, escrow_id)
+    }
+
+    /// Set the caller's payout preferences (minimum vested claim, payout address)
+    pub fn set_payout_preferences(env: Env, freelancer: Address, preferences: PayoutPreferences) -> Result<(), Error> {
+        profiles::set_payout_preferences(&env, freelancer, preferences)
+    }
+
+    /// Get a freelancer's payout preferences
+    pub fn get_payout_preferences(env: Env, freelancer: Address) -> Option<PayoutPreferences> {
+        profiles::get_payout_preferences(&env, freelancer)
+    }
+
+    /// Endorse the counterparty of a completed escrow for a skill
+    pub fn endorse(env: Env, escrow_id: u32, endorser: Address, skill: Symbol) -> Result<(), Error> {
+        profiles::endorse(&env, escrow_id, endorser, skill)
+    }
+
+    /// Get the skill endorsements a user has received
+    pub fn get_endorsements(env: Env, user: Address) -> Vec<Endorsement> {
+        profiles::get_endorsements(&env, user)
+    }
+
+    /// Submit a rating for a completed escrow
+    pub fn submit_rating(
+        env: Env,
+        escrow_id: u32,
+        rating: u32,
+        review: String,
+        client: Address,
+    ) -> Result<(), Error> {
+        ratings::submit_rating(&env, escrow_id, rating, review, client)
+    }
+
+    /// Submit a rating whose full review is stored off-chain (hash plus short excerpt on-chain)
+    pub fn submit_rating_hashed(
+        env: Env,
+        escrow_id: u32,
+        rating: u32,
+        excerpt: String,
+        review_hash: BytesN<32>,
+        client: Address,
+    ) -> Result<(), Error> {
+        ratings::submit_rating_hashed(&env, escrow_id, rating, excerpt, review_hash, client)
+    }
+
+    /// Change a rating's stars and review while its edit window is open (rater only)
+    pub fn update_rating(env: Env, escrow_id: u32, rating: u32, review: String, client: Address) -> Result<(), Error> {
+        ratings::update_rating(&env, escrow_id, rating, review, client)
+    }
+
+    /// Post the rated freelancer's single public response to a rating
+    pub fn respond_to_rating(env: Env, escrow_id: u32, response: String, freelancer: Address) -> Result<(), Error> {
+        ratings::respond_to_rating(&env, escrow_id, response, freelancer)
+    }
+
+    /// Set how many seconds after rating the rater can still edit it (owner or admin)
+    pub fn set_rating_edit_window(env: Env, seconds: u64, caller: Address) -> Result<(), Error> {
+        ratings::set_rating_edit_window(&env, seconds, caller)
+    }
+
+    /// Get how many seconds after rating the rater can still edit it
+    pub fn get_rating_edit_window(env: Env) -> u64 {
+        ratings::get_rating_edit_window(&env)
+    }
+
+    /// Set the maximum length of on-chain reviews (owner or admin)
+    pub fn set_max_review_length(env: Env, max_length: u32, caller: Address) -> Result<(), Error> {
+        ratings::set_max_review_length(&env, max_length, caller)
+    }
+
+    /// Get the maximum length of on-chain reviews
+    pub fn get_max_review_length(env: Env) -> u32 {
+        ratings::get_max_review_length(&env)
+    }
+
+    /// Get rating for an escrow
+    pub fn get_rating(env: Env, escrow_id: u32) -> Option<Rating> {
+        ratings::get_rating(&env, escrow_id)
+    }
+
+    /// Get average rating for a freelancer (returns (total_rating, count))
+    pub fn get_average_rating(env: Env, freelancer: Address) -> (u32, u32) {
+        ratings::get_average_rating(&env, freelancer)
+    }
+
+    /// Get average rating for a freelancer scaled by 100 (returns (score, count), e.g. 437 = 4.37)
+    pub fn get_rating_score(env: Env, freelancer: Address) -> (u32, u32) {
+        ratings::get_rating_score(&env, freelancer)
+    }
+
+    /// Get badge for a freelancer
+    pub fn get_badge(env: Env, freelancer: Address) -> Badge {
+        ratings::get_badge(&env, freelancer)
+    }
+
+    /// Get what a freelancer is missing for the next badge tier
+    pub fn get_badge_progress(env: Env, freelancer: Address) -> BadgeProgress {
+        ratings::get_badge_progress(&env, freelancer)
+    }
+
+    /// Set the completed projects, average rating and dispute record a badge tier requires (owner or admin)
+    pub fn set_badge_criteria(env: Env, badge: Badge, criteria: BadgeCriteria, caller: Address) -> Result<(), Error> {
+        ratings::set_badge_criteria(&env, badge, criteria, caller)
+    }
+
+    /// Get the criteria a badge tier requires
+    pub fn get_badge_criteria(env: Env, badge: Badge) -> BadgeCriteria {
+        ratings::get_badge_criteria(&env, badge)
+    }
+
+    /// Get lost disputes, abandoned escrows, and voluntarily withdrawn escrows recorded against a freelancer
+    pub fn get_adverse_record(env: Env, freelancer: Address) -> AdverseRecord {
+        ratings::get_adverse_record(&env, freelancer)
+    }
+
+    /// Get a depositor's client track record
+    pub fn get_client_record(env: Env, client: Address) -> ClientRecord {
+        ratings::get_client_record(&env, client)
+    }
+
+    /// Get client badge for a depositor
+    pub fn get_client_badge(env: Env, client: Address) -> ClientBadge {
+        ratings::get_client_badge(&env, client)
+    }
+
+    /// Get the category of a job
+    pub fn get_job_category(env: Env, escrow_id: u32) -> Option<JobCategory> {
+        categories::get_job_category(&env, escrow_id)
+    }
+
+    /// Get the skill tags of a job
+    pub fn get_skill_tags(env: Env, escrow_id: u32) -> Vec<Symbol> {
+        categories::get_skill_tags(&env, escrow_id)
+    }
+
+    /// Page through jobs in a category
+    pub fn list_jobs_by_category(env: Env, category: JobCategory, start_id: u32, limit: u32) -> Vec<EscrowListing> {
+        views::list_jobs_by_category(&env, category, start_id, limit)
+    }
+
+    /// Get a user's reputation within a job category
+    pub fn get_reputation_by_category(env: Env, user: Address, category: JobCategory) -> u32 {
+        categories::get_reputation_by_category(&env, user, category)
+    }
+
+    /// Get the achievements earned by a user
+    pub fn get_achievements(env: Env, user: Address) -> Vec<AchievementRecord> {
+        achievements::get_achievements(&env, user)
+    }
+
+    /// Check if a user has earned an achievement
+    pub fn has_achievement(env: Env, user: Address, achievement: Achievement) -> bool {
+        achievements::has_achievement(&env, user, achievement)
+    }
+
+    /// Get the non-transferable credential a holder was issued for an achievement
+    pub fn get_credential(env: Env, holder: Address, achievement: Achievement) -> Option<AchievementRecord> {
+        credentials::get_credential(&env, holder, achievement)
+    }
+
+    /// Check if a holder has been issued a credential, for gating in wallets and other contracts
+    pub fn has_credential(env: Env, holder: Address, achievement: Achievement) -> bool {
+        credentials::has_credential(&env, holder, achievement)
+    }
+
+    /// Get a freelancer's lifetime earnings in a token (None for native XLM)
+    pub fn get_total_earned(env: Env, freelancer: Address, token: Option<Address>) -> i128 {
+        credentials::get_total_earned(&env, freelancer, token)
+    }
+
+    /// Get how much a depositor has released to freelancers in a token (None for native XLM)
+    pub fn get_client_spend(env: Env, depositor: Address, token: Option<Address>) -> i128 {
+        client_spend::get_client_spend(&env, depositor, token)
+    }
+
+    /// Get a depositor's payer badge in a token (None for native XLM), e.g. Verified Payer above 1,000 XLM released
+    pub fn get_payer_badge(env: Env, depositor: Address, token: Option<Address>) -> PayerBadge {
+        client_spend::get_payer_badge(&env, depositor, token)
+    }
+
+    /// Get how many disputes an arbiter's ruling has settled
+    pub fn get_disputes_arbitrated(env: Env, arbiter: Address) -> u32 {
+        credentials::get_disputes_arbitrated(&env, arbiter)
+    }
+
+    /// Get completed escrows count for a user
+    pub fn get_completed_escrows(env: Env, user: Address) -> u32 {
+        ratings::get_completed_escrows(&env, user)
+    }
+
+    /// Page through authorized arbiters, from position `start` in authorization order
+    pub fn get_authorized_arbiters(env: Env, start: u32, limit: u32) -> Vec<Address> {
+        admin::get_authorized_arbiters(&env, start, limit)
     }
 
     /// Check if an address is an authorized arbiter
@@ -1,9 +1,21 @@
 use crate::admin;
+use crate::bonds;
+use crate::compliance;
+use crate::dispute_resolution;
 use crate::escrow_core;
-use crate::storage_types::{Application, DataKey, EscrowStatus, DeCentPayError, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
+use crate::finality;
+use crate::idle_yield;
+use crate::milestone_log;
+use crate::ratings;
+use crate::refund_system;
+use crate::stakes;
+use crate::work_lifecycle;
+use crate::storage_types::{AccountKey, ActivityAction, Application, ApplicationRequirements, ApplicationStatus, ApplicationWithdrawn, DataKey, EscrowKey, Invitation, Negotiation, NegotiationStatus, EscrowData, EscrowStatus, DeCentPayError, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
 use soroban_sdk::{Env, Address, String, Vec, Error};
 
-const MAX_APPLICATIONS: u32 = 50;
+pub const MAX_APPLICATIONS: u32 = 50;
+const MAX_WATCHLIST: u32 = 100;
+const STAKE_BONUS_BP: u32 = 1000; // 10% bonus on staked reputation returned at completion
 
 pub fn apply_to_job(
     env: &Env,
@@ -17,15 +29,46 @@ pub fn apply_to_job(
     // require_auth() validates that the freelancer signed the transaction without checking specific args
     freelancer.require_auth();
 
-    // Check if job creation is paused
-    if admin::is_job_creation_paused(env) {
-        return Err(Error::from_contract_error(DeCentPayError::JobCreationPaused as u32));
-    }
-
     // Validate escrow
-    escrow_core::require_valid_escrow(env, escrow_id)?;
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
     let escrow = escrow_core::get_escrow(env, escrow_id)
         .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    check_can_apply(env, escrow_id, &escrow, &freelancer)?;
+
+    stakes::lock_stake(env, escrow_id, &escrow, &freelancer)?;
+
+    // Applications are packed into slots 0..count, so the next one goes at the end
+    let application_index = get_application_count(env, escrow_id);
+
+    // Create application
+    let application = Application {
+        freelancer: freelancer.clone(),
+        cover_letter,
+        proposed_timeline,
+        applied_at: env.ledger().timestamp(),
+        status: ApplicationStatus::Pending,
+        rejection_reason: None,
+    };
+
+    // Save application at the next available index
+    env.storage()
+        .instance()
+        .set(&DataKey::Application(escrow_id, application_index), &application);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ApplicantIndex(escrow_id, freelancer), &application_index);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ApplicationCount(escrow_id), &(application_index + 1));
+    
+    Ok(())
+}
+
+/// Check a freelancer may apply to an open job, short of locking their application stake
+pub fn check_can_apply(env: &Env, escrow_id: u32, escrow: &EscrowData, freelancer: &Address) -> Result<(), Error> {
+    if admin::is_job_creation_paused(env) {
+        return Err(Error::from_contract_error(DeCentPayError::JobCreationPaused as u32));
+    }
 
     // Validate escrow is an open job
     if !escrow.is_open_job {
@@ -36,66 +79,47 @@ pub fn apply_to_job(
         return Err(Error::from_contract_error(DeCentPayError::JobClosed as u32));
     }
 
-    if escrow.depositor == freelancer {
+    if escrow.depositor == *freelancer {
         return Err(Error::from_contract_error(DeCentPayError::CannotApplyToOwnJob as u32));
     }
 
+    if get_application_deadline(env, escrow_id).is_some_and(|deadline| env.ledger().timestamp() >= deadline) {
+        return Err(Error::from_contract_error(DeCentPayError::ApplicationDeadlinePassed as u32));
+    }
+
     // Check if already applied
     if has_applied(env, escrow_id, freelancer.clone()) {
         return Err(Error::from_contract_error(DeCentPayError::AlreadyApplied as u32));
     }
 
-    // Find the first available slot and count existing applications
-    env.storage()
-        .instance()
-        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-    
-    let mut application_count = 0u32;
-    let mut next_available_index: Option<u32> = None;
-    
-    // Check all possible application indices to find first empty slot
-    for app_index in 0..MAX_APPLICATIONS {
-        let key = DataKey::Application(escrow_id, app_index);
-        if let Some(_existing_app) = env.storage().instance().get::<DataKey, Application>(&key) {
-            application_count += 1;
-        } else if next_available_index.is_none() {
-            next_available_index = Some(app_index);
+    if let Some(requirements) = get_application_requirements(env, escrow_id) {
+        if escrow_core::get_reputation(env, freelancer.clone()) < requirements.min_reputation
+            || ratings::get_badge(env, freelancer.clone()) < requirements.min_badge
+        {
+            return Err(Error::from_contract_error(DeCentPayError::InsufficientReputation as u32));
         }
     }
-    
-    // Check if we've reached max applications
-    if application_count >= MAX_APPLICATIONS {
+
+    if get_application_count(env, escrow_id) >= MAX_APPLICATIONS {
         return Err(Error::from_contract_error(DeCentPayError::TooManyApplications as u32));
     }
-    
-    // Get the next available index (should always be Some at this point)
-    let application_index = next_available_index
-        .ok_or_else(|| Error::from_contract_error(DeCentPayError::TooManyApplications as u32))?;
-
-    // Create application
-    let application = Application {
-        freelancer: freelancer.clone(),
-        cover_letter,
-        proposed_timeline,
-        applied_at: env.ledger().sequence(),
-    };
-
-    // Save application at the next available index
-    env.storage()
-        .instance()
-        .set(&DataKey::Application(escrow_id, application_index), &application);
-    
     Ok(())
 }
 
 pub fn accept_freelancer(env: &Env, escrow_id: u32, depositor: Address, freelancer: Address) -> Result<(), Error> {
     depositor.require_auth();
 
-    escrow_core::require_valid_escrow(env, escrow_id)?;
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
     let mut escrow = escrow_core::get_escrow(env, escrow_id)
         .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    check_listed_job(&escrow, &depositor)?;
 
-    if escrow.depositor != depositor {
+    hire(env, escrow_id, &mut escrow, freelancer, &depositor, false)
+}
+
+/// Check `depositor` posted an open job that is still listed, so they may hire for or withdraw it
+pub fn check_listed_job(escrow: &EscrowData, depositor: &Address) -> Result<(), Error> {
+    if escrow.depositor != *depositor {
         return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
     }
 
@@ -106,58 +130,657 @@ pub fn accept_freelancer(env: &Env, escrow_id: u32, depositor: Address, freelanc
     if escrow.status != EscrowStatus::Pending {
         return Err(Error::from_contract_error(DeCentPayError::JobClosed as u32));
     }
+    Ok(())
+}
+
+/// Whether an open job has an application the depositor can still accept
+pub fn has_pending_application(env: &Env, escrow_id: u32) -> bool {
+    get_applications(env, escrow_id)
+        .iter()
+        .any(|application| application.status == ApplicationStatus::Pending)
+}
+
+/// Make `freelancer` the escrow's beneficiary. Only a pending applicant can be hired, unless the
+/// depositor invited them, in which case they need not have applied.
+fn hire(env: &Env, escrow_id: u32, escrow: &mut EscrowData, freelancer: Address, actor: &Address, invited: bool) -> Result<(), Error> {
+    match find_application(env, escrow_id, &freelancer) {
+        Some((_, application)) if application.status != ApplicationStatus::Pending => {
+            return Err(Error::from_contract_error(DeCentPayError::ApplicationClosed as u32));
+        }
+        None if !invited => {
+            return Err(Error::from_contract_error(DeCentPayError::FreelancerNotApplied as u32));
+        }
+        _ => {}
+    }
+
+    compliance::require_not_flagged(env, &freelancer)?;
+    compliance::require_kyc(env, &freelancer, escrow.total_amount)?;
 
-    // TODO: Check if freelancer applied
+    // Hiring returns a refundable posting fee and the seriousness deposit to the depositor
+    settle_posting_fee(env, escrow_id, escrow, true)?;
+    bonds::return_seriousness_deposit(env, escrow_id, escrow)?;
+
+    // Applicants who were not hired get their staked reputation, application stake and any open
+    // counter-offer funding back
+    for application in get_applications(env, escrow_id).iter() {
+        if application.freelancer != freelancer {
+            release_reputation_stake(env, escrow_id, &application.freelancer, 0);
+            stakes::return_stake(env, escrow_id, escrow, &application.freelancer)?;
+            close_negotiation(env, escrow_id, escrow, &application.freelancer, NegotiationStatus::Declined)?;
+        }
+    }
 
     // Accept freelancer
+    if let Some((application_index, mut application)) = find_application(env, escrow_id, &freelancer) {
+        application.status = ApplicationStatus::Accepted;
+        env.storage()
+            .instance()
+            .set(&DataKey::Application(escrow_id, application_index), &application);
+    }
     escrow.beneficiary = Some(freelancer.clone());
+    stakes::start_grace_period(env, escrow_id, &freelancer);
     escrow.is_open_job = false;
 
     // Save updated escrow
-    escrow_core::save_escrow(env, escrow_id, &escrow);
+    escrow_core::save_escrow(env, escrow_id, escrow);
 
     // Add to user escrows
+    escrow_core::add_role_escrow(env, &AccountKey::BeneficiaryEscrows(freelancer.clone()), escrow_id);
     escrow_core::add_user_escrow(env, freelancer, escrow_id);
-    
+    milestone_log::record_activity(env, escrow_id, actor, ActivityAction::Hired, None);
+    Ok(())
+}
+
+/// Offer an open job directly to a freelancer, who can take it within `expires_in` seconds
+pub fn invite_freelancer(
+    env: &Env,
+    escrow_id: u32,
+    freelancer: Address,
+    expires_in: u32,
+    depositor: Address,
+) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    check_listed_job(&escrow, &depositor)?;
+
+    if freelancer == depositor {
+        return Err(Error::from_contract_error(DeCentPayError::CannotApplyToOwnJob as u32));
+    }
+
+    if expires_in == 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidDuration as u32));
+    }
+
+    let now = env.ledger().timestamp();
+    let invitation = Invitation {
+        invited_at: now,
+        expires_at: now + u64::from(expires_in),
+        accepted: false,
+    };
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::Invitation(escrow_id, freelancer), &invitation);
+    Ok(())
+}
+
+/// Take an open job the depositor invited you to, becoming its beneficiary without applying
+pub fn accept_invitation(env: &Env, escrow_id: u32, freelancer: Address) -> Result<(), Error> {
+    freelancer.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let mut invitation = check_invitation(env, escrow_id, &escrow, &freelancer)?;
+
+    invitation.accepted = true;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::Invitation(escrow_id, freelancer.clone()), &invitation);
+
+    hire(env, escrow_id, &mut escrow, freelancer.clone(), &freelancer, true)
+}
+
+/// Check a freelancer holds an open invitation to a still-listed job, returning it
+pub fn check_invitation(env: &Env, escrow_id: u32, escrow: &EscrowData, freelancer: &Address) -> Result<Invitation, Error> {
+    if !escrow.is_open_job || escrow.status != EscrowStatus::Pending {
+        return Err(Error::from_contract_error(DeCentPayError::JobClosed as u32));
+    }
+
+    let invitation = get_invitation(env, escrow_id, freelancer.clone())
+        .filter(|invitation| !invitation.accepted)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NotInvited as u32))?;
+    if env.ledger().timestamp() >= invitation.expires_at {
+        return Err(Error::from_contract_error(DeCentPayError::InvitationExpired as u32));
+    }
+    Ok(invitation)
+}
+
+/// Get the invitation a depositor sent a freelancer for an open job
+pub fn get_invitation(env: &Env, escrow_id: u32, freelancer: Address) -> Option<Invitation> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::Invitation(escrow_id, freelancer))
+}
+
+/// Close an open job that has not hired anyone, refunding the principal and any held fees and
+/// deposits, and clearing its applications
+pub fn cancel_open_job(env: &Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_valid_escrow(env, escrow_id)?;
+    escrow_core::require_not_frozen(env, escrow_id)?;
+    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    check_listed_job(&escrow, &depositor)?;
+
+    idle_yield::unwind(env, escrow_id, &escrow)?;
+    escrow.status = EscrowStatus::Cancelled;
+    finality::record_closed(env, escrow_id);
+
+    let refund_amount = escrow.total_amount - escrow.paid_amount;
+    if refund_amount > 0 {
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -refund_amount)?;
+        refund_system::return_principal(env, escrow_id, &escrow, refund_amount)?;
+    }
+    settle_posting_fee(env, escrow_id, &escrow, true)?;
+    escrow_core::refund_platform_fee(env, escrow_id, &escrow)?;
+    bonds::return_seriousness_deposit(env, escrow_id, &escrow)?;
+    dispute_resolution::refund_arbitration_fee(env, escrow_id, &escrow)?;
+    close_negotiations(env, escrow_id, &escrow)?;
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    for (app_index, application) in get_applications(env, escrow_id).iter().enumerate() {
+        release_reputation_stake(env, escrow_id, &application.freelancer, 0);
+        stakes::return_stake(env, escrow_id, &escrow, &application.freelancer)?;
+        let app_index = u32::try_from(app_index).unwrap_or(u32::MAX);
+        env.storage()
+            .instance()
+            .remove(&DataKey::Application(escrow_id, app_index));
+        env.storage()
+            .instance()
+            .remove(&EscrowKey::ApplicantIndex(escrow_id, application.freelancer));
+    }
+    env.storage()
+        .instance()
+        .remove(&EscrowKey::ApplicationCount(escrow_id));
+
+    escrow_core::save_escrow(env, escrow_id, &escrow);
+    milestone_log::record_activity(env, escrow_id, &depositor, ActivityAction::Cancelled, None);
     Ok(())
 }
 
+/// Counter-offer an applicant a new escrow total and timeline. A higher total and its platform fee
+/// are pulled from the depositor now and held until the freelancer answers.
+pub fn propose_terms(
+    env: &Env,
+    escrow_id: u32,
+    freelancer: Address,
+    new_amount: i128,
+    new_timeline: u32,
+    depositor: Address,
+) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if !escrow.is_open_job || escrow.status != EscrowStatus::Pending {
+        return Err(Error::from_contract_error(DeCentPayError::JobClosed as u32));
+    }
+
+    let (_, application) = find_application(env, escrow_id, &freelancer)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::FreelancerNotApplied as u32))?;
+    if application.status != ApplicationStatus::Pending {
+        return Err(Error::from_contract_error(DeCentPayError::ApplicationClosed as u32));
+    }
+
+    if new_amount <= 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    // A revised offer replaces the previous one
+    close_negotiation(env, escrow_id, &escrow, &freelancer, NegotiationStatus::Declined)?;
+
+    let held_top_up = (new_amount - escrow.total_amount).max(0)
+        + escrow_core::platform_fee_top_up(env, escrow_id, &escrow, new_amount);
+    if held_top_up > 0 {
+        escrow_core::transfer(env, escrow.token.as_ref(), &depositor, &env.current_contract_address(), held_top_up);
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), held_top_up)?;
+    }
+
+    let negotiation = Negotiation {
+        proposed_amount: new_amount,
+        proposed_timeline: new_timeline,
+        held_top_up,
+        proposed_at: env.ledger().timestamp(),
+        status: NegotiationStatus::Proposed,
+    };
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::Negotiation(escrow_id, freelancer), &negotiation);
+    Ok(())
+}
+
+/// Accept the client's counter-offer, rescaling the milestones to the new total and hiring the freelancer
+pub fn accept_terms(env: &Env, escrow_id: u32, freelancer: Address) -> Result<(), Error> {
+    freelancer.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let (mut negotiation, application_index, mut application) = check_terms(env, escrow_id, &escrow, &freelancer)?;
+
+    // Any cut in the total goes back to the depositor; a raise and its fee were pulled at proposal
+    let raise = (negotiation.proposed_amount - escrow.total_amount).max(0);
+    let refund = escrow.total_amount - negotiation.proposed_amount;
+    if refund > 0 {
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -refund)?;
+        escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &escrow.depositor, refund);
+    }
+
+    let old_total = escrow.total_amount;
+    let mut allocated = 0;
+    for milestone_index in 0..escrow.milestone_count {
+        if let Some(mut milestone) = work_lifecycle::get_milestone(env, escrow_id, milestone_index) {
+            milestone.amount = if milestone_index + 1 == escrow.milestone_count {
+                negotiation.proposed_amount - allocated
+            } else {
+                milestone.amount * negotiation.proposed_amount / old_total
+            };
+            allocated += milestone.amount;
+            env.storage()
+                .instance()
+                .set(&DataKey::Milestone(escrow_id, milestone_index), &milestone);
+        }
+    }
+    escrow.total_amount = negotiation.proposed_amount;
+    escrow_core::reprice_platform_fee(env, escrow_id, &mut escrow, negotiation.held_top_up - raise)?;
+
+    application.proposed_timeline = negotiation.proposed_timeline;
+    env.storage()
+        .instance()
+        .set(&DataKey::Application(escrow_id, application_index), &application);
+
+    negotiation.status = NegotiationStatus::Accepted;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::Negotiation(escrow_id, freelancer.clone()), &negotiation);
+
+    hire(env, escrow_id, &mut escrow, freelancer.clone(), &freelancer, false)
+}
+
+/// Check a freelancer has an open counter-offer on a pending application to a still-listed job,
+/// returning the offer and the application with its slot
+pub fn check_terms(env: &Env, escrow_id: u32, escrow: &EscrowData, freelancer: &Address) -> Result<(Negotiation, u32, Application), Error> {
+    if !escrow.is_open_job || escrow.status != EscrowStatus::Pending {
+        return Err(Error::from_contract_error(DeCentPayError::JobClosed as u32));
+    }
+
+    let negotiation = get_open_negotiation(env, escrow_id, freelancer)?;
+    let (application_index, application) = find_application(env, escrow_id, freelancer)
+        .filter(|(_, application)| application.status == ApplicationStatus::Pending)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::ApplicationClosed as u32))?;
+    Ok((negotiation, application_index, application))
+}
+
+/// Decline the client's counter-offer, returning any extra funding it held
+pub fn decline_terms(env: &Env, escrow_id: u32, freelancer: Address) -> Result<(), Error> {
+    freelancer.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    get_open_negotiation(env, escrow_id, &freelancer)?;
+    close_negotiation(env, escrow_id, &escrow, &freelancer, NegotiationStatus::Declined)?;
+    Ok(())
+}
+
+/// Get the counter-offer made on a freelancer's application
+pub fn get_negotiation(env: &Env, escrow_id: u32, freelancer: Address) -> Option<Negotiation> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::Negotiation(escrow_id, freelancer))
+}
+
+/// Close every open counter-offer on a job, returning the funding they held
+pub fn close_negotiations(env: &Env, escrow_id: u32, escrow: &EscrowData) -> Result<(), Error> {
+    for application in get_applications(env, escrow_id).iter() {
+        close_negotiation(env, escrow_id, escrow, &application.freelancer, NegotiationStatus::Declined)?;
+    }
+    Ok(())
+}
+
+fn get_open_negotiation(env: &Env, escrow_id: u32, freelancer: &Address) -> Result<Negotiation, Error> {
+    get_negotiation(env, escrow_id, freelancer.clone())
+        .filter(|negotiation| negotiation.status == NegotiationStatus::Proposed)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoNegotiation as u32))
+}
+
+/// Close an open counter-offer, returning the funding it held to the depositor
+fn close_negotiation(env: &Env, escrow_id: u32, escrow: &EscrowData, freelancer: &Address, status: NegotiationStatus) -> Result<(), Error> {
+    let Ok(mut negotiation) = get_open_negotiation(env, escrow_id, freelancer) else {
+        return Ok(());
+    };
+
+    if negotiation.held_top_up > 0 {
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -negotiation.held_top_up)?;
+        escrow_core::transfer(
+            env,
+            escrow.token.as_ref(),
+            &env.current_contract_address(),
+            &escrow.depositor,
+            negotiation.held_top_up,
+        );
+    }
+    negotiation.status = status;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::Negotiation(escrow_id, freelancer.clone()), &negotiation);
+    Ok(())
+}
+
+/// Retract an application before being hired, freeing its slot for another applicant
+pub fn withdraw_application(env: &Env, escrow_id: u32, freelancer: Address) -> Result<(), Error> {
+    freelancer.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.beneficiary.as_ref() == Some(&freelancer) {
+        return Err(Error::from_contract_error(DeCentPayError::FreelancerAlreadyAccepted as u32));
+    }
+
+    let (application_index, _) = find_application(env, escrow_id, &freelancer)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::FreelancerNotApplied as u32))?;
+
+    // Move the last application into the freed slot to keep slots packed
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    let last_index = get_application_count(env, escrow_id) - 1;
+    if application_index != last_index {
+        if let Some(last) = env
+            .storage()
+            .instance()
+            .get::<DataKey, Application>(&DataKey::Application(escrow_id, last_index))
+        {
+            env.storage()
+                .instance()
+                .set(&EscrowKey::ApplicantIndex(escrow_id, last.freelancer.clone()), &application_index);
+            env.storage()
+                .instance()
+                .set(&DataKey::Application(escrow_id, application_index), &last);
+        }
+    }
+    env.storage()
+        .instance()
+        .remove(&DataKey::Application(escrow_id, last_index));
+    env.storage()
+        .instance()
+        .remove(&EscrowKey::ApplicantIndex(escrow_id, freelancer.clone()));
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ApplicationCount(escrow_id), &last_index);
+    release_reputation_stake(env, escrow_id, &freelancer, 0);
+    stakes::return_stake(env, escrow_id, &escrow, &freelancer)?;
+    close_negotiation(env, escrow_id, &escrow, &freelancer, NegotiationStatus::Declined)?;
+
+    ApplicationWithdrawn { escrow_id, freelancer }.publish(env);
+    Ok(())
+}
+
+/// Decline an application with a reason the freelancer can read back from `get_application`
+pub fn reject_application(env: &Env, escrow_id: u32, freelancer: Address, reason: String, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    let (application_index, mut application) = find_application(env, escrow_id, &freelancer)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::FreelancerNotApplied as u32))?;
+    if application.status != ApplicationStatus::Pending {
+        return Err(Error::from_contract_error(DeCentPayError::ApplicationClosed as u32));
+    }
+
+    application.status = ApplicationStatus::Rejected;
+    application.rejection_reason = Some(reason);
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::Application(escrow_id, application_index), &application);
+    release_reputation_stake(env, escrow_id, &freelancer, 0);
+    stakes::return_stake(env, escrow_id, &escrow, &freelancer)?;
+    close_negotiation(env, escrow_id, &escrow, &freelancer, NegotiationStatus::Declined)?;
+    Ok(())
+}
+
+/// Collect the configured posting fee when an open job is created.
+/// Non-refundable fees go straight to the fee collector; refundable ones are held until hire.
+/// Without `collect` the fee was already paid into the contract (by a batch).
+pub fn collect_posting_fee(
+    env: &Env,
+    escrow_id: u32,
+    depositor: &Address,
+    token: Option<&Address>,
+    collect: bool,
+) -> Result<(), Error> {
+    let posting_fee = admin::get_posting_fee(env, token.cloned());
+    if posting_fee <= 0 {
+        return Ok(());
+    }
+
+    if admin::is_posting_fee_refundable(env) {
+        if collect {
+            escrow_core::transfer(env, token, depositor, &env.current_contract_address(), posting_fee);
+        }
+        escrow_core::update_escrowed_amount(env, token, posting_fee)?;
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .instance()
+            .set(&DataKey::PostingFeeHeld(escrow_id), &posting_fee);
+    } else {
+        let fee_collector = admin::get_fee_collector(env)?;
+        let payer = if collect { depositor.clone() } else { env.current_contract_address() };
+        escrow_core::transfer(env, token, &payer, &fee_collector, posting_fee);
+    }
+
+    Ok(())
+}
+
+/// Release a held posting fee: back to the depositor on hire, to the fee collector otherwise
+pub fn settle_posting_fee(env: &Env, escrow_id: u32, escrow: &EscrowData, hired: bool) -> Result<(), Error> {
+    let key = DataKey::PostingFeeHeld(escrow_id);
+    let Some(held) = env.storage().instance().get::<DataKey, i128>(&key) else {
+        return Ok(());
+    };
+
+    let recipient = if hired {
+        escrow.depositor.clone()
+    } else {
+        admin::get_fee_collector(env)?
+    };
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().remove(&key);
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -held)?;
+    escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &recipient, held);
+
+    Ok(())
+}
+
+/// Get the posting fee still held for an open job
+pub fn get_posting_fee_held(env: &Env, escrow_id: u32) -> i128 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&DataKey::PostingFeeHeld(escrow_id))
+        .unwrap_or(0)
+}
+
 /// Check if a freelancer has applied to a job
 pub fn has_applied(env: &Env, escrow_id: u32, freelancer: Address) -> bool {
     env.storage()
         .instance()
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-    
-    // Check all possible application indices (0 to MAX_APPLICATIONS - 1)
-    for app_index in 0..MAX_APPLICATIONS {
-        let key = DataKey::Application(escrow_id, app_index);
-        if let Some(application) = env.storage().instance().get::<DataKey, Application>(&key) {
-            if application.freelancer == freelancer {
-                return true;
-            }
-        }
+    env.storage()
+        .instance()
+        .has(&EscrowKey::ApplicantIndex(escrow_id, freelancer))
+}
+
+/// Stop a new open job taking applications `application_period` seconds after it is posted
+pub fn set_application_deadline(env: &Env, escrow_id: u32, escrow: &EscrowData, application_period: u64) -> Result<(), Error> {
+    if !escrow.is_open_job {
+        return Err(Error::from_contract_error(DeCentPayError::NotOpenJob as u32));
     }
-    
-    false
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(
+        &EscrowKey::ApplicationDeadline(escrow_id),
+        &(env.ledger().timestamp() + application_period),
+    );
+    Ok(())
+}
+
+/// Get the unix timestamp an open job stops taking applications at
+pub fn get_application_deadline(env: &Env, escrow_id: u32) -> Option<u64> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::ApplicationDeadline(escrow_id))
+}
+
+/// Delist an open job that passed its application deadline (or its escrow deadline, if it has
+/// none) without hiring anyone, so the depositor can refund it. Callable by anyone.
+pub fn expire_open_job(env: &Env, escrow_id: u32) -> Result<(), Error> {
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    check_expirable(env, escrow_id, &escrow)?;
+
+    escrow.status = EscrowStatus::Expired;
+    close_negotiations(env, escrow_id, &escrow)?;
+    release_all_reputation_stakes(env, escrow_id);
+    stakes::return_all_stakes(env, escrow_id, &escrow)?;
+    escrow_core::save_escrow(env, escrow_id, &escrow);
+    milestone_log::record_activity(env, escrow_id, &env.current_contract_address(), ActivityAction::Expired, None);
+    Ok(())
+}
+
+/// Check an open job is still listed past its application deadline, or its escrow deadline if it has none
+pub fn check_expirable(env: &Env, escrow_id: u32, escrow: &EscrowData) -> Result<(), Error> {
+    if !escrow.is_open_job {
+        return Err(Error::from_contract_error(DeCentPayError::NotOpenJob as u32));
+    }
+
+    if escrow.status != EscrowStatus::Pending {
+        return Err(Error::from_contract_error(DeCentPayError::JobClosed as u32));
+    }
+
+    let closes_at = get_application_deadline(env, escrow_id).unwrap_or(escrow.deadline);
+    if env.ledger().timestamp() < closes_at {
+        return Err(Error::from_contract_error(DeCentPayError::JobNotExpired as u32));
+    }
+    Ok(())
+}
+
+/// Restrict who can apply to a newly created open job by reputation and badge
+pub fn set_application_requirements(
+    env: &Env,
+    escrow_id: u32,
+    escrow: &EscrowData,
+    requirements: ApplicationRequirements,
+) -> Result<(), Error> {
+    if !escrow.is_open_job {
+        return Err(Error::from_contract_error(DeCentPayError::NotOpenJob as u32));
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ApplicationRequirements(escrow_id), &requirements);
+    Ok(())
+}
+
+/// Get the thresholds a freelancer must meet to apply to a job
+pub fn get_application_requirements(env: &Env, escrow_id: u32) -> Option<ApplicationRequirements> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::ApplicationRequirements(escrow_id))
+}
+
+/// Get how many applications a job currently holds
+pub fn get_application_count(env: &Env, escrow_id: u32) -> u32 {
+    env.storage()
+        .instance()
+        .get(&EscrowKey::ApplicationCount(escrow_id))
+        .unwrap_or(0)
 }
 
 /// Get an application by escrow_id and freelancer
 pub fn get_application(env: &Env, escrow_id: u32, freelancer: Address) -> Option<Application> {
+    find_application(env, escrow_id, &freelancer).map(|(_, application)| application)
+}
+
+/// Find a freelancer's application along with the slot it is stored in
+fn find_application(env: &Env, escrow_id: u32, freelancer: &Address) -> Option<(u32, Application)> {
     env.storage()
         .instance()
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-    
-    // Check all possible application indices
-    for app_index in 0..MAX_APPLICATIONS {
-        let key = DataKey::Application(escrow_id, app_index);
-        if let Some(application) = env.storage().instance().get::<DataKey, Application>(&key) {
-            if application.freelancer == freelancer {
-                return Some(application);
-            }
-        }
-    }
-    
-    None
+    let app_index: u32 = env
+        .storage()
+        .instance()
+        .get(&EscrowKey::ApplicantIndex(escrow_id, freelancer.clone()))?;
+    let application = env
+        .storage()
+        .instance()
+        .get::<DataKey, Application>(&DataKey::Application(escrow_id, app_index))?;
+    Some((app_index, application))
 }
 
 /// Get all applications for an escrow
@@ -168,8 +791,7 @@ pub fn get_applications(env: &Env, escrow_id: u32) -> Vec<Application> {
     
     let mut applications = Vec::new(env);
     
-    // Check all possible application indices
-    for app_index in 0..MAX_APPLICATIONS {
+    for app_index in 0..get_application_count(env, escrow_id) {
         let key = DataKey::Application(escrow_id, app_index);
         if let Some(application) = env.storage().instance().get::<DataKey, Application>(&key) {
             applications.push_back(application);
@@ -179,3 +801,136 @@ pub fn get_applications(env: &Env, escrow_id: u32) -> Vec<Application> {
     applications
 }
 
+
+/// Bookmark an open job in the user's watchlist
+pub fn watch_escrow(env: &Env, escrow_id: u32, user: Address) -> Result<(), Error> {
+    user.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if !escrow.is_open_job {
+        return Err(Error::from_contract_error(DeCentPayError::NotOpenJob as u32));
+    }
+
+    let mut watchlist = get_watchlist(env, user.clone());
+    if watchlist.contains(escrow_id) {
+        return Err(Error::from_contract_error(DeCentPayError::AlreadyWatching as u32));
+    }
+    if watchlist.len() >= MAX_WATCHLIST {
+        return Err(Error::from_contract_error(DeCentPayError::WatchlistFull as u32));
+    }
+
+    watchlist.push_back(escrow_id);
+    env.storage()
+        .instance()
+        .set(&DataKey::Watchlist(user), &watchlist);
+    Ok(())
+}
+
+/// Remove a job from the user's watchlist
+pub fn unwatch_escrow(env: &Env, escrow_id: u32, user: Address) -> Result<(), Error> {
+    user.require_auth();
+
+    let mut watchlist = get_watchlist(env, user.clone());
+    let index = watchlist
+        .first_index_of(escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NotWatching as u32))?;
+
+    watchlist.remove(index);
+    env.storage()
+        .instance()
+        .set(&DataKey::Watchlist(user), &watchlist);
+    Ok(())
+}
+
+/// Get the escrows saved in a user's watchlist
+pub fn get_watchlist(env: &Env, user: Address) -> Vec<u32> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&DataKey::Watchlist(user))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Stake reputation points on an application as a guarantee of a serious bid
+pub fn stake_reputation(env: &Env, escrow_id: u32, freelancer: Address, points: u32) -> Result<(), Error> {
+    freelancer.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if !escrow.is_open_job || escrow.status != EscrowStatus::Pending {
+        return Err(Error::from_contract_error(DeCentPayError::JobClosed as u32));
+    }
+
+    if points == 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    if !has_applied(env, escrow_id, freelancer.clone()) {
+        return Err(Error::from_contract_error(DeCentPayError::FreelancerNotApplied as u32));
+    }
+
+    let key = DataKey::ReputationStake(escrow_id, freelancer.clone());
+    if env.storage().instance().has(&key) {
+        return Err(Error::from_contract_error(DeCentPayError::AlreadyStaked as u32));
+    }
+
+    let reputation = escrow_core::get_reputation(env, freelancer.clone());
+    if reputation < points {
+        return Err(Error::from_contract_error(DeCentPayError::InsufficientReputation as u32));
+    }
+
+    // Staked points are locked out of the freelancer's reputation until settled
+    escrow_core::set_reputation(env, freelancer, reputation - points);
+    env.storage().instance().set(&key, &points);
+    Ok(())
+}
+
+/// Get the reputation points a freelancer staked on an application
+pub fn get_reputation_stake(env: &Env, escrow_id: u32, freelancer: Address) -> u32 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&DataKey::ReputationStake(escrow_id, freelancer))
+        .unwrap_or(0)
+}
+
+/// Return staked reputation to a freelancer, plus `bonus_bp` basis points of the stake
+pub fn release_reputation_stake(env: &Env, escrow_id: u32, freelancer: &Address, bonus_bp: u32) {
+    let key = DataKey::ReputationStake(escrow_id, freelancer.clone());
+    let Some(points) = env.storage().instance().get::<DataKey, u32>(&key) else {
+        return;
+    };
+    env.storage().instance().remove(&key);
+
+    let bonus = points * bonus_bp / 10000;
+    let reputation = escrow_core::get_reputation(env, freelancer.clone());
+    escrow_core::set_reputation(env, freelancer.clone(), reputation + points + bonus);
+}
+
+/// Return every applicant's staked reputation that has not been settled yet
+pub fn release_all_reputation_stakes(env: &Env, escrow_id: u32) {
+    for application in get_applications(env, escrow_id).iter() {
+        release_reputation_stake(env, escrow_id, &application.freelancer, 0);
+    }
+}
+
+/// Return staked reputation with the completion bonus
+pub fn reward_reputation_stake(env: &Env, escrow_id: u32, freelancer: &Address) {
+    release_reputation_stake(env, escrow_id, freelancer, STAKE_BONUS_BP);
+}
+
+/// Burn staked reputation after abandonment or a lost dispute
+pub fn burn_reputation_stake(env: &Env, escrow_id: u32, freelancer: &Address) {
+    env.storage()
+        .instance()
+        .remove(&DataKey::ReputationStake(escrow_id, freelancer.clone()));
+}
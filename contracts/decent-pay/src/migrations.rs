@@ -0,0 +1,502 @@
+use crate::admin;
+use crate::categories;
+use crate::escrow_core;
+use crate::marketplace;
+use crate::reputation;
+use crate::team;
+use crate::storage_types::{
+    AccountKey, Achievement, Application, ApplicationStatus, ApplicationV1, ConfigKey, DataKey, DeCentPayError, EscrowData, EscrowDataV1, EscrowKey,
+    EscrowStatus, JobCategory, ListingIndex, Milestone, MilestoneV1, Rating, RatingV1, RatingV2, CONTRACT_VERSION, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+    SECONDS_PER_LEDGER,
+};
+use soroban_sdk::{Address, Env, Error, IntoVal, Map, Symbol, TryFromVal, Val, Vec};
+
+type Convert = fn(&Env, u32) -> u64;
+
+const MIGRATION_BATCH_SIZE: u32 = 10;
+
+// Field counts of the version 1 layouts, used to tell them apart from records already migrated
+const ESCROW_V1_FIELDS: u32 = 16;
+const MILESTONE_V1_FIELDS: u32 = 9;
+const APPLICATION_V1_FIELDS: u32 = 4;
+const RATING_V1_FIELDS: u32 = 6;
+const RATING_V2_FIELDS: u32 = 7;
+
+/// Convert the records of the next batch of escrows from the `from_version` layout to `to_version`.
+/// The stored version only moves once every escrow has been converted, so the owner calls this
+/// repeatedly after an upgrade until it returns true.
+pub fn migrate(env: &Env, from_version: u32, to_version: u32) -> Result<bool, Error> {
+    admin::require_owner(env)?;
+    if from_version != admin::get_version(env) || to_version != CONTRACT_VERSION || from_version >= to_version {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidVersion as u32));
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    let next_escrow_id = escrow_core::get_next_escrow_id(env);
+    let start: u32 = env.storage().instance().get(&ConfigKey::MigrationCursor).unwrap_or(1);
+    let batch_end = (start + MIGRATION_BATCH_SIZE).min(next_escrow_id);
+    if start == 1 {
+        for version in from_version..to_version {
+            migrate_config(env, version);
+        }
+    }
+    for escrow_id in start..batch_end {
+        for version in from_version..to_version {
+            migrate_escrow(env, version, escrow_id);
+        }
+    }
+
+    if batch_end >= next_escrow_id {
+        env.storage().instance().remove(&ConfigKey::MigrationCursor);
+        env.storage().instance().set(&ConfigKey::Version, &to_version);
+        Ok(true)
+    } else {
+        env.storage().instance().set(&ConfigKey::MigrationCursor, &batch_end);
+        Ok(false)
+    }
+}
+
+/// Upgrade one escrow's records from `version` to `version + 1`
+fn migrate_escrow(env: &Env, version: u32, escrow_id: u32) {
+    match version {
+        1 => migrate_escrow_v1(env, escrow_id),
+        2 => migrate_escrow_v2(env, escrow_id),
+        3 => migrate_escrow_v3(env, escrow_id),
+        4 => migrate_escrow_v4(env, escrow_id),
+        5 => migrate_escrow_v5(env, escrow_id),
+        6 => migrate_escrow_v6(env, escrow_id),
+        _ => {}
+    }
+}
+
+/// Upgrade the platform-wide settings from `version` to `version + 1`
+fn migrate_config(env: &Env, version: u32) {
+    // Version 5 counted the evidence, finality and rating edit windows in ledgers
+    if version == 5 {
+        retime_value(env, &ConfigKey::EvidenceWindow, ledgers_to_seconds);
+        retime_value(env, &ConfigKey::FinalityWindow, ledgers_to_seconds);
+        retime_value(env, &ConfigKey::RatingEditWindow, ledgers_to_seconds);
+    }
+    // Version 6 kept the listing indexes as single instance entries; each escrow's migration
+    // files it into the persistent pages instead
+    if version == 6 {
+        for status in [
+            EscrowStatus::Pending,
+            EscrowStatus::InProgress,
+            EscrowStatus::Released,
+            EscrowStatus::Refunded,
+            EscrowStatus::Disputed,
+            EscrowStatus::Expired,
+            EscrowStatus::Cancelled,
+            EscrowStatus::Funding,
+        ] {
+            env.storage().instance().remove(&ConfigKey::StatusIndex(status));
+        }
+        env.storage().instance().remove(&ConfigKey::OpenJobIndex);
+        for category in [
+            JobCategory::SmartContracts,
+            JobCategory::Development,
+            JobCategory::Design,
+            JobCategory::Writing,
+            JobCategory::Marketing,
+            JobCategory::Consulting,
+            JobCategory::Other,
+            JobCategory::Uncategorized,
+        ] {
+            env.storage().instance().remove(&ConfigKey::CategoryIndex(category));
+        }
+    }
+}
+
+fn migrate_escrow_v1(env: &Env, escrow_id: u32) {
+    let Some(legacy) = read_legacy::<EscrowDataV1>(env, &DataKey::Escrow(escrow_id), ESCROW_V1_FIELDS) else {
+        return;
+    };
+
+    for milestone_index in 0..legacy.milestone_count {
+        let key = DataKey::Milestone(escrow_id, milestone_index);
+        if let Some(milestone) = read_legacy::<MilestoneV1>(env, &key, MILESTONE_V1_FIELDS) {
+            let milestone = Milestone {
+                description: milestone.description,
+                amount: milestone.amount,
+                status: milestone.status,
+                submitted_at: ledger_to_timestamp(env, milestone.submitted_at),
+                approved_at: ledger_to_timestamp(env, milestone.approved_at),
+                disputed_at: ledger_to_timestamp(env, milestone.disputed_at),
+                disputed_by: milestone.disputed_by,
+                dispute_reason: milestone.dispute_reason,
+                rejection_reason: milestone.rejection_reason,
+                completed_by_client: false,
+                manifest: Vec::new(env),
+                released: 0,
+                due_at: 0,
+            };
+            env.storage().instance().set(&key, &milestone);
+        }
+    }
+
+    // Version 1 left gaps between application slots; pack them and index them by applicant
+    let mut application_count = 0u32;
+    for slot in 0..marketplace::MAX_APPLICATIONS {
+        let key = DataKey::Application(escrow_id, slot);
+        if let Some(application) = read_legacy::<ApplicationV1>(env, &key, APPLICATION_V1_FIELDS) {
+            let status = if legacy.beneficiary.as_ref() == Some(&application.freelancer) {
+                ApplicationStatus::Accepted
+            } else {
+                ApplicationStatus::Pending
+            };
+            env.storage().instance().remove(&key);
+            env.storage().instance().set(
+                &EscrowKey::ApplicantIndex(escrow_id, application.freelancer.clone()),
+                &application_count,
+            );
+            env.storage().instance().set(
+                &DataKey::Application(escrow_id, application_count),
+                &Application {
+                    freelancer: application.freelancer,
+                    cover_letter: application.cover_letter,
+                    proposed_timeline: application.proposed_timeline,
+                    applied_at: ledger_to_timestamp(env, application.applied_at),
+                    status,
+                    rejection_reason: None,
+                },
+            );
+            application_count += 1;
+        }
+    }
+    if application_count > 0 {
+        env.storage()
+            .instance()
+            .set(&EscrowKey::ApplicationCount(escrow_id), &application_count);
+    }
+
+    if let Some(rating) = read_legacy::<RatingV1>(env, &DataKey::Rating(escrow_id), RATING_V1_FIELDS) {
+        env.storage().instance().set(
+            &DataKey::Rating(escrow_id),
+            &Rating {
+                escrow_id: rating.escrow_id,
+                freelancer: rating.freelancer,
+                client: rating.client,
+                rating: rating.rating,
+                review: rating.review,
+                review_hash: None,
+                rated_at: ledger_to_timestamp(env, rating.rated_at),
+                edited_at: 0,
+                response: None,
+            },
+        );
+    }
+
+    // Saved as a new escrow so it also joins the listing indexes
+    env.storage().instance().remove(&DataKey::Escrow(escrow_id));
+    escrow_core::save_escrow(
+        env,
+        escrow_id,
+        &EscrowData {
+            depositor: legacy.depositor,
+            beneficiary: legacy.beneficiary,
+            arbiters: legacy.arbiters,
+            required_confirmations: legacy.required_confirmations,
+            token: legacy.token,
+            total_amount: legacy.total_amount,
+            paid_amount: legacy.paid_amount,
+            platform_fee: legacy.platform_fee,
+            deadline: ledger_to_timestamp(env, legacy.deadline),
+            status: legacy.status,
+            work_started: legacy.work_started,
+            created_at: ledger_to_timestamp(env, legacy.created_at),
+            milestone_count: legacy.milestone_count,
+            is_open_job: legacy.is_open_job,
+            project_title: legacy.project_title,
+            project_description: legacy.project_description,
+            bonus_paid: 0,
+        },
+    );
+}
+
+// Version 2 kept the escrow deadline and milestone due dates as ledger sequence numbers
+fn migrate_escrow_v2(env: &Env, escrow_id: u32) {
+    let Some(milestone_count) = retime_field(env, &DataKey::Escrow(escrow_id), "deadline", ledger_to_timestamp) else {
+        return;
+    };
+    for milestone_index in 0..milestone_count {
+        retime_field(env, &DataKey::Milestone(escrow_id, milestone_index), "due_at", ledger_to_timestamp);
+    }
+}
+
+// Version 3 had no per-role escrow indexes
+fn migrate_escrow_v3(env: &Env, escrow_id: u32) {
+    let Some((depositor, beneficiary, _)) = read_escrow_parties(env, escrow_id) else {
+        return;
+    };
+    escrow_core::add_role_escrow(env, &AccountKey::DepositorEscrows(depositor), escrow_id);
+    if let Some(beneficiary) = beneficiary {
+        escrow_core::add_role_escrow(env, &AccountKey::BeneficiaryEscrows(beneficiary), escrow_id);
+    }
+}
+
+// Version 4 ratings could not be edited or answered
+fn migrate_escrow_v4(env: &Env, escrow_id: u32) {
+    if let Some(rating) = read_legacy::<RatingV2>(env, &DataKey::Rating(escrow_id), RATING_V2_FIELDS) {
+        env.storage().instance().set(
+            &DataKey::Rating(escrow_id),
+            &Rating {
+                escrow_id: rating.escrow_id,
+                freelancer: rating.freelancer,
+                client: rating.client,
+                rating: rating.rating,
+                review: rating.review,
+                review_hash: rating.review_hash,
+                rated_at: ledger_to_timestamp(env, rating.rated_at),
+                edited_at: 0,
+                response: None,
+            },
+        );
+    }
+}
+
+// Version 5 stamped milestones, vesting, closing, ratings and reputation with ledger sequence numbers,
+// counted review, warranty, vesting, retainer, stake and escalation windows in ledgers, and kept
+// reputation slashes by ledger, a preferred token in payout preferences and a removed-ratings count in adverse records
+fn migrate_escrow_v5(env: &Env, escrow_id: u32) {
+    let Some((depositor, beneficiary, milestone_count)) = read_escrow_parties(env, escrow_id) else {
+        return;
+    };
+
+    for milestone_index in 0..milestone_count {
+        let key = DataKey::Milestone(escrow_id, milestone_index);
+        retime_field(env, &key, "submitted_at", ledger_to_timestamp);
+        retime_field(env, &key, "approved_at", ledger_to_timestamp);
+        retime_field(env, &key, "disputed_at", ledger_to_timestamp);
+
+        let stream = EscrowKey::VestingStream(escrow_id, milestone_index);
+        retime_field(env, &stream, "start", ledger_to_timestamp);
+        retime_field(env, &stream, "duration", ledgers_to_seconds);
+        retime_value(env, &EscrowKey::ScheduledRelease(escrow_id, milestone_index), ledger_to_timestamp);
+
+        retime_list(env, &EscrowKey::SubmissionHistory(escrow_id, milestone_index), "submitted_at", ledger_to_timestamp);
+    }
+
+    retime_field(env, &EscrowKey::Retainage(escrow_id), "warranty_period", ledgers_to_seconds);
+    retime_field(env, &EscrowKey::Retainage(escrow_id), "release_at", ledger_to_timestamp);
+    retime_field(env, &EscrowKey::StandingApproval(escrow_id), "delay", ledgers_to_seconds);
+    retime_field(env, &EscrowKey::Retainer(escrow_id), "period", ledgers_to_seconds);
+    retime_field(env, &EscrowKey::Retainer(escrow_id), "started_at", ledger_to_timestamp);
+    retime_field(env, &EscrowKey::Retainer(escrow_id), "paused_at", ledger_to_timestamp);
+    retime_field(env, &EscrowKey::ApplicationStake(escrow_id), "grace_period", ledgers_to_seconds);
+    retime_field(env, &EscrowKey::Escalation(escrow_id), "respond_by", ledger_to_timestamp);
+    retime_value(env, &EscrowKey::StreamingDuration(escrow_id), ledgers_to_seconds);
+    retime_value(env, &EscrowKey::ReviewPeriod(escrow_id), ledgers_to_seconds);
+    retime_value(env, &EscrowKey::ClosedAt(escrow_id), ledger_to_timestamp);
+    retime_value(env, &EscrowKey::StakeStartBy(escrow_id), ledger_to_timestamp);
+    retime_field(env, &DataKey::Rating(escrow_id), "rated_at", ledger_to_timestamp);
+    retime_field(env, &DataKey::Rating(escrow_id), "edited_at", ledger_to_timestamp);
+
+    // Accounts are only reachable through their escrows; records already converted are skipped
+    let mut accounts = Vec::new(env);
+    accounts.push_back(depositor);
+    if let Some(beneficiary) = beneficiary {
+        accounts.append(&team::members(env, escrow_id, &beneficiary));
+    }
+    for account in accounts.iter() {
+        retime_field(env, &AccountKey::RecentReputation(account.clone()), "updated_at", ledger_to_timestamp);
+        retime_list(env, &AccountKey::ReputationSlashes(account.clone()), "slashed_at", ledger_to_timestamp);
+
+        // Payout preferences no longer carry a preferred token, nor adverse records a count of
+        // removed ratings that nothing ever recorded
+        strip_field(env, &DataKey::PayoutPreferences(account.clone()), "preferred_token");
+        strip_field(env, &DataKey::AdverseRecord(account), "ratings_removed");
+    }
+}
+
+// Version 6 stamped escrow creation, applications, counter-offers, evidence, checkpoints, comments,
+// change orders, defect claims, yield deposits, activity, endorsements and achievements with ledger
+// sequence numbers, and left emergency-refunded escrows Expired with nothing marking them as paid out
+fn migrate_escrow_v6(env: &Env, escrow_id: u32) {
+    if retime_field(env, &DataKey::Escrow(escrow_id), "created_at", ledger_to_timestamp).is_none() {
+        return;
+    }
+    let Some(escrow) = escrow_core::get_escrow(env, escrow_id) else {
+        return;
+    };
+
+    for slot in 0..marketplace::get_application_count(env, escrow_id) {
+        retime_field(env, &DataKey::Application(escrow_id, slot), "applied_at", ledger_to_timestamp);
+    }
+    for application in marketplace::get_applications(env, escrow_id).iter() {
+        retime_field(env, &EscrowKey::Negotiation(escrow_id, application.freelancer), "proposed_at", ledger_to_timestamp);
+    }
+    for milestone_index in 0..escrow.milestone_count {
+        let evidence_key = EscrowKey::DisputeEvidence(escrow_id, milestone_index);
+        retime_list(env, &evidence_key, "submitted_at", ledger_to_timestamp);
+        move_to_persistent(env, &evidence_key);
+        let checkpoints_key = EscrowKey::Checkpoints(escrow_id, milestone_index);
+        retime_list(env, &checkpoints_key, "posted_at", ledger_to_timestamp);
+        move_to_persistent(env, &checkpoints_key);
+        let comments_key = EscrowKey::MilestoneComments(escrow_id, milestone_index);
+        retime_list(env, &comments_key, "posted_at", ledger_to_timestamp);
+        move_to_persistent(env, &comments_key);
+        move_to_persistent(env, &EscrowKey::SubmissionHistory(escrow_id, milestone_index));
+    }
+    let order_count: u32 = env.storage().instance().get(&EscrowKey::ChangeOrderCount(escrow_id)).unwrap_or(0);
+    for order_id in 0..order_count {
+        retime_field(env, &EscrowKey::ChangeOrder(escrow_id, order_id), "created_at", ledger_to_timestamp);
+        retime_field(env, &EscrowKey::ChangeOrder(escrow_id, order_id), "executed_at", ledger_to_timestamp);
+    }
+    retime_field(env, &EscrowKey::DefectClaim(escrow_id), "opened_at", ledger_to_timestamp);
+    retime_field(env, &EscrowKey::YieldPosition(escrow_id), "deposited_at", ledger_to_timestamp);
+
+    // Activity entries stamped a `ledger`; they now carry `recorded_at`
+    let activity_count: u32 = env.storage().instance().get(&EscrowKey::ActivityCount(escrow_id)).unwrap_or(0);
+    for entry_index in 0..activity_count {
+        let key = EscrowKey::Activity(escrow_id, entry_index);
+        if let Some(mut raw) = env.storage().instance().get::<EscrowKey, Map<Symbol, Val>>(&key) {
+            if let Some(ledger) = raw.remove(Symbol::new(env, "ledger")).and_then(|value| u32::try_from_val(env, &value).ok()) {
+                raw.set(Symbol::new(env, "recorded_at"), ledger_to_timestamp(env, ledger).into_val(env));
+                env.storage().instance().set(&key, &raw);
+            }
+        }
+        move_to_persistent(env, &key);
+    }
+    move_to_persistent(env, &EscrowKey::ActivityCount(escrow_id));
+
+    escrow_core::update_index(env, &ListingIndex::Status(escrow.status.clone()), escrow_id, true);
+    if escrow_core::is_listed_open_job(&escrow) {
+        escrow_core::update_index(env, &ListingIndex::OpenJobs, escrow_id, true);
+    }
+    if let Some(category) = categories::get_job_category(env, escrow_id) {
+        escrow_core::update_index(env, &ListingIndex::Category(category), escrow_id, true);
+    }
+
+    let mut accounts = escrow.arbiters.clone();
+    accounts.push_back(escrow.depositor.clone());
+    if let Some(beneficiary) = &escrow.beneficiary {
+        accounts.append(&team::members(env, escrow_id, beneficiary));
+    }
+    for account in accounts.iter() {
+        reputation::seed_recent(env, &account);
+        retime_list(env, &DataKey::Achievements(account.clone()), "earned_at", ledger_to_timestamp);
+        retime_list(env, &DataKey::Endorsements(account.clone()), "endorsed_at", ledger_to_timestamp);
+        for achievement in [
+            Achievement::FirstJob,
+            Achievement::TenFiveStarReviews,
+            Achievement::ZeroDisputeStreak,
+            Achievement::CategorySpecialist,
+            Achievement::TenThousandEarned,
+            Achievement::TenDisputesArbitrated,
+        ] {
+            retime_field(env, &AccountKey::Credential(account.clone(), achievement), "earned_at", ledger_to_timestamp);
+        }
+    }
+
+    // Only the emergency path closes an escrow while leaving it Expired
+    if escrow.status == EscrowStatus::Expired && env.storage().instance().has(&EscrowKey::ClosedAt(escrow_id)) {
+        env.storage().instance().set(&EscrowKey::EmergencyRefunded(escrow_id), &true);
+    }
+}
+
+/// Read an escrow's parties and milestone count without decoding the record, whose layout may
+/// predate this build
+fn read_escrow_parties(env: &Env, escrow_id: u32) -> Option<(Address, Option<Address>, u32)> {
+    let raw: Map<Symbol, Val> = env.storage().instance().get(&DataKey::Escrow(escrow_id))?;
+    let field = |name: &str| raw.get(Symbol::new(env, name));
+    Some((
+        Address::try_from_val(env, &field("depositor")?).ok()?,
+        Option::<Address>::try_from_val(env, &field("beneficiary")?).ok()?,
+        u32::try_from_val(env, &field("milestone_count")?).ok()?,
+    ))
+}
+
+/// Drop a retired field from a stored record, if the record still has it
+fn strip_field(env: &Env, key: &DataKey, field: &str) {
+    if let Some(mut raw) = env.storage().instance().get::<DataKey, Map<Symbol, Val>>(key) {
+        if raw.remove(Symbol::new(env, field)).is_some() {
+            env.storage().instance().set(key, &raw);
+        }
+    }
+}
+
+/// Rewrite a ledger-based field of a stored record with `convert`, returning the record's
+/// `milestone_count` if it has one. Fields already converted are left alone.
+fn retime_field<K: IntoVal<Env, Val>>(env: &Env, key: &K, field: &str, convert: Convert) -> Option<u32> {
+    let mut raw: Map<Symbol, Val> = env.storage().instance().get(key)?;
+    let milestone_count = raw
+        .get(Symbol::new(env, "milestone_count"))
+        .and_then(|count| u32::try_from_val(env, &count).ok());
+    if retime_entry(env, &mut raw, field, convert) {
+        env.storage().instance().set(key, &raw);
+    }
+    milestone_count
+}
+
+/// Rewrite a ledger-based field of a record in place, returning whether it needed converting
+fn retime_entry(env: &Env, raw: &mut Map<Symbol, Val>, field: &str, convert: Convert) -> bool {
+    let field = Symbol::new(env, field);
+    let Some(ledgers) = raw.get(field.clone()).and_then(|value| u32::try_from_val(env, &value).ok()) else {
+        return false;
+    };
+    raw.set(field, convert(env, ledgers).into_val(env));
+    true
+}
+
+/// Rewrite a ledger-based field of every record in a stored list with `convert`
+fn retime_list<K: IntoVal<Env, Val>>(env: &Env, key: &K, field: &str, convert: Convert) {
+    let Some(records) = env.storage().instance().get::<K, Vec<Map<Symbol, Val>>>(key) else {
+        return;
+    };
+    let mut retimed = Vec::new(env);
+    for mut record in records.iter() {
+        retime_entry(env, &mut record, field, convert);
+        retimed.push_back(record);
+    }
+    env.storage().instance().set(key, &retimed);
+}
+
+/// Move a record version 6 kept in instance storage to its own persistent entry
+fn move_to_persistent<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    let Some(value) = env.storage().instance().get::<K, Val>(key) else {
+        return;
+    };
+    escrow_core::set_persistent(env, key, &value);
+    env.storage().instance().remove(key);
+}
+
+/// Rewrite a ledger-based value stored on its own with `convert`, unless it was already converted
+fn retime_value<K: IntoVal<Env, Val>>(env: &Env, key: &K, convert: Convert) {
+    let Some(raw) = env.storage().instance().get::<K, Val>(key) else {
+        return;
+    };
+    if let Ok(ledgers) = u32::try_from_val(env, &raw) {
+        env.storage().instance().set(key, &convert(env, ledgers));
+    }
+}
+
+/// Convert a number of ledgers to seconds
+fn ledgers_to_seconds(_env: &Env, ledgers: u32) -> u64 {
+    u64::from(ledgers) * u64::from(SECONDS_PER_LEDGER)
+}
+
+/// Estimate the unix time a ledger closed or will close at, keeping 0 as "never"
+fn ledger_to_timestamp(env: &Env, ledger: u32) -> u64 {
+    if ledger == 0 {
+        return 0;
+    }
+    let now = env.ledger().timestamp();
+    let current_ledger = env.ledger().sequence();
+    if ledger >= current_ledger {
+        now + u64::from(ledger - current_ledger) * u64::from(SECONDS_PER_LEDGER)
+    } else {
+        now.saturating_sub(u64::from(current_ledger - ledger) * u64::from(SECONDS_PER_LEDGER))
+    }
+}
+
+/// Read a record stored in a legacy layout, or None if it is missing or already converted
+fn read_legacy<T: TryFromVal<Env, Val>>(env: &Env, key: &DataKey, legacy_fields: u32) -> Option<T> {
+    let raw: Map<Symbol, Val> = env.storage().instance().get(key)?;
+    if raw.len() != legacy_fields {
+        return None;
+    }
+    T::try_from_val(env, &raw.to_val()).ok()
+}
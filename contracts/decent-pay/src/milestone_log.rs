@@ -0,0 +1,171 @@
+use crate::escrow_core;
+use crate::work_lifecycle;
+use crate::storage_types::{
+    ActivityAction, ActivityEntry, Checkpoint, Comment, EscrowKey, EscrowStatus, Milestone, MilestoneStatus, SubmissionRecord, DeCentPayError,
+};
+use soroban_sdk::{Address, BytesN, Env, String, Vec, Error};
+
+const MAX_CHECKPOINTS: u32 = 50;
+const MAX_NOTE_LENGTH: u32 = 280;
+const MAX_COMMENTS: u32 = 30;
+const MAX_SUBMISSION_HISTORY: u32 = 10;
+const MAX_ACTIVITY_PAGE: u32 = 50;
+
+/// Post a progress checkpoint against a milestone that has not been submitted yet
+pub fn post_checkpoint(
+    env: &Env,
+    escrow_id: u32,
+    milestone_index: u32,
+    note: String,
+    hash: Option<BytesN<32>>,
+    beneficiary: Address,
+) -> Result<(), Error> {
+    beneficiary.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.beneficiary.as_ref() != Some(&beneficiary) {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyBeneficiary as u32));
+    }
+
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let milestone = work_lifecycle::get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    if milestone.status != MilestoneStatus::NotStarted && milestone.status != MilestoneStatus::Rejected {
+        return Err(Error::from_contract_error(DeCentPayError::MilestoneAlreadySubmitted as u32));
+    }
+
+    if note.len() > MAX_NOTE_LENGTH {
+        return Err(Error::from_contract_error(DeCentPayError::NoteTooLong as u32));
+    }
+
+    let mut checkpoints = get_checkpoints(env, escrow_id, milestone_index);
+    if checkpoints.len() >= MAX_CHECKPOINTS {
+        return Err(Error::from_contract_error(DeCentPayError::LogFull as u32));
+    }
+
+    checkpoints.push_back(Checkpoint {
+        note,
+        hash,
+        posted_at: env.ledger().timestamp(),
+    });
+    escrow_core::set_persistent(env, &EscrowKey::Checkpoints(escrow_id, milestone_index), &checkpoints);
+    Ok(())
+}
+
+/// Get the progress checkpoints posted against a milestone
+pub fn get_checkpoints(env: &Env, escrow_id: u32, milestone_index: u32) -> Vec<Checkpoint> {
+    escrow_core::get_persistent(env, &EscrowKey::Checkpoints(escrow_id, milestone_index)).unwrap_or(Vec::new(env))
+}
+
+/// Add a comment to a milestone's thread; arbiters may join while the escrow is disputed
+pub fn post_comment(env: &Env, escrow_id: u32, milestone_index: u32, body: String, author: Address) -> Result<(), Error> {
+    author.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.status != EscrowStatus::Pending
+        && escrow.status != EscrowStatus::InProgress
+        && escrow.status != EscrowStatus::Disputed
+    {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let is_party = escrow.depositor == author || escrow.beneficiary.as_ref() == Some(&author);
+    let is_arbiter = escrow.status == EscrowStatus::Disputed
+        && work_lifecycle::get_milestone_arbiters(env, escrow_id, milestone_index)?
+            .arbiters
+            .contains(&author);
+    if !is_party && !is_arbiter {
+        return Err(Error::from_contract_error(DeCentPayError::Unauthorized as u32));
+    }
+
+    if milestone_index >= escrow.milestone_count {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidMilestone as u32));
+    }
+
+    if body.len() > MAX_NOTE_LENGTH {
+        return Err(Error::from_contract_error(DeCentPayError::NoteTooLong as u32));
+    }
+
+    let mut comments = get_comments(env, escrow_id, milestone_index);
+    if comments.len() >= MAX_COMMENTS {
+        return Err(Error::from_contract_error(DeCentPayError::LogFull as u32));
+    }
+
+    comments.push_back(Comment {
+        author,
+        body,
+        posted_at: env.ledger().timestamp(),
+    });
+    escrow_core::set_persistent(env, &EscrowKey::MilestoneComments(escrow_id, milestone_index), &comments);
+    Ok(())
+}
+
+/// Get the comment thread of a milestone
+pub fn get_comments(env: &Env, escrow_id: u32, milestone_index: u32) -> Vec<Comment> {
+    escrow_core::get_persistent(env, &EscrowKey::MilestoneComments(escrow_id, milestone_index)).unwrap_or(Vec::new(env))
+}
+
+/// Archive a rejected submission before it is overwritten, keeping only the most recent ones
+pub fn archive_submission(env: &Env, escrow_id: u32, milestone_index: u32, milestone: &Milestone) {
+    let mut history = get_submission_history(env, escrow_id, milestone_index);
+    if history.len() >= MAX_SUBMISSION_HISTORY {
+        history.pop_front();
+    }
+    history.push_back(SubmissionRecord {
+        description: milestone.description.clone(),
+        manifest: milestone.manifest.clone(),
+        submitted_at: milestone.submitted_at,
+        rejection_reason: milestone.rejection_reason.clone(),
+    });
+    escrow_core::set_persistent(env, &EscrowKey::SubmissionHistory(escrow_id, milestone_index), &history);
+}
+
+/// Get the earlier submissions of a milestone and why they were rejected, oldest first
+pub fn get_submission_history(env: &Env, escrow_id: u32, milestone_index: u32) -> Vec<SubmissionRecord> {
+    escrow_core::get_persistent(env, &EscrowKey::SubmissionHistory(escrow_id, milestone_index)).unwrap_or(Vec::new(env))
+}
+
+/// Append an entry to an escrow's activity log; entries are never rewritten or dropped.
+/// Transitions anyone may trigger are recorded with the contract as the actor.
+pub fn record_activity(env: &Env, escrow_id: u32, actor: &Address, action: ActivityAction, note: Option<String>) {
+    let entry_index = get_activity_count(env, escrow_id);
+    escrow_core::set_persistent(
+        env,
+        &EscrowKey::Activity(escrow_id, entry_index),
+        &ActivityEntry {
+            actor: actor.clone(),
+            action,
+            recorded_at: env.ledger().timestamp(),
+            note,
+        },
+    );
+    escrow_core::set_persistent(env, &EscrowKey::ActivityCount(escrow_id), &(entry_index + 1));
+}
+
+/// Get the number of entries in an escrow's activity log
+pub fn get_activity_count(env: &Env, escrow_id: u32) -> u32 {
+    escrow_core::get_persistent(env, &EscrowKey::ActivityCount(escrow_id)).unwrap_or(0)
+}
+
+/// Get up to `limit` entries of an escrow's activity log from `start`, oldest first
+pub fn get_escrow_activity(env: &Env, escrow_id: u32, start: u32, limit: u32) -> Vec<ActivityEntry> {
+    let stop = start
+        .saturating_add(limit.min(MAX_ACTIVITY_PAGE))
+        .min(get_activity_count(env, escrow_id));
+    let mut page = Vec::new(env);
+    for entry_index in start..stop {
+        if let Some(entry) = escrow_core::get_persistent(env, &EscrowKey::Activity(escrow_id, entry_index)) {
+            page.push_back(entry);
+        }
+    }
+    page
+}
@@ -0,0 +1,99 @@
+use crate::admin;
+use crate::compliance;
+use crate::escrow_core;
+use crate::profiles;
+use crate::storage_types::{AccountKey, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::{Address, Env, Error};
+
+/// Opt in or out of netting settlements with a counterparty; netting applies once both sides opt in
+pub fn set_netting(env: &Env, counterparty: Address, enabled: bool, party: Address) {
+    party.require_auth();
+
+    let key = AccountKey::NettingOptIn(party, counterparty);
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    if enabled {
+        env.storage().instance().set(&key, &true);
+    } else {
+        env.storage().instance().remove(&key);
+    }
+}
+
+/// Check whether two parties both opted in to netting
+pub fn is_netting_active(env: &Env, party: &Address, counterparty: &Address) -> bool {
+    let opted_in = |a: &Address, b: &Address| {
+        env.storage()
+            .instance()
+            .get::<AccountKey, bool>(&AccountKey::NettingOptIn(a.clone(), b.clone()))
+            .unwrap_or(false)
+    };
+    opted_in(party, counterparty) && opted_in(counterparty, party)
+}
+
+/// Get what the contract owes `party` from netted escrows with `counterparty` in a token (None for native XLM)
+pub fn get_netting_balance(env: &Env, party: Address, counterparty: Address, token: Option<Address>) -> i128 {
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&AccountKey::NettingBalance(party, counterparty, token_key))
+        .unwrap_or(0)
+}
+
+fn set_netting_balance(env: &Env, party: &Address, counterparty: &Address, token: Option<&Address>, balance: i128) {
+    let token_key = token.cloned().unwrap_or_else(|| env.current_contract_address());
+    let key = AccountKey::NettingBalance(party.clone(), counterparty.clone(), token_key);
+    if balance == 0 {
+        env.storage().instance().remove(&key);
+    } else {
+        env.storage().instance().set(&key, &balance);
+    }
+}
+
+/// Book a payout to `party` as a netting credit instead of transferring it.
+/// The caller has already taken `amount` off the escrowed total, so it is added back
+/// while the contract holds it.
+pub fn credit(env: &Env, token: Option<&Address>, party: &Address, counterparty: &Address, amount: i128) -> Result<(), Error> {
+    let balance = get_netting_balance(env, party.clone(), counterparty.clone(), token.cloned());
+    set_netting_balance(env, party, counterparty, token, balance + amount);
+    escrow_core::update_escrowed_amount(env, token, amount)?;
+    Ok(())
+}
+
+/// Use `party`'s netting credit with `counterparty` towards a new obligation, returning the amount covered
+pub fn draw(env: &Env, token: Option<&Address>, party: &Address, counterparty: &Address, amount: i128) -> Result<i128, Error> {
+    if !is_netting_active(env, party, counterparty) {
+        return Ok(0);
+    }
+
+    let balance = get_netting_balance(env, party.clone(), counterparty.clone(), token.cloned());
+    let drawn = balance.min(amount).max(0);
+    if drawn > 0 {
+        set_netting_balance(env, party, counterparty, token, balance - drawn);
+        escrow_core::update_escrowed_amount(env, token, -drawn)?;
+    }
+    Ok(drawn)
+}
+
+/// Pay out both sides' netting balances between two parties in one settlement, to their payout
+/// addresses and under the same screening as escrow payouts
+pub fn settle_netting(env: &Env, party: Address, counterparty: Address, token: Option<Address>) -> Result<(), Error> {
+    party.require_auth();
+    admin::require_not_paused(env)?;
+
+    for (recipient, other) in [(&party, &counterparty), (&counterparty, &party)] {
+        let balance = get_netting_balance(env, recipient.clone(), other.clone(), token.clone());
+        if balance > 0 {
+            let payout_addr = profiles::payout_address(env, recipient);
+            compliance::require_not_flagged(env, recipient)?;
+            compliance::require_not_flagged(env, &payout_addr)?;
+            set_netting_balance(env, recipient, other, token.as_ref(), 0);
+            escrow_core::update_escrowed_amount(env, token.as_ref(), -balance)?;
+            escrow_core::transfer(env, token.as_ref(), &env.current_contract_address(), &payout_addr, balance);
+        }
+    }
+    Ok(())
+}
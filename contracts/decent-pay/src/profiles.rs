@@ -0,0 +1,117 @@
+use crate::admin;
+use crate::escrow_core;
+use crate::storage_types::{
+    DataKey, Endorsement, EscrowStatus, DeCentPayError, PayoutPreferences, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error, Symbol, Vec};
+
+const MAX_ENDORSEMENTS_PER_ESCROW: u32 = 3;
+const MAX_ENDORSEMENTS: u32 = 100;
+
+/// Set a freelancer's payout preferences
+pub fn set_payout_preferences(env: &Env, freelancer: Address, preferences: PayoutPreferences) -> Result<(), Error> {
+    freelancer.require_auth();
+    admin::require_not_paused(env)?;
+
+    if preferences.min_payout < 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+    if preferences.payout_address.as_ref() == Some(&env.current_contract_address()) {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAddress as u32));
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::PayoutPreferences(freelancer), &preferences);
+    Ok(())
+}
+
+/// Get a freelancer's payout preferences
+pub fn get_payout_preferences(env: &Env, freelancer: Address) -> Option<PayoutPreferences> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&DataKey::PayoutPreferences(freelancer))
+}
+
+/// Get the smallest vested amount `freelancer` wants to claim at a time
+pub fn min_payout(env: &Env, freelancer: &Address) -> i128 {
+    get_payout_preferences(env, freelancer.clone()).map_or(0, |preferences| preferences.min_payout)
+}
+
+/// Get the address that should receive payouts owed to `freelancer`
+pub fn payout_address(env: &Env, freelancer: &Address) -> Address {
+    get_payout_preferences(env, freelancer.clone())
+        .and_then(|preferences| preferences.payout_address)
+        .unwrap_or_else(|| freelancer.clone())
+}
+
+/// Endorse the counterparty of a completed escrow for a skill
+pub fn endorse(env: &Env, escrow_id: u32, endorser: Address, skill: Symbol) -> Result<(), Error> {
+    endorser.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.status != EscrowStatus::Released {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotCompleted as u32));
+    }
+
+    let beneficiary = escrow
+        .beneficiary
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotCompleted as u32))?;
+    let recipient = if endorser == escrow.depositor {
+        beneficiary
+    } else if endorser == beneficiary {
+        escrow.depositor
+    } else {
+        return Err(Error::from_contract_error(DeCentPayError::Unauthorized as u32));
+    };
+
+    let mut endorsements = get_endorsements(env, recipient.clone());
+    if endorsements.len() >= MAX_ENDORSEMENTS {
+        return Err(Error::from_contract_error(DeCentPayError::TooManyEndorsements as u32));
+    }
+
+    let mut from_this_escrow = 0u32;
+    for existing in endorsements.iter() {
+        if existing.endorser == endorser && existing.skill == skill {
+            return Err(Error::from_contract_error(DeCentPayError::AlreadyEndorsed as u32));
+        }
+        if existing.endorser == endorser && existing.escrow_id == escrow_id {
+            from_this_escrow += 1;
+        }
+    }
+    if from_this_escrow >= MAX_ENDORSEMENTS_PER_ESCROW {
+        return Err(Error::from_contract_error(DeCentPayError::TooManyEndorsements as u32));
+    }
+
+    endorsements.push_back(Endorsement {
+        endorser,
+        skill,
+        escrow_id,
+        endorsed_at: env.ledger().timestamp(),
+    });
+    env.storage()
+        .instance()
+        .set(&DataKey::Endorsements(recipient), &endorsements);
+    Ok(())
+}
+
+/// Get the skill endorsements a user has received
+pub fn get_endorsements(env: &Env, user: Address) -> Vec<Endorsement> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&DataKey::Endorsements(user))
+        .unwrap_or(Vec::new(env))
+}
@@ -1,8 +1,17 @@
 use crate::storage_types::{
-    DataKey, EscrowStatus, Rating, Badge, DeCentPayError, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+    AdverseRecord, BadgeCriteria, BadgeProgress, ClientBadge, ClientRecord, ConfigKey, DataKey, EscrowStatus, Rating, Role, Badge, DeCentPayError, DAY_IN_SECONDS, DEFAULT_MAX_REVIEW_LENGTH, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD, MAX_REVIEW_EXCERPT_LENGTH,
 };
+use crate::achievements;
+use crate::admin;
 use crate::escrow_core;
-use soroban_sdk::{Address, Env, String, Error};
+use soroban_sdk::{Address, BytesN, Env, String, Error};
+
+// Each adverse outcome cancels out this many completed escrows when computing badges
+const ADVERSE_OUTCOME_PENALTY: u32 = 5;
+// Approvals within this many seconds of submission count as on time (7 days)
+pub const CLIENT_REVIEW_WINDOW: u64 = 7 * DAY_IN_SECONDS;
+const DEFAULT_RATING_EDIT_WINDOW: u64 = 7 * DAY_IN_SECONDS;
 
 /// Submit a rating for a completed escrow
 /// Only the depositor (client) can rate the freelancer
@@ -15,13 +24,47 @@ pub fn submit_rating(
 ) -> Result<(), Error> {
     client.require_auth();
 
+    if review.len() > get_max_review_length(env) {
+        return Err(Error::from_contract_error(DeCentPayError::ReviewTooLong as u32));
+    }
+
+    store_rating(env, escrow_id, rating, review, None, client)
+}
+
+/// Submit a rating whose full review lives off-chain.
+/// Only the review hash and a short excerpt are stored.
+pub fn submit_rating_hashed(
+    env: &Env,
+    escrow_id: u32,
+    rating: u32,
+    excerpt: String,
+    review_hash: BytesN<32>,
+    client: Address,
+) -> Result<(), Error> {
+    client.require_auth();
+
+    if excerpt.len() > MAX_REVIEW_EXCERPT_LENGTH {
+        return Err(Error::from_contract_error(DeCentPayError::ReviewTooLong as u32));
+    }
+
+    store_rating(env, escrow_id, rating, excerpt, Some(review_hash), client)
+}
+
+fn store_rating(
+    env: &Env,
+    escrow_id: u32,
+    rating: u32,
+    review: String,
+    review_hash: Option<BytesN<32>>,
+    client: Address,
+) -> Result<(), Error> {
     // Validate rating (1-5)
     if rating < 1 || rating > 5 {
         return Err(Error::from_contract_error(DeCentPayError::InvalidRating as u32));
     }
 
     // Validate escrow exists
-    escrow_core::require_valid_escrow(env, escrow_id)?;
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
     let escrow = escrow_core::get_escrow(env, escrow_id)
         .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
 
@@ -52,7 +95,10 @@ pub fn submit_rating(
         client: client.clone(),
         rating,
         review,
-        rated_at: env.ledger().sequence(),
+        review_hash,
+        rated_at: env.ledger().timestamp(),
+        edited_at: 0,
+        response: None,
     };
 
     // Save rating
@@ -65,10 +111,103 @@ pub fn submit_rating(
 
     // Update freelancer's average rating
     update_average_rating(env, &freelancer, rating);
+    achievements::on_rating_received(env, &freelancer, rating, escrow_id);
+
+    Ok(())
+}
+
+/// Change the stars and review of a rating while the edit window is open (rater only)
+pub fn update_rating(env: &Env, escrow_id: u32, rating: u32, review: String, client: Address) -> Result<(), Error> {
+    client.require_auth();
+
+    if !(1..=5).contains(&rating) {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidRating as u32));
+    }
+
+    if review.len() > get_max_review_length(env) {
+        return Err(Error::from_contract_error(DeCentPayError::ReviewTooLong as u32));
+    }
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let mut rating_data = get_rating(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::RatingNotFound as u32))?;
+
+    if rating_data.client != client {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositorCanRate as u32));
+    }
+
+    if !is_rating_editable(env, &rating_data) {
+        return Err(Error::from_contract_error(DeCentPayError::RatingEditWindowClosed as u32));
+    }
 
+    // Swap the old stars for the new ones in the freelancer's average
+    let avg_key = DataKey::AverageRating(rating_data.freelancer.clone());
+    let (total, count): (u32, u32) = env.storage().instance().get(&avg_key).unwrap_or((0, 0));
+    env.storage()
+        .instance()
+        .set(&avg_key, &(total.saturating_sub(rating_data.rating) + rating, count));
+    achievements::on_rating_edited(env, &rating_data.freelancer, rating_data.rating, rating, escrow_id);
+
+    rating_data.rating = rating;
+    rating_data.review = review;
+    rating_data.review_hash = None;
+    rating_data.edited_at = env.ledger().timestamp();
+    env.storage()
+        .instance()
+        .set(&DataKey::Rating(escrow_id), &rating_data);
     Ok(())
 }
 
+/// Attach the rated freelancer's single public response to a rating
+pub fn respond_to_rating(env: &Env, escrow_id: u32, response: String, freelancer: Address) -> Result<(), Error> {
+    freelancer.require_auth();
+
+    if response.len() > get_max_review_length(env) {
+        return Err(Error::from_contract_error(DeCentPayError::ReviewTooLong as u32));
+    }
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let mut rating_data = get_rating(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::RatingNotFound as u32))?;
+
+    if rating_data.freelancer != freelancer {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyBeneficiary as u32));
+    }
+
+    if rating_data.response.is_some() {
+        return Err(Error::from_contract_error(DeCentPayError::AlreadyResponded as u32));
+    }
+
+    rating_data.response = Some(response);
+    env.storage()
+        .instance()
+        .set(&DataKey::Rating(escrow_id), &rating_data);
+    Ok(())
+}
+
+/// Check if a rating is still within its edit window
+pub fn is_rating_editable(env: &Env, rating: &Rating) -> bool {
+    env.ledger().timestamp() <= rating.rated_at.saturating_add(get_rating_edit_window(env))
+}
+
+/// Set how many seconds after rating the rater can still edit it
+pub fn set_rating_edit_window(env: &Env, seconds: u64, caller: Address) -> Result<(), Error> {
+    admin::require_role(env, &caller, Role::Admin)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(&ConfigKey::RatingEditWindow, &seconds);
+    Ok(())
+}
+
+/// Get how many seconds after rating the rater can still edit it
+pub fn get_rating_edit_window(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&ConfigKey::RatingEditWindow)
+        .unwrap_or(DEFAULT_RATING_EDIT_WINDOW)
+}
+
 /// Update average rating for a freelancer
 fn update_average_rating(env: &Env, freelancer: &Address, new_rating: u32) {
     env.storage()
@@ -112,24 +251,111 @@ pub fn get_average_rating(env: &Env, freelancer: Address) -> (u32, u32) {
         .unwrap_or((0, 0))
 }
 
-/// Get badge for a freelancer based on completed projects
+/// Get a freelancer's average rating scaled by 100 (437 = 4.37 stars), rounded to nearest, with the count
+pub fn get_rating_score(env: &Env, freelancer: Address) -> (u32, u32) {
+    let (total, count) = get_average_rating(env, freelancer);
+    if count == 0 {
+        return (0, 0);
+    }
+    ((total * 100 + count / 2) / count, count)
+}
+
+/// Get badge for a freelancer: the highest tier whose completed projects, average rating and
+/// dispute record criteria they meet
 pub fn get_badge(env: &Env, freelancer: Address) -> Badge {
+    let standing = get_standing(env, freelancer);
+    for badge in [Badge::Expert, Badge::Advanced, Badge::Intermediate] {
+        if meets_criteria(&standing, &get_badge_criteria(env, badge)) {
+            return badge;
+        }
+    }
+    Badge::Beginner
+}
+
+/// Get how a freelancer measures up against the next badge tier
+pub fn get_badge_progress(env: &Env, freelancer: Address) -> BadgeProgress {
+    let badge = get_badge(env, freelancer.clone());
+    let next_badge = match badge {
+        Badge::Beginner => Badge::Intermediate,
+        Badge::Intermediate => Badge::Advanced,
+        Badge::Advanced | Badge::Expert => Badge::Expert,
+    };
+    let next_criteria = get_badge_criteria(env, next_badge);
+    let (completed, rating_score, dispute_loss_bp) = get_standing(env, freelancer);
+    BadgeProgress {
+        badge,
+        next_badge,
+        completed,
+        rating_score,
+        dispute_loss_bp,
+        completed_missing: next_criteria.min_completed.saturating_sub(completed),
+        rating_met: rating_score >= next_criteria.min_rating_score,
+        dispute_record_met: dispute_loss_bp <= next_criteria.max_dispute_loss_bp,
+        next_criteria,
+    }
+}
+
+/// Set the criteria a freelancer must meet to hold a badge tier
+pub fn set_badge_criteria(env: &Env, badge: Badge, criteria: BadgeCriteria, caller: Address) -> Result<(), Error> {
+    admin::require_role(env, &caller, Role::Admin)?;
+    if badge == Badge::Beginner || criteria.min_rating_score > 500 || criteria.max_dispute_loss_bp > 10000 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+    }
     env.storage()
         .instance()
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-    
+    env.storage().instance().set(&ConfigKey::BadgeCriteria(badge), &criteria);
+    Ok(())
+}
+
+/// Get the criteria a freelancer must meet to hold a badge tier
+pub fn get_badge_criteria(env: &Env, badge: Badge) -> BadgeCriteria {
+    env.storage()
+        .instance()
+        .get(&ConfigKey::BadgeCriteria(badge))
+        .unwrap_or(match badge {
+            Badge::Beginner => BadgeCriteria { min_completed: 0, min_rating_score: 0, max_dispute_loss_bp: 10000 },
+            Badge::Intermediate => BadgeCriteria { min_completed: 5, min_rating_score: 0, max_dispute_loss_bp: 2500 },
+            Badge::Advanced => BadgeCriteria { min_completed: 15, min_rating_score: 400, max_dispute_loss_bp: 1000 },
+            Badge::Expert => BadgeCriteria { min_completed: 50, min_rating_score: 450, max_dispute_loss_bp: 500 },
+        })
+}
+
+/// A freelancer's completed projects (discounted by adverse outcomes), rating score and dispute
+/// loss rate in basis points
+fn get_standing(env: &Env, freelancer: Address) -> (u32, u32, u32) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
     let completed: u32 = env
         .storage()
         .instance()
-        .get(&DataKey::CompletedEscrows(freelancer))
+        .get(&DataKey::CompletedEscrows(freelancer.clone()))
         .unwrap_or(0);
+    let adverse = get_adverse_record(env, freelancer.clone());
+    let adverse_count = adverse.disputes_lost + adverse.abandoned;
 
-    match completed {
-        0..=4 => Badge::Beginner,
-        5..=14 => Badge::Intermediate,
-        15..=49 => Badge::Advanced,
-        _ => Badge::Expert,
-    }
+    let engagements = completed + adverse.disputes_lost;
+    let dispute_loss_bp = if engagements == 0 {
+        0
+    } else {
+        adverse.disputes_lost * 10000 / engagements
+    };
+    let (rating_score, _) = get_rating_score(env, freelancer);
+
+    (
+        completed.saturating_sub(adverse_count * ADVERSE_OUTCOME_PENALTY),
+        rating_score,
+        dispute_loss_bp,
+    )
+}
+
+fn meets_criteria(standing: &(u32, u32, u32), criteria: &BadgeCriteria) -> bool {
+    let (completed, rating_score, dispute_loss_bp) = *standing;
+    completed >= criteria.min_completed
+        && rating_score >= criteria.min_rating_score
+        && dispute_loss_bp <= criteria.max_dispute_loss_bp
 }
 
 /// Get completed escrows count for a user
@@ -143,3 +369,89 @@ pub fn get_completed_escrows(env: &Env, user: Address) -> u32 {
         .unwrap_or(0)
 }
 
+
+pub fn set_max_review_length(env: &Env, max_length: u32, caller: Address) -> Result<(), Error> {
+    admin::require_role(env, &caller, Role::Admin)?;
+    if max_length < MAX_REVIEW_EXCERPT_LENGTH {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+    }
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::MaxReviewLength, &max_length);
+    Ok(())
+}
+
+pub fn get_max_review_length(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::MaxReviewLength)
+        .unwrap_or(DEFAULT_MAX_REVIEW_LENGTH)
+}
+
+/// Get the adverse outcomes recorded against a freelancer
+pub fn get_adverse_record(env: &Env, freelancer: Address) -> AdverseRecord {
+    env.storage()
+        .instance()
+        .get(&DataKey::AdverseRecord(freelancer))
+        .unwrap_or_default()
+}
+
+/// Record an adverse outcome against a freelancer
+pub fn record_adverse_outcome(env: &Env, freelancer: &Address, record: impl FnOnce(&mut AdverseRecord)) {
+    let mut adverse = get_adverse_record(env, freelancer.clone());
+    record(&mut adverse);
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::AdverseRecord(freelancer.clone()), &adverse);
+}
+
+/// Get a depositor's track record as a client
+pub fn get_client_record(env: &Env, client: Address) -> ClientRecord {
+    env.storage()
+        .instance()
+        .get(&DataKey::ClientRecord(client))
+        .unwrap_or_default()
+}
+
+/// Update a depositor's track record as a client
+pub fn record_client_outcome(env: &Env, client: &Address, record: impl FnOnce(&mut ClientRecord)) {
+    let mut client_record = get_client_record(env, client.clone());
+    record(&mut client_record);
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::ClientRecord(client.clone()), &client_record);
+}
+
+/// Get client badge for a depositor based on completed hires, approval timeliness, and disputes
+pub fn get_client_badge(env: &Env, client: Address) -> ClientBadge {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+    let record = get_client_record(env, client);
+    let reviews = record.on_time_approvals + record.late_approvals + record.rejections;
+    let on_time_percent = if reviews == 0 {
+        0
+    } else {
+        record.on_time_approvals * 100 / reviews
+    };
+
+    if record.disputes_lost == 0 && record.completed_hires >= 20 && on_time_percent >= 90 {
+        ClientBadge::Exemplary
+    } else if record.disputes_lost == 0 && record.completed_hires >= 5 && on_time_percent >= 80 {
+        ClientBadge::Trusted
+    } else if record.completed_hires >= 1 && on_time_percent >= 50 {
+        ClientBadge::Reliable
+    } else {
+        ClientBadge::New
+    }
+}
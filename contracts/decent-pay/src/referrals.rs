@@ -0,0 +1,75 @@
+use crate::admin;
+use crate::escrow_core;
+use crate::storage_types::{
+    AccountKey, DeCentPayError, EscrowData, EscrowKey, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error};
+
+/// Credit `referrer` (e.g. the wallet or frontend a new escrow was created through) with a share
+/// of its platform fee
+pub fn set_referrer(env: &Env, escrow_id: u32, escrow: &EscrowData, referrer: Address) -> Result<(), Error> {
+    if referrer == escrow.depositor {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAddress as u32));
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::Referrer(escrow_id), &referrer);
+    Ok(())
+}
+
+/// Get the referrer of an escrow
+pub fn get_referrer(env: &Env, escrow_id: u32) -> Option<Address> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::Referrer(escrow_id))
+}
+
+/// Credit the referrer's share of a booked platform fee, returning the part left for the platform
+pub fn take_referral_share(env: &Env, escrow_id: u32, token_key: &Address, platform_fee: i128) -> i128 {
+    let Some(referrer) = get_referrer(env, escrow_id) else {
+        return platform_fee;
+    };
+
+    let share = platform_fee * i128::from(admin::get_referral_share_bp(env)) / 10000;
+    if share > 0 {
+        let key = AccountKey::ReferralEarnings(referrer, token_key.clone());
+        let earned: i128 = env.storage().instance().get(&key).unwrap_or(0);
+        env.storage().instance().set(&key, &(earned + share));
+    }
+    platform_fee - share
+}
+
+/// Get a referrer's unclaimed earnings in a token (None for native XLM)
+pub fn get_referral_earnings(env: &Env, referrer: Address, token: Option<Address>) -> i128 {
+    let token_key = token.unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&AccountKey::ReferralEarnings(referrer, token_key))
+        .unwrap_or(0)
+}
+
+/// Withdraw a referrer's earnings in a token, returning the amount paid
+pub fn claim_referral_earnings(env: &Env, referrer: Address, token: Option<Address>) -> Result<i128, Error> {
+    referrer.require_auth();
+    admin::require_not_paused(env)?;
+
+    let earned = get_referral_earnings(env, referrer.clone(), token.clone());
+    if earned <= 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InsufficientFees as u32));
+    }
+
+    let token_key = token.clone().unwrap_or_else(|| env.current_contract_address());
+    env.storage()
+        .instance()
+        .remove(&AccountKey::ReferralEarnings(referrer.clone(), token_key));
+    escrow_core::transfer(env, token.as_ref(), &env.current_contract_address(), &referrer, earned);
+    Ok(earned)
+}
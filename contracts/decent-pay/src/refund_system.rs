@@ -1,21 +1,89 @@
+use crate::admin;
+use crate::bonds;
+use crate::compliance;
+use crate::crowdfunding;
+use crate::dispute_resolution;
 use crate::escrow_core;
-use crate::storage_types::{DataKey, EscrowStatus, DeCentPayError, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
-use soroban_sdk::{token, Address, Env, Error, String};
+use crate::escrow_management;
+use crate::finality;
+use crate::idle_yield;
+use crate::marketplace;
+use crate::milestone_log;
+use crate::netting;
+use crate::ratings;
+use crate::reputation;
+use crate::retainage;
+use crate::stakes;
+use crate::work_lifecycle;
+use crate::storage_types::{
+    ActivityAction, DataKey, EscrowKey, EscrowData, EscrowStatus, DeCentPayError, MilestoneStatus, SlashReason, TimeStatus, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error};
 
-const EMERGENCY_REFUND_DELAY: u32 = 2592000; // 30 days in seconds
+pub const EMERGENCY_REFUND_DELAY: u64 = 2592000; // 30 days in seconds
 
 pub fn refund_escrow(env: &Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
     depositor.require_auth();
 
     escrow_core::require_valid_escrow(env, escrow_id)?;
+    escrow_core::require_not_frozen(env, escrow_id)?;
     let mut escrow = escrow_core::get_escrow(env, escrow_id)
         .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
 
-    if escrow.depositor != depositor {
+    let refund_amount = check_refund(env, escrow_id, &escrow, &depositor)?;
+    let expired = escrow.status == EscrowStatus::Expired;
+    let past_deadline = env.ledger().timestamp() >= escrow.deadline;
+    env.storage().instance().remove(&EscrowKey::FundedAmount(escrow_id));
+
+    idle_yield::unwind(env, escrow_id, &escrow)?;
+    escrow.status = EscrowStatus::Refunded;
+    finality::record_closed(env, escrow_id);
+
+    if refund_amount > 0 {
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -refund_amount)?;
+        return_principal(env, escrow_id, &escrow, refund_amount)?;
+    }
+
+    // The platform fee is only earned once work starts; an open job that never hired forfeits a
+    // held posting fee
+    escrow_core::refund_platform_fee(env, escrow_id, &escrow)?;
+    marketplace::settle_posting_fee(env, escrow_id, &escrow, false)?;
+    // Closing the job before its deadline is a proper close; an expired job left past it is abandoned
+    if expired && past_deadline {
+        bonds::forfeit_seriousness_deposit(env, escrow_id, &escrow)?;
+    } else {
+        bonds::return_seriousness_deposit(env, escrow_id, &escrow)?;
+    }
+    dispute_resolution::refund_arbitration_fee(env, escrow_id, &escrow)?;
+
+    // Nobody is at fault when a job is refunded before work starts
+    for application in marketplace::get_applications(env, escrow_id).iter() {
+        marketplace::release_reputation_stake(env, escrow_id, &application.freelancer, 0);
+        stakes::return_stake(env, escrow_id, &escrow, &application.freelancer)?;
+    }
+    marketplace::close_negotiations(env, escrow_id, &escrow)?;
+
+    escrow_core::save_escrow(env, escrow_id, &escrow);
+    milestone_log::record_activity(env, escrow_id, &depositor, ActivityAction::Refunded, None);
+    Ok(())
+}
+
+/// Check a depositor may refund an escrow through `refund_escrow`, returning the amount refunded
+pub fn check_refund(env: &Env, escrow_id: u32, escrow: &EscrowData, depositor: &Address) -> Result<i128, Error> {
+    if escrow.depositor != *depositor {
         return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
     }
 
-    if escrow.status != EscrowStatus::Pending {
+    compliance::require_not_flagged(env, depositor)?;
+    compliance::require_not_flagged(env, &refund_destination(env, escrow_id, escrow))?;
+    crowdfunding::screen_contributors(env, escrow_id)?;
+
+    // An escrow still being funded can be abandoned at any time, returning what was paid in,
+    // and an expired open job can be refunded even after its deadline unless the emergency path
+    // already paid it out
+    let funding = escrow.status == EscrowStatus::Funding;
+    let expired = escrow.status == EscrowStatus::Expired && !is_emergency_refunded(env, escrow_id);
+    if escrow.status != EscrowStatus::Pending && !funding && !expired {
         return Err(Error::from_contract_error(DeCentPayError::InvalidEscrowStatus as u32));
     }
 
@@ -23,72 +91,138 @@ pub fn refund_escrow(env: &Env, escrow_id: u32, depositor: Address) -> Result<()
         return Err(Error::from_contract_error(DeCentPayError::WorkAlreadyStarted as u32));
     }
 
-    let current_ledger = env.ledger().sequence();
-    if current_ledger >= escrow.deadline {
+    if env.ledger().timestamp() >= escrow.deadline && !funding && !expired {
         return Err(Error::from_contract_error(DeCentPayError::DeadlineNotPassed as u32));
     }
 
-    let refund_amount = escrow.total_amount - escrow.paid_amount;
-    if refund_amount <= 0 {
+    // An escrow abandoned before anyone paid in still returns the costs held at creation
+    let refund_amount = escrow_management::get_funded_amount(env, escrow_id, escrow) - escrow.paid_amount;
+    if refund_amount <= 0 && !funding {
         return Err(Error::from_contract_error(DeCentPayError::NothingToRefund as u32));
     }
+    Ok(refund_amount)
+}
 
-    escrow.status = EscrowStatus::Refunded;
+pub fn emergency_refund_after_deadline(env: &Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_valid_escrow(env, escrow_id)?;
+    escrow_core::require_not_frozen(env, escrow_id)?;
+    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    let refund_amount = check_emergency_refund(env, escrow_id, &escrow, &depositor)?;
+    let destination = refund_destination(env, escrow_id, &escrow);
+
+    idle_yield::unwind(env, escrow_id, &escrow)?;
+    escrow.status = EscrowStatus::Expired;
+    env.storage().instance().set(&EscrowKey::EmergencyRefunded(escrow_id), &true);
+    finality::record_closed(env, escrow_id);
+
+    // Started work that was never delivered counts against the freelancer
+    if escrow.work_started {
+        if let Some(beneficiary) = &escrow.beneficiary {
+            ratings::record_adverse_outcome(env, beneficiary, |record| record.abandoned += 1);
+            reputation::slash(env, escrow_id, beneficiary, SlashReason::WorkAbandoned);
+            marketplace::burn_reputation_stake(env, escrow_id, beneficiary);
+        }
+    }
 
     // Update escrowed amount
-    let token_key = escrow.token.clone().unwrap_or_else(|| env.current_contract_address());
-    let current_escrowed: i128 = env
-        .storage()
-        .instance()
-        .get(&DataKey::EscrowedAmount(token_key.clone()))
-        .unwrap_or(0);
-    env.storage()
-        .instance()
-        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-    env.storage()
-        .instance()
-        .set(
-            &DataKey::EscrowedAmount(token_key),
-            &(current_escrowed - refund_amount),
-        );
-
-    // Transfer refund
-    if let Some(token_addr) = escrow.token.clone() {
-        let token_client = token::Client::new(env, &token_addr);
-        token_client.transfer(&env.current_contract_address(), &depositor, &refund_amount);
-    } else {
-        // Transfer native XLM refund using Stellar Asset Contract (SAC)
-        let native_token_str = String::from_str(env, "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC");
-        let native_token_address = Address::from_string(&native_token_str);
-        let native_token_client = token::Client::new(env, &native_token_address);
-        native_token_client.transfer(
-            &env.current_contract_address(),
-            &depositor,
-            &refund_amount,
-        );
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -refund_amount)?;
+
+    // Transfer refund, shared among the escrow's funders when it was crowdfunded
+    if !crowdfunding::refund_contributors(env, escrow_id, &escrow, refund_amount)? {
+        escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &destination, refund_amount);
     }
 
+    escrow_core::refund_platform_fee(env, escrow_id, &escrow)?;
+    marketplace::settle_posting_fee(env, escrow_id, &escrow, false)?;
+    marketplace::close_negotiations(env, escrow_id, &escrow)?;
+    bonds::forfeit_seriousness_deposit(env, escrow_id, &escrow)?;
+    stakes::return_all_stakes(env, escrow_id, &escrow)?;
+    marketplace::release_all_reputation_stakes(env, escrow_id);
+    work_lifecycle::refund_revision_fees(env, escrow_id, &escrow)?;
+    retainage::start_warranty(env, escrow_id);
+    dispute_resolution::refund_arbitration_fee(env, escrow_id, &escrow)?;
+
     escrow_core::save_escrow(env, escrow_id, &escrow);
+    milestone_log::record_activity(env, escrow_id, &depositor, ActivityAction::Refunded, None);
     Ok(())
 }
 
-pub fn emergency_refund_after_deadline(env: &Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
-    depositor.require_auth();
+/// Check a depositor may take the emergency refund, returning the amount refunded
+pub fn check_emergency_refund(env: &Env, escrow_id: u32, escrow: &EscrowData, depositor: &Address) -> Result<i128, Error> {
+    if escrow.depositor != *depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    compliance::require_not_flagged(env, depositor)?;
+    compliance::require_not_flagged(env, &refund_destination(env, escrow_id, escrow))?;
+    crowdfunding::screen_contributors(env, escrow_id)?;
+
+    if !is_emergency_refund_open(env.ledger().timestamp(), escrow.deadline) {
+        return Err(Error::from_contract_error(DeCentPayError::EmergencyPeriodNotReached as u32));
+    }
+
+    // An expired escrow has already been refunded here or is refunded through refund_escrow
+    if escrow.status == EscrowStatus::Released
+        || escrow.status == EscrowStatus::Refunded
+        || escrow.status == EscrowStatus::Cancelled
+        || escrow.status == EscrowStatus::Funding
+        || escrow.status == EscrowStatus::Expired
+    {
+        return Err(Error::from_contract_error(DeCentPayError::CannotRefund as u32));
+    }
+
+    let refund_amount = escrow.total_amount - escrow.paid_amount;
+    if refund_amount <= 0 {
+        return Err(Error::from_contract_error(DeCentPayError::NothingToRefund as u32));
+    }
+    Ok(refund_amount)
+}
+
+/// Let the beneficiary bow out, refunding everything unpaid to the depositor and closing the escrow
+pub fn return_funds(env: &Env, escrow_id: u32, beneficiary: Address) -> Result<(), Error> {
+    beneficiary.require_auth();
 
     escrow_core::require_valid_escrow(env, escrow_id)?;
+    escrow_core::require_not_frozen(env, escrow_id)?;
     let mut escrow = escrow_core::get_escrow(env, escrow_id)
         .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
 
-    if escrow.depositor != depositor {
-        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
-    }
+    let refund_amount = check_return_funds(env, escrow_id, &escrow, &beneficiary)?;
 
-    let current_ledger = env.ledger().sequence();
-    if current_ledger <= escrow.deadline + EMERGENCY_REFUND_DELAY {
-        return Err(Error::from_contract_error(DeCentPayError::EmergencyPeriodNotReached as u32));
+    idle_yield::unwind(env, escrow_id, &escrow)?;
+    escrow.status = EscrowStatus::Refunded;
+    finality::record_closed(env, escrow_id);
+
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -refund_amount)?;
+    refund_to_depositor(env, escrow_id, &escrow, refund_amount)?;
+
+    // A voluntary exit is recorded but handled in good faith
+    ratings::record_adverse_outcome(env, &beneficiary, |record| record.withdrawn += 1);
+    marketplace::release_reputation_stake(env, escrow_id, &beneficiary, 0);
+    stakes::return_stake(env, escrow_id, &escrow, &beneficiary)?;
+    bonds::return_performance_bond(env, escrow_id, &escrow)?;
+    escrow_core::refund_platform_fee(env, escrow_id, &escrow)?;
+    work_lifecycle::refund_revision_fees(env, escrow_id, &escrow)?;
+    retainage::start_warranty(env, escrow_id);
+    dispute_resolution::refund_arbitration_fee(env, escrow_id, &escrow)?;
+
+    escrow_core::save_escrow(env, escrow_id, &escrow);
+    milestone_log::record_activity(env, escrow_id, &beneficiary, ActivityAction::FundsReturned, None);
+    Ok(())
+}
+
+/// Check the beneficiary may hand an escrow back, returning the amount refunded to the depositor
+pub fn check_return_funds(env: &Env, escrow_id: u32, escrow: &EscrowData, beneficiary: &Address) -> Result<i128, Error> {
+    if escrow.beneficiary.as_ref() != Some(beneficiary) {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyBeneficiary as u32));
     }
+    crowdfunding::screen_contributors(env, escrow_id)?;
 
-    if escrow.status == EscrowStatus::Released || escrow.status == EscrowStatus::Refunded {
+    if escrow.status != EscrowStatus::Pending && escrow.status != EscrowStatus::InProgress {
         return Err(Error::from_contract_error(DeCentPayError::CannotRefund as u32));
     }
 
@@ -96,47 +230,83 @@ pub fn emergency_refund_after_deadline(env: &Env, escrow_id: u32, depositor: Add
     if refund_amount <= 0 {
         return Err(Error::from_contract_error(DeCentPayError::NothingToRefund as u32));
     }
+    Ok(refund_amount)
+}
 
-    escrow.status = EscrowStatus::Expired;
+/// Route refunds for an escrow to a different address than the funding wallet (None to reset)
+pub fn set_refund_address(env: &Env, escrow_id: u32, refund_address: Option<Address>, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
 
-    // Update escrowed amount
-    let token_key = escrow.token.clone().unwrap_or_else(|| env.current_contract_address());
-    let current_escrowed: i128 = env
-        .storage()
-        .instance()
-        .get(&DataKey::EscrowedAmount(token_key.clone()))
-        .unwrap_or(0);
     env.storage()
         .instance()
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    if let Some(refund_address) = refund_address {
+        compliance::require_not_flagged(env, &refund_address)?;
+        env.storage()
+            .instance()
+            .set(&EscrowKey::RefundAddress(escrow_id), &refund_address);
+    } else {
+        env.storage().instance().remove(&EscrowKey::RefundAddress(escrow_id));
+    }
+    Ok(())
+}
+
+/// Get the refund address registered for an escrow
+pub fn get_refund_address(env: &Env, escrow_id: u32) -> Option<Address> {
     env.storage()
         .instance()
-        .set(
-            &DataKey::EscrowedAmount(token_key),
-            &(current_escrowed - refund_amount),
-        );
-
-    // Transfer refund
-    if let Some(token_addr) = escrow.token.clone() {
-        let token_client = token::Client::new(env, &token_addr);
-        token_client.transfer(&env.current_contract_address(), &depositor, &refund_amount);
-    } else {
-        // Native XLM refund
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::RefundAddress(escrow_id))
+}
+
+/// Resolve where funds returned to the depositor of an escrow should go
+pub fn refund_destination(env: &Env, escrow_id: u32, escrow: &EscrowData) -> Address {
+    get_refund_address(env, escrow_id).unwrap_or_else(|| escrow.depositor.clone())
+}
+
+/// Send refunded principal back to whoever funded the escrow: the refund destination, or
+/// every contributor pro-rata for crowdfunded escrows
+pub fn return_principal(env: &Env, escrow_id: u32, escrow: &EscrowData, amount: i128) -> Result<(), Error> {
+    if !crowdfunding::refund_contributors(env, escrow_id, escrow, amount)? {
+        let destination = refund_destination(env, escrow_id, escrow);
+        escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &destination, amount);
     }
+    Ok(())
+}
 
-    escrow_core::save_escrow(env, escrow_id, &escrow);
+/// Return funds to the depositor, booking them for netting when it is active with the beneficiary
+pub fn refund_to_depositor(env: &Env, escrow_id: u32, escrow: &EscrowData, amount: i128) -> Result<(), Error> {
+    if crowdfunding::refund_contributors(env, escrow_id, escrow, amount)? {
+        return Ok(());
+    }
+    match &escrow.beneficiary {
+        Some(beneficiary) if netting::is_netting_active(env, &escrow.depositor, beneficiary) => {
+            netting::credit(env, escrow.token.as_ref(), &escrow.depositor, beneficiary, amount)?;
+        }
+        _ => {
+            let destination = refund_destination(env, escrow_id, escrow);
+            escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &destination, amount);
+        }
+    }
     Ok(())
 }
 
 pub fn extend_deadline(env: &Env, escrow_id: u32, depositor: Address, extra_seconds: u32) -> Result<(), Error> {
     depositor.require_auth();
 
-    if extra_seconds == 0 || extra_seconds > 2592000 {
-        // Max 30 days
+    if extra_seconds == 0 || extra_seconds > admin::get_duration_bounds(env).max_extension {
         return Err(Error::from_contract_error(DeCentPayError::InvalidExtension as u32));
     }
 
-    escrow_core::require_valid_escrow(env, escrow_id)?;
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
     let mut escrow = escrow_core::get_escrow(env, escrow_id)
         .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
 
@@ -148,8 +318,144 @@ pub fn extend_deadline(env: &Env, escrow_id: u32, depositor: Address, extra_seco
         return Err(Error::from_contract_error(DeCentPayError::CannotExtend as u32));
     }
 
-    escrow.deadline += extra_seconds as u32;
+    escrow.deadline += u64::from(extra_seconds);
     escrow_core::save_escrow(env, escrow_id, &escrow);
+    milestone_log::record_activity(env, escrow_id, &depositor, ActivityAction::DeadlineExtended, None);
     Ok(())
 }
 
+/// Give a milestone a due date `due_in` seconds from now (0 to clear). The depositor sets due dates
+/// alone until work starts; after that the beneficiary has to authorize the change as well.
+pub fn set_milestone_due_date(
+    env: &Env,
+    escrow_id: u32,
+    milestone_index: u32,
+    due_in: u32,
+    depositor: Address,
+) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::Pending && escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    if escrow.work_started {
+        if let Some(beneficiary) = &escrow.beneficiary {
+            beneficiary.require_auth();
+        }
+    }
+
+    let mut milestone = work_lifecycle::get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    milestone.due_at = if due_in == 0 {
+        0
+    } else {
+        env.ledger().timestamp() + u64::from(due_in)
+    };
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::Milestone(escrow_id, milestone_index), &milestone);
+    Ok(())
+}
+
+/// Whether an in-progress milestone is past its due date without having been delivered
+pub fn is_milestone_overdue(env: &Env, escrow_id: u32, milestone_index: u32) -> bool {
+    let Some(escrow) = escrow_core::get_escrow(env, escrow_id) else {
+        return false;
+    };
+    let Some(milestone) = work_lifecycle::get_milestone(env, escrow_id, milestone_index) else {
+        return false;
+    };
+    escrow.status == EscrowStatus::InProgress
+        && milestone.due_at != 0
+        && env.ledger().timestamp() > milestone.due_at
+        && matches!(
+            milestone.status,
+            MilestoneStatus::NotStarted | MilestoneStatus::Rejected | MilestoneStatus::PartiallyApproved
+        )
+}
+
+/// Refund the unpaid part of an overdue milestone to the depositor and drop it from the escrow
+pub fn refund_overdue_milestone(env: &Env, escrow_id: u32, milestone_index: u32, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_valid_escrow(env, escrow_id)?;
+    escrow_core::require_not_frozen(env, escrow_id)?;
+    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    compliance::require_not_flagged(env, &depositor)?;
+    compliance::require_not_flagged(env, &refund_destination(env, escrow_id, &escrow))?;
+
+    if !is_milestone_overdue(env, escrow_id, milestone_index) {
+        return Err(Error::from_contract_error(DeCentPayError::MilestoneNotOverdue as u32));
+    }
+
+    let mut milestone = work_lifecycle::get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    let refund_amount = milestone.amount;
+    milestone.status = MilestoneStatus::Removed;
+
+    idle_yield::unwind(env, escrow_id, &escrow)?;
+    escrow.total_amount -= refund_amount;
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -refund_amount)?;
+    refund_to_depositor(env, escrow_id, &escrow, refund_amount)?;
+    work_lifecycle::refund_revision_fee(env, escrow_id, milestone_index, &escrow)?;
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::Milestone(escrow_id, milestone_index), &milestone);
+    work_lifecycle::complete_if_paid(env, escrow_id, &mut escrow)?;
+    escrow_core::save_escrow(env, escrow_id, &escrow);
+    milestone_log::record_activity(env, escrow_id, &depositor, ActivityAction::MilestoneRefunded(milestone_index), None);
+    Ok(())
+}
+
+/// Whether an escrow's unpaid balance has already gone back through the emergency refund
+pub fn is_emergency_refunded(env: &Env, escrow_id: u32) -> bool {
+    env.storage()
+        .instance()
+        .get(&EscrowKey::EmergencyRefunded(escrow_id))
+        .unwrap_or(false)
+}
+
+/// Whether the emergency refund window has opened for an escrow with `deadline`
+pub fn is_emergency_refund_open(now: u64, deadline: u64) -> bool {
+    now > deadline + EMERGENCY_REFUND_DELAY
+}
+
+/// Get deadline-related status for an escrow
+pub fn get_time_status(env: &Env, escrow_id: u32) -> Result<TimeStatus, Error> {
+    escrow_core::require_valid_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    let current_time = env.ledger().timestamp();
+
+    Ok(TimeStatus {
+        current_time,
+        deadline: escrow.deadline,
+        is_expired: current_time >= escrow.deadline,
+        seconds_remaining: escrow.deadline.saturating_sub(current_time),
+        emergency_refund_open: is_emergency_refund_open(current_time, escrow.deadline),
+    })
+}
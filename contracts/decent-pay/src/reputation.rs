@@ -0,0 +1,132 @@
+use crate::admin;
+use crate::escrow_core;
+use crate::storage_types::{
+    AccountKey, ConfigKey, RecentReputation, ReputationSlash, Role, SlashReason, DAY_IN_SECONDS, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error, Vec};
+
+const DECAY_PERIOD: u64 = 180 * DAY_IN_SECONDS; // Earned reputation fades out linearly over 180 days
+const DEFAULT_DISPUTE_LOST_SLASH: u32 = 25;
+const DEFAULT_WORK_ABANDONED_SLASH: u32 = 50;
+const MAX_SLASH_HISTORY: u32 = 50;
+
+/// Add earned points to a user's recent reputation, decaying what was there first
+pub fn record_gain(env: &Env, user: &Address, points: u32) {
+    let now = env.ledger().timestamp();
+    let score = decayed_score(env, user, now).saturating_add(points);
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(
+        &AccountKey::RecentReputation(user.clone()),
+        &RecentReputation { score, updated_at: now },
+    );
+}
+
+/// Start decay tracking for a user who earned reputation before it existed, treating their whole
+/// lifetime score as earned now. Users already tracked are left alone.
+pub fn seed_recent(env: &Env, user: &Address) {
+    let key = AccountKey::RecentReputation(user.clone());
+    if env.storage().instance().has(&key) {
+        return;
+    }
+    let score = escrow_core::get_reputation(env, user.clone());
+    if score > 0 {
+        env.storage().instance().set(&key, &RecentReputation { score, updated_at: env.ledger().timestamp() });
+    }
+}
+
+/// Get a user's reputation with inactivity decay applied. Points fade out linearly over 180 days
+/// without new earnings and never exceed the lifetime score, which staking can draw down.
+pub fn get_effective_reputation(env: &Env, user: Address) -> u32 {
+    let score = decayed_score(env, &user, env.ledger().timestamp());
+    score.min(escrow_core::get_reputation(env, user))
+}
+
+/// Deduct the points configured for `reason` from a user's lifetime and recent reputation,
+/// flooring both at zero, and record why
+pub fn slash(env: &Env, escrow_id: u32, user: &Address, reason: SlashReason) {
+    let penalty = get_slash_points(env, reason);
+    let reputation = escrow_core::get_reputation(env, user.clone());
+    let points = penalty.min(reputation);
+    escrow_core::set_reputation(env, user.clone(), reputation - points);
+
+    let now = env.ledger().timestamp();
+    if let Some(mut recent) = env
+        .storage()
+        .instance()
+        .get::<AccountKey, RecentReputation>(&AccountKey::RecentReputation(user.clone()))
+    {
+        recent.score = recent.score.saturating_sub(penalty);
+        env.storage()
+            .instance()
+            .set(&AccountKey::RecentReputation(user.clone()), &recent);
+    }
+
+    let mut slashes = get_slash_history(env, user.clone());
+    if slashes.len() >= MAX_SLASH_HISTORY {
+        slashes.pop_front();
+    }
+    slashes.push_back(ReputationSlash {
+        escrow_id,
+        reason,
+        points,
+        slashed_at: now,
+    });
+    env.storage()
+        .instance()
+        .set(&AccountKey::ReputationSlashes(user.clone()), &slashes);
+}
+
+/// Set the reputation points deducted for a slashing reason (moderator)
+pub fn set_slash_points(env: &Env, reason: SlashReason, points: u32, caller: Address) -> Result<(), Error> {
+    admin::require_role(env, &caller, Role::Moderator)?;
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(&ConfigKey::SlashPoints(reason), &points);
+    Ok(())
+}
+
+/// Get the reputation points deducted for a slashing reason
+pub fn get_slash_points(env: &Env, reason: SlashReason) -> u32 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&ConfigKey::SlashPoints(reason))
+        .unwrap_or(match reason {
+            SlashReason::DisputeLost => DEFAULT_DISPUTE_LOST_SLASH,
+            SlashReason::WorkAbandoned => DEFAULT_WORK_ABANDONED_SLASH,
+        })
+}
+
+/// Get the most recent reputation slashes against a user, oldest first
+pub fn get_slash_history(env: &Env, user: Address) -> Vec<ReputationSlash> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&AccountKey::ReputationSlashes(user))
+        .unwrap_or(Vec::new(env))
+}
+
+fn decayed_score(env: &Env, user: &Address, now: u64) -> u32 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    let Some(recent) = env
+        .storage()
+        .instance()
+        .get::<AccountKey, RecentReputation>(&AccountKey::RecentReputation(user.clone()))
+    else {
+        return 0;
+    };
+
+    let remaining = DECAY_PERIOD.saturating_sub(now.saturating_sub(recent.updated_at));
+    let score = u64::from(recent.score) * remaining / DECAY_PERIOD;
+    u32::try_from(score).unwrap_or(u32::MAX)
+}
@@ -0,0 +1,307 @@
+use crate::compliance;
+use crate::dispute_resolution;
+use crate::escrow_core;
+use crate::milestone_log;
+use crate::profiles;
+use crate::refund_system;
+use crate::work_lifecycle;
+use crate::storage_types::{
+    ActivityAction, EscrowKey, DefectClaim, DefectVote, EscrowData, EscrowStatus, Retainage, DeCentPayError, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, String, Vec, Error};
+
+const MAX_RETAINAGE_BP: u32 = 2000; // 20% of each payout
+
+/// Withhold `retainage_bp` of every milestone payout until `warranty_period` seconds after the escrow closes
+pub fn set_retainage(
+    env: &Env,
+    escrow_id: u32,
+    retainage_bp: u32,
+    warranty_period: u32,
+    depositor: Address,
+) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::Pending || escrow.work_started {
+        return Err(Error::from_contract_error(DeCentPayError::WorkAlreadyStarted as u32));
+    }
+
+    if retainage_bp > MAX_RETAINAGE_BP {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    if retainage_bp == 0 {
+        env.storage().instance().remove(&EscrowKey::Retainage(escrow_id));
+    } else {
+        let retainage = Retainage {
+            retainage_bp,
+            warranty_period: u64::from(warranty_period),
+            held: 0,
+            release_at: 0,
+        };
+        env.storage()
+            .instance()
+            .set(&EscrowKey::Retainage(escrow_id), &retainage);
+    }
+    Ok(())
+}
+
+/// Get the retainage configured for an escrow
+pub fn get_retainage(env: &Env, escrow_id: u32) -> Option<Retainage> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::Retainage(escrow_id))
+}
+
+/// Hold back the retainage share of a payout, returning the amount withheld
+pub fn withhold(env: &Env, escrow_id: u32, amount: i128) -> i128 {
+    let Some(mut retainage) = get_retainage(env, escrow_id) else {
+        return 0;
+    };
+
+    let retained = retained_share(amount, retainage.retainage_bp);
+    retainage.held += retained;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::Retainage(escrow_id), &retainage);
+    retained
+}
+
+/// Part of a payout of `amount` withheld at `retainage_bp`
+pub fn retained_share(amount: i128, retainage_bp: u32) -> i128 {
+    amount * i128::from(retainage_bp) / 10000
+}
+
+/// Refund up to `amount` of the held retainage to the depositor, returning how much was clawed back
+pub fn claw_back(env: &Env, escrow_id: u32, escrow: &EscrowData, amount: i128) -> Result<i128, Error> {
+    let Some(mut retainage) = get_retainage(env, escrow_id) else {
+        return Ok(0);
+    };
+
+    let clawed = amount.min(retainage.held).max(0);
+    if clawed == 0 {
+        return Ok(0);
+    }
+    retainage.held -= clawed;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::Retainage(escrow_id), &retainage);
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -clawed)?;
+    refund_system::refund_to_depositor(env, escrow_id, escrow, clawed)?;
+    Ok(clawed)
+}
+
+/// Start the warranty window once the escrow has closed
+pub fn start_warranty(env: &Env, escrow_id: u32) {
+    let Some(mut retainage) = get_retainage(env, escrow_id) else {
+        return;
+    };
+    if retainage.release_at != 0 {
+        return;
+    }
+
+    retainage.release_at = env.ledger().timestamp() + retainage.warranty_period;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::Retainage(escrow_id), &retainage);
+}
+
+/// Release held retainage to the beneficiary once the warranty window passes without a defect dispute
+pub fn release_retainage(env: &Env, escrow_id: u32) -> Result<(), Error> {
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let retainage = get_retainage(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoRetainage as u32))?;
+
+    if retainage.release_at == 0 || env.ledger().timestamp() < retainage.release_at {
+        return Err(Error::from_contract_error(DeCentPayError::WarrantyActive as u32));
+    }
+
+    if get_defect_claim(env, escrow_id).is_some() {
+        return Err(Error::from_contract_error(DeCentPayError::DefectDisputed as u32));
+    }
+
+    pay_out(env, escrow_id, &escrow, retainage.held, 0)
+}
+
+/// Accept a completed escrow's work as a whole, releasing its held retainage to the beneficiary
+/// without waiting out the warranty window
+pub fn release_retention(env: &Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let retainage = check_release_retention(env, escrow_id, &escrow, &depositor)?;
+
+    pay_out(env, escrow_id, &escrow, retainage.held, 0)?;
+    milestone_log::record_activity(env, escrow_id, &depositor, ActivityAction::RetentionReleased, None);
+    Ok(())
+}
+
+/// Check the depositor may release a completed escrow's retainage early, returning it
+pub fn check_release_retention(env: &Env, escrow_id: u32, escrow: &EscrowData, depositor: &Address) -> Result<Retainage, Error> {
+    if escrow.depositor != *depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::Released {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidEscrowStatus as u32));
+    }
+
+    let retainage = get_retainage(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoRetainage as u32))?;
+
+    if get_defect_claim(env, escrow_id).is_some() {
+        return Err(Error::from_contract_error(DeCentPayError::DefectDisputed as u32));
+    }
+    Ok(retainage)
+}
+
+/// Claim back part of the held retainage for defects found during the warranty window
+pub fn open_defect_claim(env: &Env, escrow_id: u32, reason: String, amount: i128, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    let retainage = get_retainage(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoRetainage as u32))?;
+
+    if retainage.release_at == 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidEscrowStatus as u32));
+    }
+
+    if env.ledger().timestamp() >= retainage.release_at {
+        return Err(Error::from_contract_error(DeCentPayError::WarrantyExpired as u32));
+    }
+
+    if get_defect_claim(env, escrow_id).is_some() {
+        return Err(Error::from_contract_error(DeCentPayError::DefectDisputed as u32));
+    }
+
+    if amount <= 0 || amount > retainage.held {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    // Escrows without a panel have a platform arbiter drawn to rule on the claim
+    if escrow.arbiters.is_empty() {
+        let arbiter = dispute_resolution::draw_pool_arbiter(env, &escrow)?;
+        env.storage()
+            .instance()
+            .set(&EscrowKey::DefectArbiter(escrow_id), &arbiter);
+    }
+
+    let claim = DefectClaim {
+        reason,
+        amount,
+        opened_at: env.ledger().timestamp(),
+        votes: Vec::new(env),
+    };
+    env.storage()
+        .instance()
+        .set(&EscrowKey::DefectClaim(escrow_id), &claim);
+    Ok(())
+}
+
+/// Get the defect claim open against an escrow's retainage
+pub fn get_defect_claim(env: &Env, escrow_id: u32) -> Option<DefectClaim> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::DefectClaim(escrow_id))
+}
+
+/// Get the platform arbiter drawn to rule on the defect claim of an escrow without arbiters
+pub fn get_defect_arbiter(env: &Env, escrow_id: u32) -> Option<Address> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::DefectArbiter(escrow_id))
+}
+
+/// Arbiter vote on how much of a defect claim to uphold. Once the escrow's confirmation
+/// threshold agrees on an amount it goes to the depositor and the rest of the retainage
+/// to the beneficiary. Escrows without arbiters rely on the platform arbiter drawn for the claim.
+pub fn vote_defect_claim(env: &Env, escrow_id: u32, arbiter: Address, upheld_amount: i128) -> Result<(), Error> {
+    arbiter.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    let (is_arbiter, required) = if escrow.arbiters.is_empty() {
+        (get_defect_arbiter(env, escrow_id) == Some(arbiter.clone()), 1)
+    } else {
+        (escrow.arbiters.contains(&arbiter), escrow.required_confirmations.max(1))
+    };
+    if !is_arbiter {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyArbiter as u32));
+    }
+
+    let retainage = get_retainage(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoRetainage as u32))?;
+    let mut claim = get_defect_claim(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::DefectNotDisputed as u32))?;
+
+    if upheld_amount < 0 || upheld_amount > claim.amount {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    if claim.votes.iter().any(|vote| vote.arbiter == arbiter) {
+        return Err(Error::from_contract_error(DeCentPayError::AlreadyVoted as u32));
+    }
+
+    claim.votes.push_back(DefectVote { arbiter, upheld_amount });
+    let agreeing = claim.votes.iter().filter(|vote| vote.upheld_amount == upheld_amount).count();
+    if u32::try_from(agreeing).unwrap_or(u32::MAX) >= required {
+        return pay_out(env, escrow_id, &escrow, retainage.held - upheld_amount, upheld_amount);
+    }
+
+    env.storage()
+        .instance()
+        .set(&EscrowKey::DefectClaim(escrow_id), &claim);
+    Ok(())
+}
+
+fn pay_out(env: &Env, escrow_id: u32, escrow: &EscrowData, to_beneficiary: i128, to_depositor: i128) -> Result<(), Error> {
+    let beneficiary = escrow
+        .beneficiary
+        .clone()
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoRetainage as u32))?;
+
+    if to_beneficiary > 0 {
+        compliance::require_not_flagged(env, &beneficiary)?;
+        compliance::require_not_flagged(env, &profiles::payout_address(env, &beneficiary))?;
+    }
+
+    env.storage().instance().remove(&EscrowKey::Retainage(escrow_id));
+    env.storage().instance().remove(&EscrowKey::DefectClaim(escrow_id));
+    env.storage().instance().remove(&EscrowKey::DefectArbiter(escrow_id));
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -(to_beneficiary + to_depositor))?;
+    if to_beneficiary > 0 {
+        work_lifecycle::pay_beneficiary(env, escrow_id, escrow, &beneficiary, to_beneficiary)?;
+    }
+    if to_depositor > 0 {
+        refund_system::return_principal(env, escrow_id, escrow, to_depositor)?;
+    }
+    Ok(())
+}
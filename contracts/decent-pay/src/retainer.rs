@@ -0,0 +1,165 @@
+use crate::escrow_core;
+use crate::escrow_management::{self, Deposit};
+use crate::work_lifecycle;
+use crate::storage_types::{
+    DeCentPayError, EscrowCreateOptions, EscrowKey, EscrowStatus, MilestoneStatus, Retainer, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error, String, Vec};
+
+/// Create a retainer: an escrow funding `num_periods` payments of `amount_per_period`, one
+/// milestone per period. Once work starts, each period's payment unlocks for the beneficiary
+/// when the period ends unless the depositor has paused the retainer or disputed the period.
+pub fn create_retainer(
+    env: &Env,
+    depositor: Address,
+    beneficiary: Address,
+    token: Option<Address>,
+    amount_per_period: i128,
+    period_seconds: u32,
+    num_periods: u32,
+) -> Result<u32, Error> {
+    if amount_per_period <= 0 || period_seconds == 0 || num_periods == 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+    }
+    let duration = period_seconds
+        .checked_mul(num_periods)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidDuration as u32))?;
+
+    let mut milestone_amounts = Vec::new(env);
+    let mut milestone_descriptions = Vec::new(env);
+    for _ in 0..num_periods {
+        milestone_amounts.push_back(amount_per_period);
+        milestone_descriptions.push_back(String::from_str(env, "Retainer period"));
+    }
+
+    let escrow_id = escrow_management::create_escrow(
+        env,
+        depositor,
+        Some(beneficiary),
+        Vec::new(env),
+        0,
+        milestone_amounts,
+        milestone_descriptions,
+        token,
+        amount_per_period * i128::from(num_periods),
+        duration,
+        String::from_str(env, "Retainer"),
+        String::from_str(env, ""),
+        Deposit::Transfer,
+        EscrowCreateOptions::new(env),
+    )?;
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(
+        &EscrowKey::Retainer(escrow_id),
+        &Retainer {
+            period: u64::from(period_seconds),
+            started_at: 0,
+            paused_at: 0,
+        },
+    );
+    Ok(escrow_id)
+}
+
+/// Get the retainer terms of an escrow, if it is one
+pub fn get_retainer(env: &Env, escrow_id: u32) -> Option<Retainer> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::Retainer(escrow_id))
+}
+
+/// Start the first period of a retainer when the beneficiary starts work
+pub fn start(env: &Env, escrow_id: u32) {
+    if let Some(mut retainer) = get_retainer(env, escrow_id) {
+        retainer.started_at = env.ledger().timestamp();
+        env.storage()
+            .instance()
+            .set(&EscrowKey::Retainer(escrow_id), &retainer);
+    }
+}
+
+/// Claim the payment of a period that has ended
+pub fn claim_retainer_period(env: &Env, escrow_id: u32, period_index: u32, beneficiary: Address) -> Result<(), Error> {
+    beneficiary.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.beneficiary.as_ref() != Some(&beneficiary) {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyBeneficiary as u32));
+    }
+
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let retainer = get_retainer(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NotRetainer as u32))?;
+    if retainer.paused_at != 0 {
+        return Err(Error::from_contract_error(DeCentPayError::RetainerPaused as u32));
+    }
+    if env.ledger().timestamp() < retainer.started_at + u64::from(period_index + 1) * retainer.period {
+        return Err(Error::from_contract_error(DeCentPayError::PeriodNotEnded as u32));
+    }
+
+    // Disputed periods wait for the dispute to be resolved
+    let milestone = work_lifecycle::get_milestone(env, escrow_id, period_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    if milestone.status != MilestoneStatus::NotStarted && milestone.status != MilestoneStatus::Submitted {
+        return Err(Error::from_contract_error(DeCentPayError::MilestoneAlreadyProcessed as u32));
+    }
+
+    work_lifecycle::approve_and_pay(env, escrow_id, period_index, escrow, milestone)
+}
+
+/// Pause a running retainer; no period unlocks until it is resumed
+pub fn pause_retainer(env: &Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+    let mut retainer = load_for_depositor(env, escrow_id, &depositor)?;
+    if retainer.paused_at != 0 {
+        return Err(Error::from_contract_error(DeCentPayError::RetainerPaused as u32));
+    }
+
+    retainer.paused_at = env.ledger().timestamp();
+    env.storage()
+        .instance()
+        .set(&EscrowKey::Retainer(escrow_id), &retainer);
+    Ok(())
+}
+
+/// Resume a paused retainer, pushing every remaining period back by the time spent paused
+pub fn resume_retainer(env: &Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+    let mut retainer = load_for_depositor(env, escrow_id, &depositor)?;
+    if retainer.paused_at == 0 {
+        return Err(Error::from_contract_error(DeCentPayError::RetainerNotPaused as u32));
+    }
+
+    retainer.started_at += env.ledger().timestamp() - retainer.paused_at;
+    retainer.paused_at = 0;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::Retainer(escrow_id), &retainer);
+    Ok(())
+}
+
+fn load_for_depositor(env: &Env, escrow_id: u32, depositor: &Address) -> Result<Retainer, Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != *depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    get_retainer(env, escrow_id).ok_or_else(|| Error::from_contract_error(DeCentPayError::NotRetainer as u32))
+}
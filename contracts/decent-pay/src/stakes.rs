@@ -0,0 +1,143 @@
+use crate::escrow_core;
+use crate::marketplace;
+use crate::storage_types::{
+    ApplicationStake, DeCentPayError, EscrowData, EscrowKey, EscrowStatus, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error};
+
+/// Require applicants to a newly created open job to lock `amount` of the escrow token,
+/// forfeited if the hired freelancer does not start work within `grace_period` seconds
+pub fn set_application_stake(env: &Env, escrow_id: u32, escrow: &EscrowData, amount: i128, grace_period: u64) -> Result<(), Error> {
+    if !escrow.is_open_job {
+        return Err(Error::from_contract_error(DeCentPayError::NotOpenJob as u32));
+    }
+
+    if amount < 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::ApplicationStake(escrow_id), &ApplicationStake { amount, grace_period });
+    Ok(())
+}
+
+/// Get the stake an open job requires from applicants
+pub fn get_application_stake(env: &Env, escrow_id: u32) -> Option<ApplicationStake> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::ApplicationStake(escrow_id))
+}
+
+/// Get the application stake a freelancer has locked on a job
+pub fn get_locked_stake(env: &Env, escrow_id: u32, freelancer: Address) -> i128 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::LockedStake(escrow_id, freelancer))
+        .unwrap_or(0)
+}
+
+/// Lock the job's application stake from an applicant
+pub fn lock_stake(env: &Env, escrow_id: u32, escrow: &EscrowData, freelancer: &Address) -> Result<(), Error> {
+    let Some(stake) = get_application_stake(env, escrow_id) else {
+        return Ok(());
+    };
+
+    escrow_core::transfer(env, escrow.token.as_ref(), freelancer, &env.current_contract_address(), stake.amount);
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), stake.amount)?;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::LockedStake(escrow_id, freelancer.clone()), &stake.amount);
+    Ok(())
+}
+
+/// Start the hired freelancer's window to begin work before their stake can be forfeited
+pub fn start_grace_period(env: &Env, escrow_id: u32, freelancer: &Address) {
+    if get_locked_stake(env, escrow_id, freelancer.clone()) == 0 {
+        return;
+    }
+    if let Some(stake) = get_application_stake(env, escrow_id) {
+        env.storage().instance().set(
+            &EscrowKey::StakeStartBy(escrow_id),
+            &env.ledger().timestamp().saturating_add(stake.grace_period),
+        );
+    }
+}
+
+/// Give a freelancer back their locked stake, if any
+pub fn return_stake(env: &Env, escrow_id: u32, escrow: &EscrowData, freelancer: &Address) -> Result<(), Error> {
+    release_stake(env, escrow_id, escrow, freelancer, freelancer)
+}
+
+/// Give every applicant still holding a stake their stake back
+pub fn return_all_stakes(env: &Env, escrow_id: u32, escrow: &EscrowData) -> Result<(), Error> {
+    for application in marketplace::get_applications(env, escrow_id).iter() {
+        return_stake(env, escrow_id, escrow, &application.freelancer)?;
+    }
+    Ok(())
+}
+
+/// Forfeit the hired freelancer's stake to the depositor once they let the grace period pass
+/// without starting work
+pub fn slash_application_stake(env: &Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::Pending || escrow.work_started {
+        return Err(Error::from_contract_error(DeCentPayError::WorkAlreadyStarted as u32));
+    }
+
+    let beneficiary = escrow
+        .beneficiary
+        .clone()
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoApplicationStake as u32))?;
+    let start_by: u64 = env
+        .storage()
+        .instance()
+        .get(&EscrowKey::StakeStartBy(escrow_id))
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoApplicationStake as u32))?;
+    if env.ledger().timestamp() <= start_by {
+        return Err(Error::from_contract_error(DeCentPayError::StakeGracePeriodActive as u32));
+    }
+
+    release_stake(env, escrow_id, &escrow, &beneficiary, &depositor)
+}
+
+/// Pay out a freelancer's locked stake to `recipient` and clear it
+fn release_stake(
+    env: &Env,
+    escrow_id: u32,
+    escrow: &EscrowData,
+    freelancer: &Address,
+    recipient: &Address,
+) -> Result<(), Error> {
+    let amount = get_locked_stake(env, escrow_id, freelancer.clone());
+    if amount == 0 {
+        return Ok(());
+    }
+
+    env.storage()
+        .instance()
+        .remove(&EscrowKey::LockedStake(escrow_id, freelancer.clone()));
+    if escrow.beneficiary.as_ref() == Some(freelancer) {
+        env.storage().instance().remove(&EscrowKey::StakeStartBy(escrow_id));
+    }
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -amount)?;
+    escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), recipient, amount);
+    Ok(())
+}
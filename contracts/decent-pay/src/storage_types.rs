@@ -1,9 +1,19 @@
-use soroban_sdk::{contracttype, Address, String, Vec, Error};
+use soroban_sdk::{contractevent, contracttype, Address, BytesN, Env, String, Symbol, Vec, Error};
 
 // Constants
 pub const DAY_IN_LEDGERS: u32 = 17280;
+pub const DAY_IN_SECONDS: u64 = 86_400;
 pub const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
 pub const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+pub const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+pub const DEFAULT_MAX_REVIEW_LENGTH: u32 = 1000;
+pub const MAX_REVIEW_EXCERPT_LENGTH: u32 = 140;
+pub const SECONDS_PER_LEDGER: u32 = 5; // Approximate ledger close time
+pub const DEFAULT_MIN_DURATION: u32 = 3600; // 1 hour
+pub const DEFAULT_MAX_DURATION: u32 = 31_536_000; // 365 days
+pub const DEFAULT_MAX_EXTENSION: u32 = 2_592_000; // 30 days
+pub const CONTRACT_VERSION: u32 = 7; // Storage layout version of this build
 
 // Error codes for proper error handling
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -13,6 +23,12 @@ pub enum DeCentPayError {
     FeeTooHigh = 1001,
     NotOwner = 1002,
     NotInitialized = 1003,
+    InsufficientFees = 1004,
+    InvalidVersion = 1005,
+    ContractPaused = 1006,
+    MissingRole = 1007,
+    AccountingUnderflow = 1008,
+    MigrationPending = 1009,
     
     // Escrow errors (1100-1199)
     EscrowNotFound = 1100,
@@ -20,6 +36,7 @@ pub enum DeCentPayError {
     InvalidEscrowStatus = 1102,
     WorkAlreadyStarted = 1103,
     WorkNotStarted = 1104,
+    EscrowFrozen = 1105,
     
     // Escrow creation errors (1200-1299)
     JobCreationPaused = 1200,
@@ -29,6 +46,10 @@ pub enum DeCentPayError {
     TooManyArbiters = 1204,
     InvalidConfirmations = 1205,
     TokenNotWhitelisted = 1206,
+    InsufficientClientReputation = 1207,
+    BelowMinimumEscrow = 1208,
+    TokenBlacklisted = 1209,
+    MilestoneTotalMismatch = 1210,
     
     // Marketplace errors (1300-1399)
     NotOpenJob = 1300,
@@ -38,12 +59,34 @@ pub enum DeCentPayError {
     OnlyDepositor = 1304,
     FreelancerNotApplied = 1305,
     AlreadyApplied = 1306,
+    AlreadyWatching = 1307,
+    NotWatching = 1308,
+    WatchlistFull = 1309,
+    InsufficientReputation = 1310,
+    AlreadyStaked = 1311,
+    FreelancerAlreadyAccepted = 1312,
+    ApplicationClosed = 1313,
+    NoNegotiation = 1314,
+    TooManySkillTags = 1315,
+    ApplicationDeadlinePassed = 1316,
+    JobNotExpired = 1317,
+    NotInvited = 1318,
+    InvitationExpired = 1319,
     
     // Milestone errors (1400-1499)
     InvalidMilestone = 1400,
     MilestoneAlreadySubmitted = 1401,
     MilestoneNotSubmitted = 1402,
     MilestoneAlreadyProcessed = 1403,
+    NoteTooLong = 1404,
+    LogFull = 1405,
+    TooManyDeliverables = 1406,
+    NoScheduledRelease = 1407,
+    ReleaseNotDue = 1408,
+    NoStandingApproval = 1409,
+    ExceedsStandingApproval = 1410,
+    NoReviewPeriod = 1411,
+    MilestoneNotOverdue = 1412,
     
     // Refund errors (1500-1599)
     NothingToRefund = 1500,
@@ -67,6 +110,86 @@ pub enum DeCentPayError {
     RatingAlreadySubmitted = 1801,
     InvalidRating = 1802,
     OnlyDepositorCanRate = 1803,
+    ReviewTooLong = 1804,
+    RatingNotFound = 1805,
+    RatingEditWindowClosed = 1806,
+    AlreadyResponded = 1807,
+
+    // Compliance errors (1900-1999)
+    KycRequired = 1900,
+    AddressFlagged = 1901,
+
+    // Profile errors (2000-2099)
+    AlreadyEndorsed = 2000,
+    TooManyEndorsements = 2001,
+
+    // Bond errors (2100-2199)
+    NoBond = 2100,
+    BondNotSettleable = 2101,
+    OnlyArbiter = 2102,
+    JobNotAbandoned = 2103,
+    ApplicantsAlreadyStaked = 2104,
+    NoApplicationStake = 2105,
+    StakeGracePeriodActive = 2106,
+    BelowBondThreshold = 2107,
+
+    // Escalation errors (2200-2299)
+    AlreadyEscalated = 2200,
+    NotEscalated = 2201,
+    EscalationNotMatched = 2202,
+    ResponseWindowOpen = 2203,
+    ResponseWindowClosed = 2204,
+    DisputeEscalated = 2205,
+    ArbiterAlreadyAppointed = 2206,
+    NoArbiterPool = 2207,
+    EvidenceWindowClosed = 2208,
+    TooMuchEvidence = 2209,
+
+    // Change order errors (2300-2399)
+    ChangeOrderNotFound = 2300,
+    ChangeOrderClosed = 2301,
+    ChangeOrderAlreadyAccepted = 2302,
+    MilestoneLocked = 2303,
+
+    // Retainage errors (2400-2499)
+    NoRetainage = 2400,
+    WarrantyActive = 2401,
+    WarrantyExpired = 2402,
+    DefectDisputed = 2403,
+    DefectNotDisputed = 2404,
+    AlreadyVoted = 2405,
+
+    // Finality errors (2500-2599)
+    EscrowFinal = 2500,
+    EscrowNotClosed = 2501,
+    ChallengeWindowOpen = 2502,
+
+    // Yield errors (2600-2699)
+    YieldAdapterNotSet = 2600,
+    YieldPositionOpen = 2601,
+    NoYieldPosition = 2602,
+    YieldShortfall = 2603,
+
+    // Retainer errors (2700-2799)
+    NotRetainer = 2700,
+    RetainerPaused = 2701,
+    RetainerNotPaused = 2702,
+    PeriodNotEnded = 2703,
+
+    // Streaming errors (2800-2899)
+    NoVestingStream = 2800,
+    NothingVested = 2801,
+    StreamDisputed = 2802,
+    BelowMinPayout = 2803,
+
+    // Time and materials errors (2900-2999)
+    NotTimeAndMaterials = 2900,
+    ExceedsCap = 2901,
+    EntriesOutstanding = 2902,
+
+    // Co-signature errors (3000-3099)
+    CosignRequired = 3000,
+    CosignNotRequested = 3001,
 }
 
 impl From<DeCentPayError> for Error {
@@ -84,7 +207,9 @@ pub enum EscrowStatus {
     Released,
     Refunded,
     Disputed,
-    Expired,
+    Expired, // Open job that stopped taking applications without hiring anyone
+    Cancelled, // Open job withdrawn by its poster before hiring
+    Funding, // Created unfunded; becomes Pending once the deposit is complete
 }
 
 // Enum for Milestone Status
@@ -97,6 +222,8 @@ pub enum MilestoneStatus {
     Disputed,
     Resolved,
     Rejected,
+    Removed, // Dropped by a change order
+    PartiallyApproved, // Part paid out, the rest awaiting rework
 }
 
 // Milestone struct
@@ -106,12 +233,33 @@ pub struct Milestone {
     pub description: String,
     pub amount: i128,
     pub status: MilestoneStatus,
-    pub submitted_at: u32,
-    pub approved_at: u32,
-    pub disputed_at: u32,
+    pub submitted_at: u64, // Unix timestamp, 0 until submitted
+    pub approved_at: u64,
+    pub disputed_at: u64,
     pub disputed_by: Option<Address>,
     pub dispute_reason: Option<String>,
     pub rejection_reason: Option<String>,
+    pub completed_by_client: bool, // Approved by the depositor without a submission
+    pub manifest: Vec<Deliverable>, // Files delivered with the latest submission
+    pub released: i128,            // Paid out through partial approvals; `amount` is what remains
+    pub due_at: u64,               // Unix timestamp the milestone is due by, 0 if it has no due date
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Deliverable {
+    pub label: String,
+    pub hash: BytesN<32>,
+    pub size: u64, // Bytes
+}
+
+// Arbiter panel for a milestone, either added to or replacing the escrow's arbiters
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct MilestoneArbiters {
+    pub arbiters: Vec<Address>,
+    pub required_confirmations: u32,
+    pub replace_default: bool,
 }
 
 // Application struct
@@ -121,7 +269,54 @@ pub struct Application {
     pub freelancer: Address,
     pub cover_letter: String,
     pub proposed_timeline: u32,
-    pub applied_at: u32,
+    pub applied_at: u64, // Unix timestamp
+    pub status: ApplicationStatus,
+    pub rejection_reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ApplicationStatus {
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+// Counter-offer from a client on an application
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Negotiation {
+    pub proposed_amount: i128,   // New escrow total
+    pub proposed_timeline: u32,
+    pub held_top_up: i128,       // Extra funding and platform fee pulled from the depositor while the offer is open
+    pub proposed_at: u64,        // Unix timestamp
+    pub status: NegotiationStatus,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum NegotiationStatus {
+    Proposed,
+    Accepted,
+    Declined,
+}
+
+// A depositor's direct offer of an open job to a freelancer, skipping the application flow
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Invitation {
+    pub invited_at: u64,  // unix timestamp
+    pub expires_at: u64,  // unix timestamp after which it can no longer be accepted
+    pub accepted: bool,
+}
+
+// Emitted when a freelancer retracts an application
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApplicationWithdrawn {
+    #[topic]
+    pub escrow_id: u32,
+    pub freelancer: Address,
 }
 
 // Rating struct
@@ -132,18 +327,723 @@ pub struct Rating {
     pub freelancer: Address,
     pub client: Address,
     pub rating: u32, // 1-5 stars
-    pub review: String,
-    pub rated_at: u32,
+    pub review: String,                    // Full review, or a short excerpt when hashed
+    pub review_hash: Option<BytesN<32>>,   // Hash of the full off-chain review
+    pub rated_at: u64,                     // Unix timestamp
+    pub edited_at: u64,                    // Timestamp of the last edit, 0 if never edited
+    pub response: Option<String>,          // The freelancer's public reply
 }
 
-// Badge enum
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+// Adverse outcomes counted against a freelancer's badge
+#[derive(Clone, Debug, Default)]
+#[contracttype]
+pub struct AdverseRecord {
+    pub disputes_lost: u32,
+    pub abandoned: u32,       // Escrows refunded after the freelancer started but never delivered
+    pub withdrawn: u32,       // Escrows the freelancer handed back voluntarily; tracked but not penalised
+}
+
+// Badge enum, ordered from lowest to highest
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 #[contracttype]
 pub enum Badge {
-    Beginner,      // 0-4 completed projects
-    Intermediate,  // 5-14 completed projects
-    Advanced,      // 15-49 completed projects
-    Expert,        // 50+ completed projects
+    #[default]
+    Beginner,      // Meets no higher tier's BadgeCriteria
+    Intermediate,  // By default 5+ completed projects, at most 25% of disputes lost
+    Advanced,      // By default 15+ completed projects, 4.00+ stars, at most 10% lost
+    Expert,        // By default 50+ completed projects, 4.50+ stars, at most 5% lost
+}
+
+// Payer standing freelancers can check before accepting work, per token
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum PayerBadge {
+    Unverified,    // Released 1,000 whole units or less
+    VerifiedPayer, // Released more than 1,000 whole units, e.g. >1000 XLM
+    TrustedPayer,  // Released more than 10,000 whole units
+}
+
+// What a freelancer needs to hold a badge tier
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct BadgeCriteria {
+    pub min_completed: u32,       // Completed projects, after the adverse outcome penalty
+    pub min_rating_score: u32,    // Average stars scaled by 100, unrated freelancers score 0
+    pub max_dispute_loss_bp: u32, // Lost disputes as basis points of completed projects plus lost disputes
+}
+
+// A ranked user on the reputation leaderboard
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct LeaderboardEntry {
+    pub user: Address,
+    pub reputation: u32,
+}
+
+// A freelancer's standing against the next badge tier
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct BadgeProgress {
+    pub badge: Badge,
+    pub next_badge: Badge, // Same as badge at the top tier
+    pub next_criteria: BadgeCriteria,
+    pub completed: u32,
+    pub rating_score: u32,
+    pub dispute_loss_bp: u32,
+    pub completed_missing: u32, // More completed projects the next tier needs
+    pub rating_met: bool,
+    pub dispute_record_met: bool,
+}
+
+// Freelancer payout preferences, readable by clients before hiring
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct PayoutPreferences {
+    pub min_payout: i128,                // Smallest vested amount worth claiming, short of a stream's last remainder
+    pub payout_address: Option<Address>, // Receives payouts instead of the freelancer's own address
+}
+
+// Skill endorsement from a counterparty of a completed escrow
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct Endorsement {
+    pub endorser: Address,
+    pub skill: Symbol,
+    pub escrow_id: u32,
+    pub endorsed_at: u64, // Unix timestamp
+}
+
+// Thresholds a freelancer must meet to apply to an open job
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct ApplicationRequirements {
+    pub min_reputation: u32,
+    pub min_badge: Badge, // Beginner admits everyone
+}
+
+// Requirements a depositor must meet to post escrows above a value threshold
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct PostingGate {
+    pub value_threshold: i128,
+    pub min_reputation: u32,
+    pub min_completed_escrows: u32,
+}
+
+// External attestation contract consulted for KYC above a value threshold
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct KycConfig {
+    pub attestation_contract: Address,
+    pub value_threshold: i128,
+}
+
+// Owner-configurable limits on escrow duration and deadline extensions (seconds)
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct DurationBounds {
+    pub min_duration: u32,
+    pub max_duration: u32,
+    pub max_extension: u32,
+}
+
+// Per-token value thresholds, in the token's smallest unit
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ValueThresholds {
+    pub min_escrow_value: i128,          // Smallest escrow that can be created
+    pub reputation_eligible_value: i128, // Smallest escrow that earns reputation
+}
+
+// Platform fee for escrows of at least `min_amount` of a token, in its smallest unit
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct FeeTier {
+    pub min_amount: i128,
+    pub fee_bp: u32,
+}
+
+// Operational roles the owner can delegate; admins hold every role
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Role {
+    Admin,     // Every role, platform policy settings and delegating the other roles
+    Moderator, // Token whitelisting, arbiter authorization and pausing
+    Treasurer, // Fee settings
+}
+
+// Global contract configuration
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct Config {
+    pub owner: Address,
+    pub fee_collector: Address,
+    pub platform_fee_bp: u32,
+    pub job_creation_paused: bool,
+    pub min_duration: u32,
+    pub max_duration: u32,
+    pub max_extension: u32,
+}
+
+// Dry-run breakdown of a prospective escrow and its milestones
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct EscrowQuote {
+    pub platform_fee: i128,
+    pub milestone_payouts: Vec<i128>, // Paid to the beneficiary per milestone net of retainage; the fee is charged on top
+    pub retainage: i128,              // Withheld across all milestones until the warranty window passes
+    pub beneficiary_total: i128,
+    pub depositor_total: i128,        // Amount the depositor must authorize
+}
+
+// One escrow of a batch created by the same depositor
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct EscrowCreateParams {
+    pub beneficiary: Option<Address>,
+    pub arbiters: Vec<Address>,
+    pub required_confirmations: u32,
+    pub milestones: Vec<(i128, String)>,
+    pub token: Option<Address>,
+    pub total_amount: i128,
+    pub duration: u32,
+    pub project_title: String,
+    pub project_description: String,
+}
+
+// Optional terms of an escrow, fixed when it is created
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct EscrowCreateOptions {
+    pub arbitration_fee: ArbitrationFee,  // Held from the depositor on top of the deposit
+    pub min_reputation: u32,              // Open jobs only; 0 admits everyone
+    pub min_badge: Badge,                 // Open jobs only; Beginner admits everyone
+    pub referrer: Option<Address>,        // Credited with a share of the platform fee
+    pub application_stake: i128,          // Open jobs only; locked by each applicant, 0 for none
+    pub stake_grace_period: u64,          // Seconds the hired applicant has to start work before forfeiting it
+    pub review_period: u64,               // Seconds a submission waits for review before auto-release; 0 for none
+    pub category: JobCategory,            // Uncategorized leaves the job out of the category listings
+    pub skill_tags: Vec<Symbol>,          // At most five
+    pub team_splits: Vec<(Address, u32)>, // Basis-point shares of each payout, the beneficiary's included; empty for none
+    pub application_period: u64,          // Open jobs only; seconds after posting that applications close, 0 for none
+    pub milestone_arbiters: Vec<(u32, MilestoneArbiters)>, // Panels for single milestones, by milestone index
+}
+
+impl EscrowCreateOptions {
+    /// Options that add no terms to an escrow
+    pub fn new(env: &Env) -> Self {
+        EscrowCreateOptions {
+            arbitration_fee: ArbitrationFee::None,
+            min_reputation: 0,
+            min_badge: Badge::Beginner,
+            referrer: None,
+            application_stake: 0,
+            stake_grace_period: 0,
+            review_period: 0,
+            category: JobCategory::Uncategorized,
+            skill_tags: Vec::new(env),
+            team_splits: Vec::new(env),
+            application_period: 0,
+            milestone_arbiters: Vec::new(env),
+        }
+    }
+}
+
+// Fee-relevant options of a prospective escrow
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct EscrowOptions {
+    pub is_open_job: bool,
+    pub arbitration_fee: ArbitrationFee,
+    pub depositor: Option<Address>, // Quotes the depositor's fee exemption, if any
+    pub retainage_bp: u32,          // Share of each payout withheld until the warranty window passes
+}
+
+// Fee and payout breakdown for a prospective escrow
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct FeeQuote {
+    pub amount: i128,
+    pub platform_fee: i128,        // Held on top of the deposit until work starts, refunded if it never does
+    pub arbiter_fee: i128,         // Held on top of the deposit for arbiters, refunded if never needed
+    pub discount: i128,            // Reduction of the platform fee
+    pub posting_fee: i128,         // Charged on top of the deposit for open jobs
+    pub seriousness_deposit: i128, // Held on top of the deposit for open jobs, returned on a hire or proper close
+    pub depositor_total: i128,     // Amount the depositor must authorize
+    pub retainage: i128,           // Withheld from payouts until the warranty window passes
+    pub beneficiary_net: i128,     // Amount the beneficiary is paid as milestones are released
+}
+
+// Entrypoints a caller may currently invoke on an escrow (milestone actions carry the index)
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum EscrowAction {
+    ApplyToJob,
+    AcceptFreelancer,
+    StartWork,
+    SubmitMilestone(u32),
+    ResubmitMilestone(u32),
+    ApproveMilestone(u32),
+    MarkMilestoneComplete(u32),
+    RejectMilestone(u32),
+    DisputeMilestone(u32),
+    RefundEscrow,
+    EmergencyRefund,
+    ExtendDeadline,
+    SubmitRating,
+    FundEscrow,
+    EditRating,
+    RespondToRating,
+    ReturnFunds,
+    CancelOpenJob,
+    ExpireOpenJob,
+    ClaimVested(u32),
+    ReleaseRetention,
+    SendBonus,
+    ApproveMilestonePartial(u32),
+    AcceptInvitation,
+    AcceptTerms,
+    ResolveDispute(u32),
+    CosignMilestone(u32),
+}
+
+// Reference to a milestone across escrows
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct MilestoneRef {
+    pub escrow_id: u32,
+    pub milestone_index: u32,
+    pub status: MilestoneStatus,
+}
+
+// Filter for paging through escrows
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum EscrowStatusFilter {
+    All,
+    Status(EscrowStatus),
+}
+
+// An escrow returned by the listing views
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct EscrowListing {
+    pub escrow_id: u32,
+    pub escrow: EscrowData,
+}
+
+// Everything a frontend shows for one escrow, in a single call
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct EscrowSummary {
+    pub escrow_id: u32,
+    pub escrow: EscrowData,
+    pub milestones: Vec<Milestone>,
+    pub application_count: u32,
+    pub disputes: Vec<DisputeSummary>, // Milestones currently in dispute
+    pub remaining_balance: i128,       // Funded and not yet paid out
+}
+
+// A milestone in dispute and where its arbitration stands
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct DisputeSummary {
+    pub milestone_index: u32,
+    pub disputed_by: Option<Address>,
+    pub reason: Option<String>,
+    pub disputed_at: u64,
+    pub votes: Vec<ArbiterVote>,
+    pub appointed_arbiter: Option<Address>,
+}
+
+// Home-screen aggregate for a user
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct Dashboard {
+    pub active_escrows: Vec<u32>,
+    pub awaiting_action: Vec<MilestoneRef>,
+    pub open_disputes: Vec<u32>,
+    pub jobs_awaiting_hire: Vec<u32>, // User's open jobs that received applications
+    pub pending_offers: Vec<u32>,     // Open jobs with a client counter-offer awaiting the user's answer
+    pub invitations: Vec<u32>,        // Open jobs the user was invited to and can still take
+    pub unclaimed_vested: Vec<VestedBalance>,
+    pub netting_credits: Vec<NettingCredit>,
+    pub referral_earnings: Vec<TokenBalance>,
+}
+
+// Vested, unclaimed part of a streamed milestone payout
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct VestedBalance {
+    pub escrow_id: u32,
+    pub milestone_index: u32,
+    pub amount: i128,
+}
+
+// Netted payouts the contract holds for a user against one counterparty
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct NettingCredit {
+    pub counterparty: Address,
+    pub token: Option<Address>, // None for native XLM
+    pub amount: i128,
+}
+
+// Amount held for a user in one token
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct TokenBalance {
+    pub token: Option<Address>, // None for native XLM
+    pub amount: i128,
+}
+
+// Milestone progress summary for an escrow
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct EscrowProgress {
+    pub milestone_count: u32,
+    pub not_started: u32,
+    pub submitted: u32,
+    pub approved: u32,
+    pub disputed: u32,
+    pub resolved: u32,
+    pub rejected: u32,
+    pub removed: u32,
+    pub partially_approved: u32,
+    pub paid_amount: i128,
+    pub total_amount: i128,
+    pub completion_percent: u32, // paid / total, 0-100
+}
+
+// Deadline-related state of an escrow, derived with the contract's own deadline math
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct TimeStatus {
+    pub current_time: u64, // Unix timestamp
+    pub deadline: u64,     // Unix timestamp
+    pub is_expired: bool,
+    pub seconds_remaining: u64,
+    pub emergency_refund_open: bool,
+}
+
+// Depositor track record used for client badges
+#[derive(Clone, Debug, Default)]
+#[contracttype]
+pub struct ClientRecord {
+    pub completed_hires: u32,
+    pub on_time_approvals: u32, // Approved within the review window after submission
+    pub late_approvals: u32,
+    pub rejections: u32,
+    pub disputes_lost: u32,
+}
+
+// Client badge enum
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum ClientBadge {
+    New,        // No meaningful track record yet
+    Reliable,   // 1+ completed hires, mostly on-time approvals
+    Trusted,    // 5+ completed hires, 80%+ on time, no lost disputes
+    Exemplary,  // 20+ completed hires, 90%+ on time, no lost disputes
+}
+
+// Token bond a freelancer locks at start_work, as basis points of the escrow value
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct PerformanceBond {
+    pub bond_bp: u32,
+    pub amount: i128, // Locked amount, 0 until work starts
+}
+
+// Token stake each applicant to an open job locks, returned unless the hired freelancer never starts
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct ApplicationStake {
+    pub amount: i128,
+    pub grace_period: u64, // Seconds the hired freelancer has to start work before forfeiting the stake
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenInfo {
+    pub symbol: String,
+    pub decimals: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecentReputation {
+    pub score: u32,      // Decayed score as of updated_at
+    pub updated_at: u64, // Unix timestamp the score was last earned at
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SlashReason {
+    DisputeLost,   // An arbiter panel ruled against the party
+    WorkAbandoned, // Started work expired undelivered
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReputationSlash {
+    pub escrow_id: u32,
+    pub reason: SlashReason,
+    pub points: u32,     // Points actually deducted, after the floor of zero
+    pub slashed_at: u64, // Unix timestamp
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum YieldRecipient {
+    Depositor,
+    Split(u32), // Basis points of the yield to the beneficiary, the rest to the depositor
+    Treasury,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct YieldPosition {
+    pub adapter: Address,          // Adapter the funds were deposited with
+    pub principal: i128,           // Escrowed funds deposited
+    pub recipient: YieldRecipient, // Who receives the accrued yield
+    pub deposited_at: u64,         // Unix timestamp
+}
+
+// Ongoing engagement paid per period; each period is one milestone of the escrow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Retainer {
+    pub period: u64,     // Seconds per period
+    pub started_at: u64, // Unix timestamp the first period began, 0 until work starts
+    pub paused_at: u64,  // Unix timestamp the depositor paused the retainer, 0 while running
+}
+
+// Hourly engagement billed against the escrow's funded cap; each time entry is one milestone
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimeAndMaterials {
+    pub hourly_rate: i128,
+    pub hours_logged: u32,
+}
+
+// Approved milestone payout vesting linearly to the beneficiary
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingStream {
+    pub amount: i128,
+    pub claimed: i128,
+    pub start: u64,    // Unix timestamp the milestone was approved
+    pub duration: u64, // Seconds until fully vested
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StandingApproval {
+    pub max_milestone_amount: i128, // Largest milestone that auto-approves
+    pub budget_remaining: i128,     // Total left to auto-approve
+    pub delay: u64,                 // Seconds after submission before auto-approval
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Retainage {
+    pub retainage_bp: u32,
+    pub warranty_period: u64,               // Seconds after the escrow closes
+    pub held: i128,
+    pub release_at: u64,                    // Unix timestamp, 0 until the escrow closes
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefectClaim {
+    pub reason: String,
+    pub amount: i128, // Portion of the held retainage claimed back
+    pub opened_at: u64, // Unix timestamp
+    pub votes: Vec<DefectVote>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefectVote {
+    pub arbiter: Address,
+    pub upheld_amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum ArbitrationFee {
+    #[default]
+    None,
+    BasisPoints(u32), // Of each disputed milestone
+    Flat(i128),       // Once for the escrow
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArbitrationDeposit {
+    pub fee: ArbitrationFee,
+    pub held: i128, // Still held for arbiters
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DisputeRuling {
+    Release,
+    Refund,
+    Split(i128), // Amount of the milestone paid to the beneficiary, the rest refunded
+    SplitRemainder(u32), // Basis points of everything unpaid paid to the beneficiary, the rest refunded
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArbiterVote {
+    pub arbiter: Address,
+    pub ruling: DisputeRuling,
+}
+
+// A piece of evidence attached to a disputed milestone
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeEvidence {
+    pub submitted_by: Address,
+    pub evidence_hash: BytesN<32>,
+    pub uri: String,
+    pub submitted_at: u64, // Unix timestamp
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Escalation {
+    pub milestone_index: u32,
+    pub initiator: Address,
+    pub stake: i128,     // Deposit each side puts up
+    pub respond_by: u64, // Unix timestamp by which the other side must match
+    pub matched: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    pub note: String,
+    pub hash: Option<BytesN<32>>,
+    pub posted_at: u64, // Unix timestamp
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Comment {
+    pub author: Address,
+    pub body: String,
+    pub posted_at: u64, // Unix timestamp
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubmissionRecord {
+    pub description: String,
+    pub manifest: Vec<Deliverable>,
+    pub submitted_at: u64,
+    pub rejection_reason: Option<String>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ActivityAction {
+    Created,
+    Hired,
+    WorkStarted,
+    MilestoneSubmitted(u32),
+    MilestoneResubmitted(u32),
+    MilestoneApproved(u32),
+    MilestonePartiallyApproved(u32),
+    MilestoneRejected(u32),
+    MilestoneDisputed(u32),
+    DisputeVoteCast(u32),
+    DeadlineExtended,
+    Refunded,
+    FundsReturned,
+    Cancelled,
+    Expired,
+    Frozen,
+    Unfrozen,
+    RetentionReleased,
+    BonusSent,
+    MilestoneRefunded(u32),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActivityEntry {
+    pub actor: Address,
+    pub action: ActivityAction,
+    pub recorded_at: u64, // Unix timestamp
+    pub note: Option<String>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MilestoneAdjustment {
+    pub milestone_index: u32,
+    pub amount_delta: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChangeOrderStatus {
+    Proposed,
+    Executed,
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChangeOrder {
+    pub requested_by: Address,
+    pub amount_deltas: Vec<MilestoneAdjustment>,
+    pub removed_milestones: Vec<u32>,
+    pub new_milestone_amounts: Vec<i128>,
+    pub new_milestone_descriptions: Vec<String>,
+    pub depositor_accepted: bool,
+    pub beneficiary_accepted: bool,
+    pub status: ChangeOrderStatus,
+    pub created_at: u64,  // Unix timestamp
+    pub executed_at: u64, // Unix timestamp, 0 until executed
+}
+
+// Job category enum
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum JobCategory {
+    SmartContracts,
+    Development,
+    Design,
+    Writing,
+    Marketing,
+    Consulting,
+    Other,
+    Uncategorized, // Only as a creation option, for a job without a category
+}
+
+// Achievement kinds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum Achievement {
+    FirstJob,            // First escrow completed as freelancer
+    TenFiveStarReviews,  // Ten 5-star ratings received
+    ZeroDisputeStreak,   // Ten consecutive completed escrows without a dispute
+    CategorySpecialist,  // Category reputation reached the specialist threshold
+    TenThousandEarned,   // 10,000 whole units of one token earned as freelancer
+    TenDisputesArbitrated, // Ten disputes settled by the arbiter's ruling
+}
+
+// Achievement earned by an address
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct AchievementRecord {
+    pub achievement: Achievement,
+    pub escrow_id: u32, // Escrow whose outcome earned it
+    pub earned_at: u64, // Unix timestamp
 }
 
 // EscrowData struct
@@ -158,6 +1058,29 @@ pub struct EscrowData {
     pub total_amount: i128,
     pub paid_amount: i128,
     pub platform_fee: i128,
+    pub deadline: u64, // Unix timestamp
+    pub status: EscrowStatus,
+    pub work_started: bool,
+    pub created_at: u64, // Unix timestamp
+    pub milestone_count: u32,
+    pub is_open_job: bool,
+    pub project_title: String,
+    pub project_description: String,
+    pub bonus_paid: i128, // Tips sent to the beneficiary after release
+}
+
+// Version 1 layouts, kept so migrations can read records written before the current schema
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct EscrowDataV1 {
+    pub depositor: Address,
+    pub beneficiary: Option<Address>,
+    pub arbiters: Vec<Address>,
+    pub required_confirmations: u32,
+    pub token: Option<Address>,
+    pub total_amount: i128,
+    pub paid_amount: i128,
+    pub platform_fee: i128,
     pub deadline: u32,
     pub status: EscrowStatus,
     pub work_started: bool,
@@ -168,6 +1091,52 @@ pub struct EscrowData {
     pub project_description: String,
 }
 
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct MilestoneV1 {
+    pub description: String,
+    pub amount: i128,
+    pub status: MilestoneStatus,
+    pub submitted_at: u32,
+    pub approved_at: u32,
+    pub disputed_at: u32,
+    pub disputed_by: Option<Address>,
+    pub dispute_reason: Option<String>,
+    pub rejection_reason: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct ApplicationV1 {
+    pub freelancer: Address,
+    pub cover_letter: String,
+    pub proposed_timeline: u32,
+    pub applied_at: u32,
+}
+
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct RatingV1 {
+    pub escrow_id: u32,
+    pub freelancer: Address,
+    pub client: Address,
+    pub rating: u32,
+    pub review: String,
+    pub rated_at: u32,
+}
+
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct RatingV2 {
+    pub escrow_id: u32,
+    pub freelancer: Address,
+    pub client: Address,
+    pub rating: u32,
+    pub review: String,
+    pub review_hash: Option<BytesN<32>>,
+    pub rated_at: u32,
+}
+
 // Storage keys enum
 #[derive(Clone)]
 #[contracttype]
@@ -190,5 +1159,145 @@ pub enum DataKey {
     FeeCollector,                   // -> Address
     Owner,                          // -> Address
     JobCreationPaused,              // -> bool
+    PostingFee(Address),            // token -> i128
+    PostingFeeRefundable,           // -> bool
+    PostingFeeHeld(u32),            // escrow_id -> i128 (held until hire when refundable)
+    PostingGate,                    // -> PostingGate
+    KycConfig,                      // -> KycConfig
+    ScreeningContract,              // -> Address
+    DurationBounds,                 // -> DurationBounds
+    Watchlist(Address),             // user -> Vec<u32> (saved escrow_ids)
+    PayoutPreferences(Address),     // freelancer -> PayoutPreferences
+    MaxReviewLength,                // -> u32
+    AdverseRecord(Address),         // freelancer -> AdverseRecord
+    ClientRecord(Address),          // depositor -> ClientRecord
+    Achievements(Address),          // user -> Vec<AchievementRecord>
+    FiveStarCount(Address),         // freelancer -> u32
+    DisputeFreeStreak(Address),     // freelancer -> u32
+    JobCategory(u32),               // escrow_id -> JobCategory
+    CategoryReputation(Address, JobCategory), // (user, category) -> u32
+    Endorsements(Address),          // user -> Vec<Endorsement>
+    ReputationStake(u32, Address),  // (escrow_id, freelancer) -> u32 (staked reputation points)
+    PerformanceBond(u32),           // escrow_id -> PerformanceBond
+    SeriousnessDeposit(Address),    // token -> i128 (required from open job posters)
+    SeriousnessDepositHeld(u32),    // escrow_id -> i128
+    MilestoneArbiters(u32, u32),    // (escrow_id, milestone_index) -> MilestoneArbiters
+}
+
+// Per-escrow record keys, kept apart from DataKey which is at the contract type variant limit
+#[derive(Clone)]
+#[contracttype]
+pub enum EscrowKey {
+    Escalation(u32),                // escrow_id -> Escalation
+    EscalationVotes(u32),           // escrow_id -> Vec<ArbiterVote> cast on a matched escalation
+    Checkpoints(u32, u32),          // (escrow_id, milestone_index) -> Vec<Checkpoint>; persistent
+    MilestoneComments(u32, u32),    // (escrow_id, milestone_index) -> Vec<Comment>; persistent
+    SubmissionHistory(u32, u32),    // (escrow_id, milestone_index) -> Vec<SubmissionRecord>; persistent
+    RevisionFee(u32, u32),          // (escrow_id, milestone_index) -> i128 funded for extra revisions
+    ChangeOrder(u32, u32),          // (escrow_id, order_id) -> ChangeOrder
+    ChangeOrderCount(u32),          // escrow_id -> u32
+    ChangeOrderFunding(u32, u32),   // (escrow_id, order_id) -> i128 paid in by the depositor while the order is open
+    Retainage(u32),                 // escrow_id -> Retainage
+    DefectClaim(u32),               // escrow_id -> DefectClaim against held retainage
+    DefectArbiter(u32),             // escrow_id -> Address drawn from the arbiter pool to rule on a defect claim
+    RefundAddress(u32),             // escrow_id -> Address refunds are sent to
+    ClosedAt(u32),                  // escrow_id -> unix timestamp the escrow was released or refunded
+    Final(u32),                     // escrow_id -> bool
+    Frozen(u32),                    // escrow_id -> bool, held by the owner pending investigation
+    ScheduledRelease(u32, u32),     // (escrow_id, milestone_index) -> unix timestamp to release at
+    StandingApproval(u32),          // escrow_id -> StandingApproval
+    YieldPosition(u32),             // escrow_id -> YieldPosition
+    DisputeVotes(u32, u32),         // (escrow_id, milestone_index) -> Vec<ArbiterVote>
+    DisputeEvidence(u32, u32),      // (escrow_id, milestone_index) -> Vec<DisputeEvidence>, in submission order; persistent
+    PlatformFeeHeld(u32),           // escrow_id -> i128 platform fee held on top of the deposit until work starts
+    CosignThreshold(u32),           // escrow_id -> i128 milestone amount above which approvals need an arbiter co-signature
+    CosignPending(u32, u32),        // (escrow_id, milestone_index) -> bool, approved and awaiting an arbiter co-signature
+    Negotiation(u32, Address),      // (escrow_id, freelancer) -> Negotiation
+    ReviewPeriod(u32),              // escrow_id -> seconds a submission waits for review before auto-release
+    ArbitrationDeposit(u32),        // escrow_id -> ArbitrationDeposit
+    ApplicationCount(u32),          // escrow_id -> u32 (applications occupy slots 0..count)
+    ApplicantIndex(u32, Address),   // (escrow_id, freelancer) -> application slot
+    SkillTags(u32),                 // escrow_id -> Vec<Symbol>
+    ApplicationRequirements(u32),   // escrow_id -> ApplicationRequirements
+    AppointedArbiter(u32, u32),     // (escrow_id, milestone_index) -> Address drawn from the arbiter pool
+    FundedAmount(u32),              // escrow_id -> i128 deposited so far while Funding
+    Contributors(u32),              // escrow_id -> Vec<Address> funders, in order of first contribution
+    Contribution(u32, Address),     // (escrow_id, contributor) -> i128 contributed
+    ApprovalVotes(u32, u32),        // (escrow_id, milestone_index) -> Vec<Address> contributors voting to approve
+    TeamSplits(u32),                // escrow_id -> Vec<(Address, u32)> beneficiaries and basis-point shares
+    Retainer(u32),                  // escrow_id -> Retainer
+    StreamingDuration(u32),         // escrow_id -> seconds each approved milestone payout vests over
+    VestingStream(u32, u32),        // (escrow_id, milestone_index) -> VestingStream
+    TimeAndMaterials(u32),          // escrow_id -> TimeAndMaterials
+    Referrer(u32),                  // escrow_id -> Address credited with a share of the platform fee
+    ApplicationDeadline(u32),       // escrow_id -> u64 unix timestamp an open job stops taking applications
+    Invitation(u32, Address),       // (escrow_id, freelancer) -> Invitation
+    ApplicationStake(u32),          // escrow_id -> ApplicationStake required from applicants
+    LockedStake(u32, Address),      // (escrow_id, freelancer) -> i128 application stake held
+    StakeStartBy(u32),              // escrow_id -> unix timestamp the hired freelancer must start work by to keep their stake
+    Activity(u32, u32),             // (escrow_id, entry_index) -> ActivityEntry, append-only; persistent
+    ActivityCount(u32),             // escrow_id -> u32 (activity entries occupy slots 0..count); persistent
+    EmergencyRefunded(u32),         // escrow_id -> bool, set once emergency_refund_after_deadline has paid out
+}
+
+// Listing indexes, each split into persistent pages of escrow ids so no single entry grows unbounded
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum ListingIndex {
+    Status(EscrowStatus),
+    OpenJobs,              // Open jobs still taking applications
+    Category(JobCategory),
+}
+
+// Contract-wide setting keys, kept apart from DataKey for the same reason
+#[derive(Clone)]
+#[contracttype]
+pub enum ConfigKey {
+    FinalityWindow, // Seconds before a closed escrow can be finalized
+    YieldAdapter,   // -> Address of the lending pool adapter idle funds may be deposited in
+    TokenInfo(Address), // token -> TokenInfo snapshotted at whitelisting
+    ValueThresholds(Address), // token -> ValueThresholds
+    NativeToken,    // -> Address of the native XLM Stellar Asset Contract
+    StatusIndex(EscrowStatus), // Version 6 and earlier: status -> Vec<u32> escrow_ids, now ListingIndex::Status pages
+    OpenJobIndex,   // Version 6 and earlier: -> Vec<u32> escrow_ids, now ListingIndex::OpenJobs pages
+    CategoryIndex(JobCategory), // Version 6 and earlier: category -> Vec<u32> escrow_ids, now ListingIndex::Category pages
+    IndexPage(ListingIndex, u32), // (index, escrow_id / INDEX_PAGE_SIZE) -> Vec<u32> escrow_ids, ascending; persistent
+    TipFeeBP,       // -> u32 basis points taken from bonuses, 0 if unset
+    ArbiterPool,    // -> Vec<Address> authorized arbiters, in authorization order
+    NextPoolArbiter, // -> u32 round-robin cursor into the arbiter pool
+    Version,        // -> u32 storage layout version, 1 if unset
+    MigrationCursor, // -> u32 next escrow_id to migrate while a migration is in progress
+    TokenFeeBP(Address), // token -> u32 platform fee overriding PlatformFeeBP
+    FeeTiers(Address), // token -> Vec<FeeTier> ascending by min_amount
+    ReferralShareBP, // -> u32 basis points of the platform fee paid to referrers, 0 if unset
+    WhitelistedTokens, // -> Vec<Address> whitelisted tokens, in whitelisting order
+    BlacklistedToken(Address), // token -> bool, barred from new escrows even if whitelisted before
+    ContractPaused, // -> bool, blocks everything but refunds while true
+    EvidenceWindow, // -> u64 seconds after a dispute is raised that evidence can be submitted
+    SlashPoints(SlashReason), // reason -> u32 reputation points deducted for it
+    RatingEditWindow, // -> u64 seconds after rating that the rater can still edit it
+    BadgeCriteria(Badge), // badge -> BadgeCriteria overriding the default thresholds of a tier
+    Leaderboard,    // -> Vec<LeaderboardEntry> highest reputation first
+    BondThreshold(Address), // token -> i128 smallest escrow value that may require a performance bond
+    CollectedFees(Address), // token -> i128 platform fees booked over the contract's lifetime, withdrawals included
+}
+
+// Per-account record keys
+#[derive(Clone)]
+#[contracttype]
+pub enum AccountKey {
+    NettingOptIn(Address, Address),            // (party, counterparty) -> bool
+    NettingBalance(Address, Address, Address), // (party, counterparty, token) -> i128 owed to party
+    FeeExempt(Address),                        // depositor -> bool, pays no platform fee when true
+    ReferralEarnings(Address, Address),        // (referrer, token) -> i128 unclaimed referral fees
+    Role(Address, Role),                       // (address, role) -> bool
+    DepositorEscrows(Address),                 // depositor -> Vec<u32> escrow_ids, ascending
+    BeneficiaryEscrows(Address),               // beneficiary -> Vec<u32> escrow_ids, ascending
+    RecentReputation(Address),                 // user -> RecentReputation earned since decay tracking began
+    ReputationSlashes(Address),                // user -> Vec<ReputationSlash>, most recent last
+    Credential(Address, Achievement),          // (holder, achievement) -> AchievementRecord, non-transferable
+    TotalEarned(Address, Address),             // (freelancer, token) -> i128 credited over their lifetime
+    DisputesArbitrated(Address),               // arbiter -> u32 disputes settled by their ruling
+    ClientSpend(Address, Address),             // (depositor, token) -> i128 released to freelancers over their lifetime
 }
 
@@ -0,0 +1,181 @@
+use crate::compliance;
+use crate::escrow_core;
+use crate::idle_yield;
+use crate::profiles;
+use crate::refund_system;
+use crate::work_lifecycle;
+use crate::storage_types::{
+    DeCentPayError, EscrowData, EscrowKey, EscrowStatus, MilestoneStatus, VestingStream, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error};
+
+/// Stream every approved milestone payout to the beneficiary linearly over `duration` seconds
+/// instead of paying it at once. A duration of 0 turns streaming off.
+pub fn set_streaming(env: &Env, escrow_id: u32, duration: u32, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::Pending || escrow.work_started {
+        return Err(Error::from_contract_error(DeCentPayError::WorkAlreadyStarted as u32));
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    if duration == 0 {
+        env.storage().instance().remove(&EscrowKey::StreamingDuration(escrow_id));
+    } else {
+        env.storage()
+            .instance()
+            .set(&EscrowKey::StreamingDuration(escrow_id), &u64::from(duration));
+    }
+    Ok(())
+}
+
+/// Get the number of seconds approved payouts vest over, if the escrow streams them
+pub fn get_streaming_duration(env: &Env, escrow_id: u32) -> Option<u64> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::StreamingDuration(escrow_id))
+}
+
+/// Get the vesting stream of an approved milestone
+pub fn get_vesting_stream(env: &Env, escrow_id: u32, milestone_index: u32) -> Option<VestingStream> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::VestingStream(escrow_id, milestone_index))
+}
+
+/// Start vesting `amount` for a milestone if the escrow streams payouts, returning whether it does.
+/// A milestone approved in parts vests through one stream, started early enough that what
+/// already vested stays vested.
+pub fn open_stream(env: &Env, escrow_id: u32, milestone_index: u32, amount: i128) -> bool {
+    let Some(duration) = get_streaming_duration(env, escrow_id) else {
+        return false;
+    };
+
+    let now = env.ledger().timestamp();
+    let stream = match get_vesting_stream(env, escrow_id, milestone_index) {
+        Some(existing) => {
+            let total = existing.amount + amount;
+            let vested = vested_amount(env, &existing);
+            let elapsed = u64::try_from((vested * i128::from(duration) + total - 1) / total).unwrap_or(0);
+            VestingStream {
+                amount: total,
+                claimed: existing.claimed,
+                start: now.saturating_sub(elapsed),
+                duration,
+            }
+        }
+        None => VestingStream {
+            amount,
+            claimed: 0,
+            start: now,
+            duration,
+        },
+    };
+    env.storage()
+        .instance()
+        .set(&EscrowKey::VestingStream(escrow_id, milestone_index), &stream);
+    true
+}
+
+/// Amount of a stream vested so far
+pub fn vested_amount(env: &Env, stream: &VestingStream) -> i128 {
+    let elapsed = env.ledger().timestamp().saturating_sub(stream.start).min(stream.duration);
+    stream.amount * i128::from(elapsed) / i128::from(stream.duration)
+}
+
+/// Withdraw the vested, unclaimed part of a milestone's payout
+pub fn claim_vested(env: &Env, escrow_id: u32, milestone_index: u32, beneficiary: Address) -> Result<i128, Error> {
+    beneficiary.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let (mut stream, claimable) = check_claimable(env, escrow_id, milestone_index, &escrow, &beneficiary)?;
+
+    let payout_addr = profiles::payout_address(env, &beneficiary);
+    compliance::require_not_flagged(env, &beneficiary)?;
+    compliance::require_not_flagged(env, &payout_addr)?;
+    idle_yield::unwind(env, escrow_id, &escrow)?;
+
+    stream.claimed += claimable;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::VestingStream(escrow_id, milestone_index), &stream);
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -claimable)?;
+    work_lifecycle::pay_beneficiary(env, escrow_id, &escrow, &beneficiary, claimable)?;
+    Ok(claimable)
+}
+
+/// Check the beneficiary can claim from a milestone's stream now, returning the stream and the
+/// amount claimable
+pub fn check_claimable(
+    env: &Env,
+    escrow_id: u32,
+    milestone_index: u32,
+    escrow: &EscrowData,
+    beneficiary: &Address,
+) -> Result<(VestingStream, i128), Error> {
+    if escrow.beneficiary.as_ref() != Some(beneficiary) {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyBeneficiary as u32));
+    }
+
+    let stream = get_vesting_stream(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NoVestingStream as u32))?;
+
+    // A disputed payout stops vesting out until the dispute is resolved
+    let milestone = work_lifecycle::get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    if milestone.status == MilestoneStatus::Disputed {
+        return Err(Error::from_contract_error(DeCentPayError::StreamDisputed as u32));
+    }
+
+    let claimable = vested_amount(env, &stream) - stream.claimed;
+    if claimable <= 0 {
+        return Err(Error::from_contract_error(DeCentPayError::NothingVested as u32));
+    }
+
+    // Claims wait until they reach the beneficiary's minimum, except for the stream's last remainder
+    if claimable < profiles::min_payout(env, beneficiary) && claimable < stream.amount - stream.claimed {
+        return Err(Error::from_contract_error(DeCentPayError::BelowMinPayout as u32));
+    }
+    Ok((stream, claimable))
+}
+
+/// Cut the stream of a disputed approved milestone down to what the beneficiary was awarded,
+/// refunding the unclaimed excess to the depositor
+pub fn settle_disputed(env: &Env, escrow_id: u32, milestone_index: u32, escrow: &mut EscrowData, beneficiary_amount: i128) -> Result<(), Error> {
+    let Some(mut stream) = get_vesting_stream(env, escrow_id, milestone_index) else {
+        return Ok(());
+    };
+
+    let awarded = beneficiary_amount.clamp(stream.claimed, stream.amount);
+    let refund = stream.amount - awarded;
+    if refund == 0 {
+        return Ok(());
+    }
+
+    stream.amount = awarded;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::VestingStream(escrow_id, milestone_index), &stream);
+    escrow.total_amount -= refund;
+    escrow.paid_amount -= refund;
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -refund)?;
+    refund_system::refund_to_depositor(env, escrow_id, escrow, refund)?;
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use crate::compliance;
+use crate::escrow_core;
+use crate::profiles;
+use crate::storage_types::{
+    DeCentPayError, EscrowData, EscrowKey, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error, Vec};
+
+const MAX_TEAM_SIZE: u32 = 10;
+
+/// Split a new escrow's payouts across a team, each member taking a basis-point share. The lead
+/// beneficiary must be on the team, and agrees to the split by starting work.
+pub fn set_team_splits(env: &Env, escrow_id: u32, escrow: &EscrowData, splits: Vec<(Address, u32)>) -> Result<(), Error> {
+    let lead = escrow
+        .beneficiary
+        .clone()
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidEscrowStatus as u32))?;
+
+    if splits.is_empty() || splits.len() > MAX_TEAM_SIZE {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+    }
+
+    let mut members: Vec<Address> = Vec::new(env);
+    let mut total_bp = 0u32;
+    for (member, share_bp) in splits.iter() {
+        if share_bp == 0 || members.contains(&member) || member == escrow.depositor {
+            return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+        }
+        compliance::require_not_flagged(env, &member)?;
+        total_bp += share_bp;
+        members.push_back(member);
+    }
+    if total_bp != 10000 || !members.contains(&lead) {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::TeamSplits(escrow_id), &splits);
+    Ok(())
+}
+
+/// Get the team an escrow's payouts are split across, if any
+pub fn get_team_splits(env: &Env, escrow_id: u32) -> Option<Vec<(Address, u32)>> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::TeamSplits(escrow_id))
+}
+
+/// Get everyone credited for an escrow's work: the team, or just the beneficiary
+pub fn members(env: &Env, escrow_id: u32, beneficiary: &Address) -> Vec<Address> {
+    let mut members = Vec::new(env);
+    match get_team_splits(env, escrow_id) {
+        Some(splits) => {
+            for (member, _) in splits.iter() {
+                members.push_back(member);
+            }
+        }
+        None => members.push_back(beneficiary.clone()),
+    }
+    members
+}
+
+/// Pay `amount` out across the team by share, the last member taking the rounding remainder
+pub fn pay_team(env: &Env, escrow: &EscrowData, splits: &Vec<(Address, u32)>, amount: i128) -> Result<(), Error> {
+    let last = splits.len() - 1;
+    let mut remaining = amount;
+    for (position, (member, share_bp)) in splits.iter().enumerate() {
+        let share = if u32::try_from(position).unwrap_or(u32::MAX) == last {
+            remaining
+        } else {
+            amount * i128::from(share_bp) / 10000
+        };
+        remaining -= share;
+        let payout_addr = profiles::payout_address(env, &member);
+        compliance::require_not_flagged(env, &member)?;
+        compliance::require_not_flagged(env, &payout_addr)?;
+        if share > 0 {
+            escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &payout_addr, share);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,2762 @@
+#![cfg(test)]
+
+use super::{
+    ActivityAction, ArbitrationFee, ChangeOrderStatus, ConfigKey, DataKey, DeCentPay, DeCentPayClient, DeCentPayError,
+    DisputeRuling, EscrowAction, EscrowCreateOptions, EscrowCreateParams, EscrowKey, EscrowOptions, EscrowStatus, FeeTier, JobCategory,
+    KycConfig, MilestoneAdjustment, MilestoneArbiters, PayoutPreferences, PostingGate, Role, SlashReason, ValueThresholds,
+    YieldRecipient,
+};
+use soroban_sdk::testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke};
+use soroban_sdk::{contract, contractimpl, token, vec, Address, Env, Error, IntoVal, Map, String, Symbol, Val, Vec};
+
+const EMERGENCY_REFUND_DELAY: u64 = 2_592_000;
+
+fn setup(env: &Env) -> (DeCentPayClient<'_>, Address, Address) {
+    env.mock_all_auths();
+
+    let contract_id = env.register(DeCentPay, ());
+    let client = DeCentPayClient::new(env, &contract_id);
+    let owner = Address::generate(env);
+    let native = env.register_stellar_asset_contract_v2(owner.clone()).address();
+
+    client.initialize(&owner, &owner, &100);
+    client.set_native_token(&native);
+    (client, owner, native)
+}
+
+fn mint(env: &Env, token: &Address, to: &Address, amount: i128) {
+    token::StellarAssetClient::new(env, token).mint(to, &amount);
+}
+
+fn contract_error(error: DeCentPayError) -> Error {
+    Error::from_contract_error(error as u32)
+}
+
+/// Assert the last invocation needed no signature but `signer`'s
+fn assert_signed_only_by(env: &Env, signer: &Address) {
+    let auths = env.auths();
+    assert!(!auths.is_empty());
+    for (address, _) in auths {
+        assert_eq!(address, *signer);
+    }
+}
+
+/// The action of the last entry in an escrow's activity log
+fn last_activity(client: &DeCentPayClient, escrow_id: u32) -> Option<ActivityAction> {
+    let count = client.get_escrow_activity_count(&escrow_id);
+    client.get_escrow_activity(&escrow_id, &(count - 1), &1).get(0).map(|entry| entry.action)
+}
+
+/// Create a native XLM escrow with a single milestone worth `amount`, lasting an hour, that
+/// stays in Funding until it is paid in
+fn create_unfunded_escrow(env: &Env, client: &DeCentPayClient, depositor: &Address, beneficiary: &Address, amount: i128) -> u32 {
+    client.create_escrow_unfunded(
+        depositor,
+        &Some(beneficiary.clone()),
+        &vec![env],
+        &0,
+        &vec![env, (amount, String::from_str(env, "Build"))],
+        &None,
+        &amount,
+        &3600,
+        &String::from_str(env, "Crowdfunded job"),
+        &String::from_str(env, "Paid in XLM"),
+    )
+}
+
+/// Post a native XLM open job with a single milestone worth `amount`, lasting an hour
+fn create_open_job(env: &Env, client: &DeCentPayClient, depositor: &Address, amount: i128) -> u32 {
+    client.create_escrow(
+        depositor,
+        &None,
+        &vec![env],
+        &0,
+        &vec![env, (amount, String::from_str(env, "Build"))],
+        &None,
+        &amount,
+        &3600,
+        &String::from_str(env, "Open job"),
+        &String::from_str(env, "Paid in XLM"),
+    )
+}
+
+/// Create a native XLM escrow with a single milestone worth `amount`, lasting an hour
+fn create_escrow(env: &Env, client: &DeCentPayClient, depositor: &Address, beneficiary: &Address, amount: i128) -> u32 {
+    client.create_escrow(
+        depositor,
+        &Some(beneficiary.clone()),
+        &vec![env],
+        &0,
+        &vec![env, (amount, String::from_str(env, "Build"))],
+        &None,
+        &amount,
+        &3600,
+        &String::from_str(env, "Native job"),
+        &String::from_str(env, "Paid in XLM"),
+    )
+}
+
+/// Describe a native XLM escrow with a single milestone worth `amount`, lasting an hour (an open
+/// job if there is no beneficiary)
+fn escrow_params(env: &Env, beneficiary: Option<Address>, amount: i128) -> EscrowCreateParams {
+    EscrowCreateParams {
+        beneficiary,
+        arbiters: vec![env],
+        required_confirmations: 0,
+        milestones: vec![env, (amount, String::from_str(env, "Build"))],
+        token: None,
+        total_amount: amount,
+        duration: 3600,
+        project_title: String::from_str(env, "Job with terms"),
+        project_description: String::from_str(env, "Paid in XLM"),
+    }
+}
+
+#[test]
+fn emergency_refund_returns_native_xlm() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    assert_eq!(xlm.balance(&depositor), 0);
+    assert_eq!(xlm.balance(&client.address), 10_100);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3600 + EMERGENCY_REFUND_DELAY + 1);
+    client.emergency_refund_after_deadline(&escrow_id, &depositor);
+
+    assert_eq!(xlm.balance(&depositor), 10_100);
+    assert_eq!(xlm.balance(&client.address), 0);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.status), Some(EscrowStatus::Expired));
+}
+
+#[test]
+fn emergency_refund_cannot_be_repeated() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let other_depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+    mint(&env, &native, &other_depositor, 10_100);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    create_escrow(&env, &client, &other_depositor, &beneficiary, 10_000);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3600 + EMERGENCY_REFUND_DELAY + 1);
+    client.emergency_refund_after_deadline(&escrow_id, &depositor);
+    assert_eq!(
+        client.try_emergency_refund_after_deadline(&escrow_id, &depositor),
+        Err(Ok(contract_error(DeCentPayError::CannotRefund)))
+    );
+
+    // The other escrow's deposit and fee stay in the contract
+    assert_eq!(xlm.balance(&depositor), 10_100);
+    assert_eq!(xlm.balance(&client.address), 10_100);
+    assert_eq!(client.get_escrowed_amount(&None), 10_100);
+}
+
+#[test]
+fn change_order_increase_is_funded_by_the_depositor_at_proposal() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 15_150);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    let increase = vec![&env, MilestoneAdjustment { milestone_index: 0, amount_delta: 5_000 }];
+    let order_id = client.propose_change_order(&escrow_id, &increase, &vec![&env], &vec![&env], &vec![&env], &depositor);
+    assert_eq!(xlm.balance(&depositor), 0);
+    assert_eq!(client.get_change_order_funding(&escrow_id, &order_id), 5_050);
+
+    client.accept_change_order(&escrow_id, &order_id, &beneficiary);
+    assert_signed_only_by(&env, &beneficiary);
+
+    let escrow = client.get_escrow(&escrow_id).unwrap();
+    assert_eq!(escrow.total_amount, 15_000);
+    assert_eq!(escrow.platform_fee, 150);
+    assert_eq!(client.get_milestone(&escrow_id, &0).map(|milestone| milestone.amount), Some(15_000));
+    assert_eq!(client.get_change_order_funding(&escrow_id, &order_id), 0);
+    assert_eq!(client.get_escrowed_amount(&None), 15_150);
+    assert_eq!(xlm.balance(&client.address), 15_150);
+}
+
+#[test]
+fn change_order_increase_is_funded_when_the_depositor_accepts() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 12_120);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    let increase = vec![&env, MilestoneAdjustment { milestone_index: 0, amount_delta: 2_000 }];
+    let order_id = client.propose_change_order(&escrow_id, &increase, &vec![&env], &vec![&env], &vec![&env], &beneficiary);
+    assert_eq!(xlm.balance(&depositor), 2_020);
+
+    client.accept_change_order(&escrow_id, &order_id, &depositor);
+    assert_eq!(xlm.balance(&depositor), 0);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.total_amount), Some(12_000));
+    assert_eq!(client.get_escrowed_amount(&None), 12_120);
+}
+
+#[test]
+fn change_order_decrease_refunds_principal_and_fee() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    let decrease = vec![&env, MilestoneAdjustment { milestone_index: 0, amount_delta: -4_000 }];
+    let order_id = client.propose_change_order(&escrow_id, &decrease, &vec![&env], &vec![&env], &vec![&env], &beneficiary);
+    client.accept_change_order(&escrow_id, &order_id, &depositor);
+
+    assert_eq!(xlm.balance(&depositor), 4_040);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.platform_fee), Some(60));
+    assert_eq!(client.get_escrowed_amount(&None), 6_060);
+}
+
+#[test]
+fn cancelled_change_order_returns_its_funding() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 15_150);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    let increase = vec![&env, MilestoneAdjustment { milestone_index: 0, amount_delta: 5_000 }];
+    let order_id = client.propose_change_order(&escrow_id, &increase, &vec![&env], &vec![&env], &vec![&env], &depositor);
+    client.cancel_change_order(&escrow_id, &order_id, &beneficiary);
+
+    assert_eq!(xlm.balance(&depositor), 5_050);
+    assert_eq!(client.get_escrowed_amount(&None), 10_100);
+    assert_eq!(client.get_change_order(&escrow_id, &order_id).map(|order| order.status), Some(ChangeOrderStatus::Cancelled));
+}
+
+#[test]
+fn change_order_rejects_repeated_milestone_deltas() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    let deltas = vec![
+        &env,
+        MilestoneAdjustment { milestone_index: 0, amount_delta: -6_000 },
+        MilestoneAdjustment { milestone_index: 0, amount_delta: -6_000 },
+    ];
+    assert_eq!(
+        client.try_propose_change_order(&escrow_id, &deltas, &vec![&env], &vec![&env], &vec![&env], &beneficiary),
+        Err(Ok(contract_error(DeCentPayError::InvalidParameter)))
+    );
+}
+
+#[test]
+fn amendment_is_funded_at_proposal_and_accepted_by_the_beneficiary_alone() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 13_130);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    let milestones = vec![&env, (3_000i128, String::from_str(&env, "Extra"))];
+    let amendment_id = client.propose_amendment(&escrow_id, &milestones, &3_000, &depositor);
+    assert_eq!(xlm.balance(&depositor), 0);
+
+    client.accept_amendment(&escrow_id, &amendment_id, &beneficiary);
+    assert_signed_only_by(&env, &beneficiary);
+
+    let escrow = client.get_escrow(&escrow_id).unwrap();
+    assert_eq!(escrow.milestone_count, 2);
+    assert_eq!(escrow.total_amount, 13_000);
+    assert_eq!(escrow.platform_fee, 130);
+    assert_eq!(client.get_escrowed_amount(&None), 13_130);
+}
+
+#[test]
+fn outside_contribution_completes_funding_without_the_depositor() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let backer = Address::generate(&env);
+    mint(&env, &native, &depositor, 100);
+    mint(&env, &native, &backer, 10_000);
+
+    // The platform fee is paid in by the depositor when they create the escrow
+    let escrow_id = create_unfunded_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    assert_eq!(xlm.balance(&depositor), 0);
+
+    client.contribute(&escrow_id, &10_000, &backer);
+    assert_signed_only_by(&env, &backer);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.status), Some(EscrowStatus::Pending));
+    assert_eq!(client.get_escrowed_amount(&None), 10_100);
+}
+
+#[test]
+fn abandoned_funding_returns_contributions_and_fee() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let backer = Address::generate(&env);
+    mint(&env, &native, &depositor, 2_100);
+    mint(&env, &native, &backer, 3_000);
+
+    let escrow_id = create_unfunded_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.contribute(&escrow_id, &2_000, &depositor);
+    client.contribute(&escrow_id, &3_000, &backer);
+    client.refund_escrow(&escrow_id, &depositor);
+
+    assert_eq!(xlm.balance(&depositor), 2_100);
+    assert_eq!(xlm.balance(&backer), 3_000);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.status), Some(EscrowStatus::Refunded));
+}
+
+#[test]
+fn escrow_abandoned_before_any_funding_returns_its_fee() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 100);
+
+    let escrow_id = create_unfunded_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.refund_escrow(&escrow_id, &depositor);
+
+    assert_eq!(xlm.balance(&depositor), 100);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+}
+
+#[test]
+fn escrow_funded_by_a_single_backer_refunds_the_backer() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let backer = Address::generate(&env);
+    mint(&env, &native, &depositor, 100);
+    mint(&env, &native, &backer, 10_000);
+
+    let escrow_id = create_unfunded_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.contribute(&escrow_id, &10_000, &backer);
+    client.refund_escrow(&escrow_id, &depositor);
+
+    assert_eq!(xlm.balance(&backer), 10_000);
+    assert_eq!(xlm.balance(&depositor), 100);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+}
+
+#[test]
+fn installments_fund_an_escrow() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_unfunded_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.fund_escrow(&escrow_id, &4_000, &depositor);
+    assert_eq!(client.get_funded_amount(&escrow_id), 4_000);
+    assert_eq!(
+        client.try_fund_escrow(&escrow_id, &7_000, &depositor),
+        Err(Ok(contract_error(DeCentPayError::InvalidAmount)))
+    );
+
+    client.fund_escrow(&escrow_id, &6_000, &depositor);
+    assert_eq!(xlm.balance(&depositor), 0);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.status), Some(EscrowStatus::Pending));
+    assert_eq!(client.get_escrowed_amount(&None), 10_100);
+}
+
+#[test]
+fn accepting_a_raised_counter_offer_needs_only_the_freelancer() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    mint(&env, &native, &depositor, 12_120);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    client.apply_to_job(&escrow_id, &String::from_str(&env, "Hire me"), &7, &freelancer);
+    client.propose_terms(&escrow_id, &freelancer, &12_000, &5, &depositor);
+    assert_eq!(xlm.balance(&depositor), 0);
+
+    client.accept_terms(&escrow_id, &freelancer);
+    assert_signed_only_by(&env, &freelancer);
+
+    let escrow = client.get_escrow(&escrow_id).unwrap();
+    assert_eq!(escrow.beneficiary, Some(freelancer));
+    assert_eq!(escrow.total_amount, 12_000);
+    assert_eq!(escrow.platform_fee, 120);
+    assert_eq!(client.get_escrowed_amount(&None), 12_120);
+}
+
+#[test]
+fn accepting_a_lowered_counter_offer_refunds_the_difference_and_fee() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    client.apply_to_job(&escrow_id, &String::from_str(&env, "Hire me"), &7, &freelancer);
+    client.propose_terms(&escrow_id, &freelancer, &8_000, &5, &depositor);
+    client.accept_terms(&escrow_id, &freelancer);
+
+    assert_eq!(xlm.balance(&depositor), 2_020);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.platform_fee), Some(80));
+    assert_eq!(client.get_escrowed_amount(&None), 8_080);
+}
+
+#[test]
+fn partial_approvals_cannot_bypass_the_cosign_threshold() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+    client.authorize_arbiter(&arbiter, &owner);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.set_cosign_threshold(&escrow_id, &5_000, &depositor);
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Done"), &None, &beneficiary);
+    assert_eq!(
+        client.try_approve_milestone_partial(&escrow_id, &0, &4_000, &depositor),
+        Err(Ok(contract_error(DeCentPayError::CosignRequired)))
+    );
+
+    // A full approval waits for the co-signature
+    client.approve_milestone(&escrow_id, &0, &depositor);
+    assert_eq!(xlm.balance(&beneficiary), 0);
+    client.cosign_milestone(&escrow_id, &0, &arbiter);
+    assert_eq!(xlm.balance(&beneficiary), 10_000);
+}
+
+#[test]
+fn partial_approval_below_the_threshold_pays_out() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.set_cosign_threshold(&escrow_id, &20_000, &depositor);
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Done"), &None, &beneficiary);
+    client.approve_milestone_partial(&escrow_id, &0, &4_000, &depositor);
+
+    assert_eq!(xlm.balance(&beneficiary), 4_000);
+    assert_eq!(client.get_milestone(&escrow_id, &0).map(|milestone| milestone.released), Some(4_000));
+}
+
+#[test]
+fn withdrawing_an_application_closes_its_counter_offer() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    mint(&env, &native, &depositor, 12_120);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    client.apply_to_job(&escrow_id, &String::from_str(&env, "Hire me"), &7, &freelancer);
+    client.propose_terms(&escrow_id, &freelancer, &12_000, &5, &depositor);
+    client.withdraw_application(&escrow_id, &freelancer);
+
+    assert_eq!(xlm.balance(&depositor), 2_020);
+    assert_eq!(client.get_escrowed_amount(&None), 10_100);
+    assert_eq!(
+        client.try_accept_terms(&escrow_id, &freelancer),
+        Err(Ok(contract_error(DeCentPayError::NoNegotiation)))
+    );
+    assert_eq!(client.get_escrow(&escrow_id).and_then(|escrow| escrow.beneficiary), None);
+}
+
+#[test]
+fn rejecting_an_application_closes_its_counter_offer() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    mint(&env, &native, &depositor, 12_120);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    client.apply_to_job(&escrow_id, &String::from_str(&env, "Hire me"), &7, &freelancer);
+    client.propose_terms(&escrow_id, &freelancer, &12_000, &5, &depositor);
+    client.reject_application(&escrow_id, &freelancer, &String::from_str(&env, "Filled"), &depositor);
+
+    assert_eq!(xlm.balance(&depositor), 2_020);
+    assert!(client.try_accept_terms(&escrow_id, &freelancer).is_err());
+}
+
+#[test]
+fn netting_settlement_respects_pause_and_payout_address() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let payout = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+    client.set_netting(&beneficiary, &true, &depositor);
+    client.set_netting(&depositor, &true, &beneficiary);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Done"), &None, &beneficiary);
+    client.approve_milestone(&escrow_id, &0, &depositor);
+    assert_eq!(client.get_netting_balance(&beneficiary, &depositor, &None), 10_000);
+    assert_eq!(xlm.balance(&beneficiary), 0);
+
+    let preferences = PayoutPreferences { min_payout: 0, payout_address: Some(payout.clone()) };
+    client.set_payout_preferences(&beneficiary, &preferences);
+    client.pause_contract(&owner);
+    assert_eq!(
+        client.try_settle_netting(&beneficiary, &depositor, &None),
+        Err(Ok(contract_error(DeCentPayError::ContractPaused)))
+    );
+
+    client.unpause_contract(&owner);
+    client.settle_netting(&beneficiary, &depositor, &None);
+    assert_eq!(xlm.balance(&payout), 10_000);
+    assert_eq!(client.get_netting_balance(&beneficiary, &depositor, &None), 0);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+}
+
+#[test]
+fn final_escrow_cannot_be_frozen() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+    client.set_finality_window(&3600, &owner);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.refund_escrow(&escrow_id, &depositor);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3599);
+    assert_eq!(
+        client.try_finalize_escrow(&escrow_id),
+        Err(Ok(contract_error(DeCentPayError::ChallengeWindowOpen)))
+    );
+    env.ledger().with_mut(|ledger| ledger.timestamp += 1);
+    client.finalize_escrow(&escrow_id);
+
+    assert_eq!(
+        client.try_freeze_escrow(&escrow_id, &owner),
+        Err(Ok(contract_error(DeCentPayError::EscrowFinal)))
+    );
+}
+
+#[test]
+fn performance_bond_requires_a_high_value_escrow() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    assert_eq!(
+        client.try_require_performance_bond(&escrow_id, &1_000, &depositor),
+        Err(Ok(contract_error(DeCentPayError::BelowBondThreshold)))
+    );
+
+    client.set_bond_threshold(&None, &10_000, &owner);
+    client.require_performance_bond(&escrow_id, &1_000, &depositor);
+    assert_eq!(client.get_performance_bond(&escrow_id).map(|bond| bond.bond_bp), Some(1_000));
+}
+
+/// Create an hour-long escrow whose freelancer locks a 10% bond and then abandons the work
+fn create_abandoned_bonded_escrow(env: &Env, client: &DeCentPayClient, native: &Address, depositor: &Address, beneficiary: &Address) -> u32 {
+    mint(env, native, depositor, 10_100);
+    mint(env, native, beneficiary, 1_000);
+    client.set_bond_threshold(&None, &10_000, &client.get_owner());
+
+    let escrow_id = create_escrow(env, client, depositor, beneficiary, 10_000);
+    client.require_performance_bond(&escrow_id, &1_000, depositor);
+    client.start_work(&escrow_id, beneficiary);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3600 + EMERGENCY_REFUND_DELAY + 1);
+    client.emergency_refund_after_deadline(&escrow_id, depositor);
+    escrow_id
+}
+
+#[test]
+fn unsettled_bond_without_arbiters_is_forfeited_after_the_ruling_window() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+
+    let escrow_id = create_abandoned_bonded_escrow(&env, &client, &native, &depositor, &beneficiary);
+    assert_eq!(client.get_escrowed_amount(&None), 1_000);
+    assert_eq!(
+        client.try_forfeit_unsettled_bond(&escrow_id),
+        Err(Ok(contract_error(DeCentPayError::BondNotSettleable)))
+    );
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 2_592_000);
+    client.forfeit_unsettled_bond(&escrow_id);
+
+    assert_eq!(xlm.balance(&depositor), 11_000);
+    assert_eq!(xlm.balance(&beneficiary), 0);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+    assert_eq!(
+        client.try_forfeit_unsettled_bond(&escrow_id),
+        Err(Ok(contract_error(DeCentPayError::NoBond)))
+    );
+}
+
+#[test]
+fn platform_arbiter_settles_a_bond_on_an_escrow_without_arbiters() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    client.authorize_arbiter(&arbiter, &owner);
+
+    let escrow_id = create_abandoned_bonded_escrow(&env, &client, &native, &depositor, &beneficiary);
+    assert_eq!(
+        client.try_settle_performance_bond(&escrow_id, &outsider, &false),
+        Err(Ok(contract_error(DeCentPayError::OnlyArbiter)))
+    );
+
+    client.settle_performance_bond(&escrow_id, &arbiter, &false);
+    assert_eq!(xlm.balance(&beneficiary), 1_000);
+    assert_eq!(xlm.balance(&depositor), 10_000);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+}
+
+/// Release a team escrow whose single milestone withholds 10% retainage, split 60/40 between
+/// the lead and a partner
+fn release_team_escrow_with_retainage(env: &Env, client: &DeCentPayClient, native: &Address, depositor: &Address, lead: &Address, partner: &Address) -> u32 {
+    mint(env, native, depositor, 10_100);
+    let params = escrow_params(env, Some(lead.clone()), 10_000);
+    let options = EscrowCreateOptions { team_splits: vec![env, (lead.clone(), 6_000), (partner.clone(), 4_000)], ..EscrowCreateOptions::new(env) };
+    let escrow_id = client.create_escrow_with_options(depositor, &params, &options);
+    client.set_retainage(&escrow_id, &1_000, &3600, depositor);
+    client.start_work(&escrow_id, lead);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(env, "Done"), &None, lead);
+    client.approve_milestone(&escrow_id, &0, depositor);
+    escrow_id
+}
+
+#[test]
+fn released_retention_is_split_across_the_team() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let lead = Address::generate(&env);
+    let partner = Address::generate(&env);
+
+    let escrow_id = release_team_escrow_with_retainage(&env, &client, &native, &depositor, &lead, &partner);
+    assert_eq!(xlm.balance(&lead), 5_400);
+    assert_eq!(xlm.balance(&partner), 3_600);
+    assert_eq!(client.get_escrowed_amount(&None), 1_000);
+
+    client.release_retention(&escrow_id, &depositor);
+    assert_eq!(xlm.balance(&lead), 6_000);
+    assert_eq!(xlm.balance(&partner), 4_000);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+    assert_eq!(
+        client.try_release_retention(&escrow_id, &depositor),
+        Err(Ok(contract_error(DeCentPayError::NoRetainage)))
+    );
+}
+
+#[test]
+fn bonus_is_split_across_the_team_after_the_tip_fee() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let lead = Address::generate(&env);
+    let partner = Address::generate(&env);
+    client.set_tip_fee_bp(&500, &owner);
+
+    let escrow_id = release_team_escrow_with_retainage(&env, &client, &native, &depositor, &lead, &partner);
+    mint(&env, &native, &depositor, 1_000);
+    client.send_bonus(&escrow_id, &1_000, &depositor);
+
+    assert_eq!(xlm.balance(&depositor), 0);
+    assert_eq!(xlm.balance(&lead), 5_400 + 570);
+    assert_eq!(xlm.balance(&partner), 3_600 + 380);
+    assert_eq!(client.get_escrowed_amount(&None), 1_000);
+    assert_eq!(last_activity(&client, escrow_id), Some(ActivityAction::BonusSent));
+    assert_eq!(
+        client.try_send_bonus(&escrow_id, &0, &depositor),
+        Err(Ok(contract_error(DeCentPayError::InvalidAmount)))
+    );
+}
+
+#[test]
+fn retainage_is_released_once_the_warranty_time_passes() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let lead = Address::generate(&env);
+    let partner = Address::generate(&env);
+
+    let escrow_id = release_team_escrow_with_retainage(&env, &client, &native, &depositor, &lead, &partner);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3599);
+    assert_eq!(
+        client.try_release_retainage(&escrow_id),
+        Err(Ok(contract_error(DeCentPayError::WarrantyActive)))
+    );
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 1);
+    client.release_retainage(&escrow_id);
+    assert_eq!(xlm.balance(&lead), 6_000);
+    assert_eq!(xlm.balance(&partner), 4_000);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+}
+
+#[test]
+fn streamed_payout_vests_over_its_duration_in_seconds() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.set_streaming(&escrow_id, &1_000, &depositor);
+    assert_eq!(client.get_streaming_duration(&escrow_id), Some(1_000));
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Done"), &None, &beneficiary);
+    client.approve_milestone(&escrow_id, &0, &depositor);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 250);
+    assert_eq!(client.claim_vested(&escrow_id, &0, &beneficiary), 2_500);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 2_000);
+    assert_eq!(client.claim_vested(&escrow_id, &0, &beneficiary), 7_500);
+    assert_eq!(xlm.balance(&beneficiary), 10_000);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+}
+
+#[test]
+fn dashboard_lists_offers_and_unclaimed_vesting() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    mint(&env, &native, &depositor, 32_320);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &freelancer, 10_000);
+    client.set_streaming(&escrow_id, &1_000, &depositor);
+    client.start_work(&escrow_id, &freelancer);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Done"), &None, &freelancer);
+    client.approve_milestone(&escrow_id, &0, &depositor);
+
+    let invited_job = create_open_job(&env, &client, &depositor, 10_000);
+    client.invite_freelancer(&invited_job, &freelancer, &600, &depositor);
+    let offered_job = create_open_job(&env, &client, &depositor, 10_000);
+    client.apply_to_job(&offered_job, &String::from_str(&env, "Hire me"), &7, &freelancer);
+    client.propose_terms(&offered_job, &freelancer, &12_000, &5, &depositor);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 250);
+    let dashboard = client.get_dashboard(&freelancer);
+    assert_eq!(dashboard.invitations, vec![&env, invited_job]);
+    assert_eq!(dashboard.pending_offers, vec![&env, offered_job]);
+    let vested = dashboard.unclaimed_vested.get(0).unwrap();
+    assert_eq!((vested.escrow_id, vested.milestone_index, vested.amount), (escrow_id, 0, 2_500));
+    assert!(dashboard.netting_credits.is_empty());
+    assert!(dashboard.referral_earnings.is_empty());
+}
+
+#[test]
+fn partially_approved_payouts_stream_through_one_stream() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.set_streaming(&escrow_id, &1_000, &depositor);
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Done"), &None, &beneficiary);
+    client.approve_milestone_partial(&escrow_id, &0, &4_000, &depositor);
+    assert_eq!(xlm.balance(&beneficiary), 0);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 500);
+    assert_eq!(client.claim_vested(&escrow_id, &0, &beneficiary), 2_000);
+
+    // The remainder joins the stream without giving back what already vested
+    client.mark_milestone_complete(&escrow_id, &0, &depositor);
+    assert_eq!(client.get_vesting_stream(&escrow_id, &0).map(|stream| stream.amount), Some(10_000));
+    env.ledger().with_mut(|ledger| ledger.timestamp += 400);
+    assert_eq!(client.claim_vested(&escrow_id, &0, &beneficiary), 4_000);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 1_000);
+    assert_eq!(client.claim_vested(&escrow_id, &0, &beneficiary), 4_000);
+    assert_eq!(xlm.balance(&beneficiary), 10_000);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+}
+
+#[test]
+fn migration_converts_ledger_windows_to_seconds() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.set_retainage(&escrow_id, &1_000, &3_600, &depositor);
+    client.set_payout_preferences(&beneficiary, &PayoutPreferences { min_payout: 500, payout_address: None });
+
+    // Rewrite the records as version 5 stored them, counting ledgers
+    env.ledger().with_mut(|ledger| {
+        ledger.sequence_number = 1_000;
+        ledger.timestamp = 1_000_000;
+    });
+    env.as_contract(&client.address, || {
+        let storage = env.storage().instance();
+        storage.set(&ConfigKey::Version, &5u32);
+        storage.set(&ConfigKey::EvidenceWindow, &120u32);
+        storage.set(&ConfigKey::RatingEditWindow, &120u32);
+        storage.set(&EscrowKey::ReviewPeriod(escrow_id), &720u32);
+        let mut retainage: Map<Symbol, Val> = storage.get(&EscrowKey::Retainage(escrow_id)).unwrap();
+        retainage.set(Symbol::new(&env, "warranty_period"), 720u32.into_val(&env));
+        storage.set(&EscrowKey::Retainage(escrow_id), &retainage);
+        let preferences_key = DataKey::PayoutPreferences(beneficiary.clone());
+        let mut preferences: Map<Symbol, Val> = storage.get(&preferences_key).unwrap();
+        preferences.set(Symbol::new(&env, "preferred_token"), Option::<Address>::None.into_val(&env));
+        storage.set(&preferences_key, &preferences);
+        let adverse_key = DataKey::AdverseRecord(beneficiary.clone());
+        let mut adverse: Map<Symbol, Val> = Map::new(&env);
+        for field in ["abandoned", "disputes_lost", "ratings_removed", "withdrawn"] {
+            adverse.set(Symbol::new(&env, field), 1u32.into_val(&env));
+        }
+        storage.set(&adverse_key, &adverse);
+        let mut slash: Map<Symbol, Val> = Map::new(&env);
+        slash.set(Symbol::new(&env, "escrow_id"), escrow_id.into_val(&env));
+        slash.set(Symbol::new(&env, "points"), 10u32.into_val(&env));
+        slash.set(Symbol::new(&env, "reason"), SlashReason::DisputeLost.into_val(&env));
+        slash.set(Symbol::new(&env, "slashed_at"), 900u32.into_val(&env));
+        storage.set(&super::AccountKey::ReputationSlashes(beneficiary.clone()), &vec![&env, slash]);
+    });
+
+    assert!(client.migrate(&5, &7));
+    assert_eq!(client.get_version(), 7);
+    assert_eq!(client.get_evidence_window(), 600);
+    assert_eq!(client.get_rating_edit_window(), 600);
+    assert_eq!(client.get_review_period(&escrow_id), Some(3_600));
+    assert_eq!(client.get_retainage(&escrow_id).map(|retainage| retainage.warranty_period), Some(3_600));
+    assert_eq!(client.get_payout_preferences(&beneficiary).map(|preferences| preferences.min_payout), Some(500));
+    assert_eq!(client.get_adverse_record(&beneficiary).disputes_lost, 1);
+    assert_eq!(client.get_slash_history(&beneficiary).get(0).map(|slash| slash.slashed_at), Some(1_000_000 - 100 * 5));
+    assert_eq!(
+        client.try_migrate(&5, &7),
+        Err(Ok(contract_error(DeCentPayError::InvalidVersion)))
+    );
+}
+
+#[test]
+fn migration_converts_ledger_stamps_to_timestamps() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    let freelancer = Address::generate(&env);
+    client.apply_to_job(&escrow_id, &String::from_str(&env, "Cover letter"), &7, &freelancer);
+
+    // Rewrite the records as version 6 stored them, stamped with ledger sequence numbers
+    env.ledger().with_mut(|ledger| {
+        ledger.sequence_number = 1_000;
+        ledger.timestamp = 1_000_000;
+    });
+    env.as_contract(&client.address, || {
+        let storage = env.storage().instance();
+        storage.set(&ConfigKey::Version, &6u32);
+        let stamp = |key: &DataKey, field: &str| {
+            let mut raw: Map<Symbol, Val> = storage.get(key).unwrap();
+            raw.set(Symbol::new(&env, field), 900u32.into_val(&env));
+            storage.set(key, &raw);
+        };
+        stamp(&DataKey::Escrow(escrow_id), "created_at");
+        stamp(&DataKey::Application(escrow_id, 0), "applied_at");
+        // Version 6 kept the activity log and listing indexes in instance storage
+        let persistent = env.storage().persistent();
+        let activity_key = EscrowKey::Activity(escrow_id, 0);
+        let mut activity: Map<Symbol, Val> = persistent.get(&activity_key).unwrap();
+        activity.remove(Symbol::new(&env, "recorded_at"));
+        activity.set(Symbol::new(&env, "ledger"), 900u32.into_val(&env));
+        storage.set(&activity_key, &activity);
+        persistent.remove(&activity_key);
+        let activity_count: u32 = persistent.get(&EscrowKey::ActivityCount(escrow_id)).unwrap();
+        storage.set(&EscrowKey::ActivityCount(escrow_id), &activity_count);
+        persistent.remove(&EscrowKey::ActivityCount(escrow_id));
+        storage.set(&ConfigKey::OpenJobIndex, &vec![&env, escrow_id]);
+        persistent.remove(&ConfigKey::IndexPage(super::ListingIndex::OpenJobs, 0));
+        // Reputation earned before decay tracking has no recent score to decay from
+        crate::escrow_core::set_reputation(&env, depositor.clone(), 40);
+        storage.remove(&super::AccountKey::RecentReputation(depositor.clone()));
+    });
+    assert_eq!(client.get_effective_reputation(&depositor), 0);
+    // Nothing may touch the old records until the migration finishes
+    assert_eq!(
+        client.try_apply_to_job(&escrow_id, &String::from_str(&env, "Cover letter"), &7, &Address::generate(&env)),
+        Err(Ok(contract_error(DeCentPayError::MigrationPending)))
+    );
+    assert_eq!(
+        client.try_cancel_open_job(&escrow_id, &depositor),
+        Err(Ok(contract_error(DeCentPayError::MigrationPending)))
+    );
+
+    assert!(client.migrate(&6, &7));
+    assert_eq!(client.get_effective_reputation(&depositor), 40);
+    let converted = 1_000_000 - 100 * 5;
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.created_at), Some(converted));
+    assert_eq!(client.get_applications(&escrow_id).get(0).map(|application| application.applied_at), Some(converted));
+    assert_eq!(client.get_escrow_activity(&escrow_id, &0, &1).get(0).map(|entry| entry.recorded_at), Some(converted));
+    assert_eq!(client.list_open_jobs(&0, &10).get(0).map(|listing| listing.escrow_id), Some(escrow_id));
+    env.as_contract(&client.address, || {
+        assert!(!env.storage().instance().has(&EscrowKey::Activity(escrow_id, 0)));
+        assert!(!env.storage().instance().has(&ConfigKey::OpenJobIndex));
+    });
+}
+
+/// Apply to an open job staking `points` of the freelancer's reputation
+fn apply_with_reputation_stake(env: &Env, client: &DeCentPayClient, escrow_id: u32, points: u32) -> Address {
+    let freelancer = Address::generate(env);
+    env.as_contract(&client.address, || crate::escrow_core::set_reputation(env, freelancer.clone(), 50));
+    client.apply_to_job(&escrow_id, &String::from_str(env, "Cover letter"), &7, &freelancer);
+    client.stake_reputation(&escrow_id, &freelancer, &points);
+    assert_eq!(client.get_reputation(&freelancer), 50 - points);
+    freelancer
+}
+
+#[test]
+fn expired_open_job_returns_applicants_stakes() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    let first = apply_with_reputation_stake(&env, &client, escrow_id, 20);
+    let second = apply_with_reputation_stake(&env, &client, escrow_id, 30);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3600);
+    client.expire_open_job(&escrow_id);
+
+    for freelancer in [first, second] {
+        assert_eq!(client.get_reputation(&freelancer), 50);
+        assert_eq!(client.get_reputation_stake(&escrow_id, &freelancer), 0);
+    }
+}
+
+#[test]
+fn emergency_refund_of_an_unfilled_job_returns_applicants_stakes() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    let freelancer = apply_with_reputation_stake(&env, &client, escrow_id, 20);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3600 + EMERGENCY_REFUND_DELAY + 1);
+    client.emergency_refund_after_deadline(&escrow_id, &depositor);
+
+    assert_eq!(client.get_reputation(&freelancer), 50);
+    assert_eq!(client.get_reputation_stake(&escrow_id, &freelancer), 0);
+}
+
+#[test]
+fn emergency_refunded_job_offers_no_further_refunds() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3600);
+    client.expire_open_job(&escrow_id);
+    let actions = client.get_allowed_actions(&escrow_id, &depositor);
+    assert!(actions.contains(EscrowAction::RefundEscrow));
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += EMERGENCY_REFUND_DELAY + 1);
+    let actions = client.get_allowed_actions(&escrow_id, &depositor);
+    assert!(!actions.contains(EscrowAction::EmergencyRefund));
+    client.refund_escrow(&escrow_id, &depositor);
+    let actions = client.get_allowed_actions(&escrow_id, &depositor);
+    assert!(!actions.contains(EscrowAction::RefundEscrow));
+}
+
+#[test]
+fn allowed_actions_follow_the_entrypoint_guards() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    mint(&env, &native, &depositor, 20_200);
+
+    // Nobody to hire yet, and applications close with the application period
+    let options = EscrowCreateOptions { application_period: 600, ..EscrowCreateOptions::new(&env) };
+    let job_id = client.create_escrow_with_options(&depositor, &escrow_params(&env, None, 10_000), &options);
+    let actions = client.get_allowed_actions(&job_id, &depositor);
+    assert!(actions.contains(EscrowAction::CancelOpenJob));
+    assert!(!actions.contains(EscrowAction::AcceptFreelancer));
+    assert!(client.get_allowed_actions(&job_id, &freelancer).contains(EscrowAction::ApplyToJob));
+    client.apply_to_job(&job_id, &String::from_str(&env, "Hire me"), &7, &freelancer);
+    assert!(client.get_allowed_actions(&job_id, &depositor).contains(EscrowAction::AcceptFreelancer));
+    env.ledger().with_mut(|ledger| ledger.timestamp += 600);
+    assert!(!client.get_allowed_actions(&job_id, &Address::generate(&env)).contains(EscrowAction::ApplyToJob));
+
+    // The depositor can dispute a milestone once it is overdue
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.start_work(&escrow_id, &beneficiary);
+    client.set_milestone_due_date(&escrow_id, &0, &100, &depositor);
+    assert!(!client.get_allowed_actions(&escrow_id, &depositor).contains(EscrowAction::DisputeMilestone(0)));
+    env.ledger().with_mut(|ledger| ledger.timestamp += 101);
+    assert!(client.get_allowed_actions(&escrow_id, &depositor).contains(EscrowAction::DisputeMilestone(0)));
+    assert!(client.get_allowed_actions(&escrow_id, &beneficiary).contains(EscrowAction::ReturnFunds));
+
+    // Pausing leaves only the refund paths open
+    client.pause_contract(&owner);
+    let actions = client.get_allowed_actions(&escrow_id, &depositor);
+    assert!(!actions.contains(EscrowAction::DisputeMilestone(0)));
+    assert!(!actions.contains(EscrowAction::MarkMilestoneComplete(0)));
+    assert_eq!(client.get_allowed_actions(&escrow_id, &beneficiary), vec![&env, EscrowAction::ReturnFunds]);
+}
+
+#[test]
+fn emergency_refunded_job_cannot_be_refunded_again() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let other_depositor = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+    mint(&env, &native, &other_depositor, 10_100);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    create_escrow(&env, &client, &other_depositor, &Address::generate(&env), 10_000);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3600 + EMERGENCY_REFUND_DELAY + 1);
+    client.emergency_refund_after_deadline(&escrow_id, &depositor);
+    assert!(!client.get_allowed_actions(&escrow_id, &depositor).contains(EscrowAction::RefundEscrow));
+    assert_eq!(
+        client.try_refund_escrow(&escrow_id, &depositor),
+        Err(Ok(contract_error(DeCentPayError::InvalidEscrowStatus)))
+    );
+
+    // The other escrow's deposit and fee stay in the contract
+    assert_eq!(xlm.balance(&depositor), 10_100);
+    assert_eq!(xlm.balance(&client.address), 10_100);
+}
+
+/// Dispute the single milestone of a delivered escrow ruled on by `arbiters`, and escalate it with
+/// matched 20% stakes from both sides
+fn matched_escalation(env: &Env, client: &DeCentPayClient, native: &Address, arbiters: &soroban_sdk::Vec<Address>, required: u32) -> (u32, Address, Address) {
+    let depositor = Address::generate(env);
+    let beneficiary = Address::generate(env);
+    mint(env, native, &depositor, 12_100);
+    mint(env, native, &beneficiary, 2_000);
+
+    let escrow_id = client.create_escrow(
+        &depositor,
+        &Some(beneficiary.clone()),
+        arbiters,
+        &required,
+        &vec![env, (10_000, String::from_str(env, "Build"))],
+        &None,
+        &10_000,
+        &3600,
+        &String::from_str(env, "Disputed job"),
+        &String::from_str(env, "Paid in XLM"),
+    );
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(env, "Done"), &None, &beneficiary);
+    client.dispute_milestone(&escrow_id, &0, &String::from_str(env, "Incomplete"), &depositor);
+    client.escalate_dispute(&escrow_id, &0, &depositor);
+    client.escalate_dispute(&escrow_id, &0, &beneficiary);
+    (escrow_id, depositor, beneficiary)
+}
+
+#[test]
+fn escalation_without_a_panel_is_ruled_by_the_appointed_arbiter_only() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    client.authorize_arbiter(&first, &owner);
+    client.authorize_arbiter(&second, &owner);
+
+    let (escrow_id, depositor, beneficiary) = matched_escalation(&env, &client, &native, &vec![&env], 0);
+    assert_eq!(
+        client.try_rule_escalation(&escrow_id, &first, &true),
+        Err(Ok(contract_error(DeCentPayError::OnlyArbiter)))
+    );
+
+    let appointed = client.appoint_platform_arbiter(&escrow_id, &0, &depositor);
+    let other = if appointed == first { second } else { first };
+    assert_eq!(
+        client.try_rule_escalation(&escrow_id, &other, &true),
+        Err(Ok(contract_error(DeCentPayError::OnlyArbiter)))
+    );
+
+    client.rule_escalation(&escrow_id, &appointed, &true);
+    assert_eq!(xlm.balance(&beneficiary), 14_000);
+    assert_eq!(xlm.balance(&depositor), 0);
+    assert_eq!(client.get_escalation(&escrow_id), None);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+}
+
+#[test]
+fn escalation_ruling_waits_for_the_panel_threshold() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    for arbiter in [&first, &second, &outsider] {
+        client.authorize_arbiter(arbiter, &owner);
+    }
+
+    let panel = vec![&env, first.clone(), second.clone()];
+    let (escrow_id, depositor, beneficiary) = matched_escalation(&env, &client, &native, &panel, 2);
+    assert_eq!(
+        client.try_rule_escalation(&escrow_id, &outsider, &false),
+        Err(Ok(contract_error(DeCentPayError::OnlyArbiter)))
+    );
+
+    client.rule_escalation(&escrow_id, &first, &false);
+    assert_eq!(client.get_escalation_votes(&escrow_id).len(), 1);
+    assert_eq!(
+        client.try_rule_escalation(&escrow_id, &first, &false),
+        Err(Ok(contract_error(DeCentPayError::AlreadyVoted)))
+    );
+    assert!(client.get_escalation(&escrow_id).is_some());
+
+    client.rule_escalation(&escrow_id, &second, &false);
+    assert_eq!(client.get_escalation(&escrow_id), None);
+    assert_eq!(client.get_escalation_votes(&escrow_id).len(), 0);
+    assert_eq!(xlm.balance(&beneficiary), 0);
+    assert_eq!(xlm.balance(&depositor), 14_000);
+}
+
+#[test]
+fn defect_claim_without_a_panel_is_ruled_by_the_drawn_arbiter_only() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let lead = Address::generate(&env);
+    let partner = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    client.authorize_arbiter(&first, &owner);
+    client.authorize_arbiter(&second, &owner);
+
+    let escrow_id = release_team_escrow_with_retainage(&env, &client, &native, &depositor, &lead, &partner);
+    client.open_defect_claim(&escrow_id, &String::from_str(&env, "Broken build"), &600, &depositor);
+    let drawn = client.get_defect_arbiter(&escrow_id).unwrap();
+    let other = if drawn == first { second } else { first };
+    assert_eq!(
+        client.try_vote_defect_claim(&escrow_id, &other, &600),
+        Err(Ok(contract_error(DeCentPayError::OnlyArbiter)))
+    );
+
+    client.vote_defect_claim(&escrow_id, &drawn, &600);
+    assert_eq!(xlm.balance(&depositor), 600);
+    assert_eq!(xlm.balance(&lead), 5_400 + 240);
+    assert_eq!(xlm.balance(&partner), 3_600 + 160);
+    assert_eq!(client.get_defect_arbiter(&escrow_id), None);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+}
+
+#[test]
+fn milestone_arbiter_override_must_be_able_to_rule() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    client.authorize_arbiter(&arbiter, &owner);
+    mint(&env, &native, &depositor, 10_100);
+
+    let params = escrow_params(&env, Some(beneficiary.clone()), 10_000);
+    let with_panel = |milestone_index: u32, panel: &MilestoneArbiters| EscrowCreateOptions {
+        milestone_arbiters: vec![&env, (milestone_index, panel.clone())],
+        ..EscrowCreateOptions::new(&env)
+    };
+    let empty_panel = MilestoneArbiters {
+        arbiters: vec![&env],
+        required_confirmations: 0,
+        replace_default: true,
+    };
+    assert_eq!(
+        client.try_create_escrow_with_options(&depositor, &params, &with_panel(0, &empty_panel)),
+        Err(Ok(contract_error(DeCentPayError::InvalidParameter)))
+    );
+
+    let unconfirmed = MilestoneArbiters {
+        arbiters: vec![&env, arbiter.clone()],
+        required_confirmations: 0,
+        replace_default: true,
+    };
+    assert_eq!(
+        client.try_create_escrow_with_options(&depositor, &params, &with_panel(0, &unconfirmed)),
+        Err(Ok(contract_error(DeCentPayError::InvalidConfirmations)))
+    );
+
+    let biased = MilestoneArbiters {
+        arbiters: vec![&env, beneficiary.clone()],
+        required_confirmations: 1,
+        replace_default: true,
+    };
+    assert_eq!(
+        client.try_create_escrow_with_options(&depositor, &params, &with_panel(0, &biased)),
+        Err(Ok(contract_error(DeCentPayError::InvalidAddress)))
+    );
+
+    let panel = MilestoneArbiters {
+        arbiters: vec![&env, arbiter],
+        required_confirmations: 1,
+        replace_default: true,
+    };
+    assert_eq!(
+        client.try_create_escrow_with_options(&depositor, &params, &with_panel(1, &panel)),
+        Err(Ok(contract_error(DeCentPayError::InvalidMilestone)))
+    );
+    let escrow_id = client.create_escrow_with_options(&depositor, &params, &with_panel(0, &panel));
+    let effective = client.get_milestone_arbiters(&escrow_id, &0);
+    assert_eq!(effective.arbiters, panel.arbiters);
+    assert_eq!(effective.required_confirmations, 1);
+}
+
+#[test]
+fn vested_claims_wait_for_the_minimum_payout() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+    client.set_payout_preferences(&beneficiary, &PayoutPreferences { min_payout: 6_000, payout_address: None });
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.set_streaming(&escrow_id, &1_000, &depositor);
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Done"), &None, &beneficiary);
+    client.approve_milestone(&escrow_id, &0, &depositor);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 500);
+    assert_eq!(
+        client.try_claim_vested(&escrow_id, &0, &beneficiary),
+        Err(Ok(contract_error(DeCentPayError::BelowMinPayout)))
+    );
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 200);
+    assert_eq!(client.claim_vested(&escrow_id, &0, &beneficiary), 7_000);
+
+    // The last remainder can always be claimed
+    env.ledger().with_mut(|ledger| ledger.timestamp += 300);
+    assert_eq!(client.claim_vested(&escrow_id, &0, &beneficiary), 3_000);
+    assert_eq!(xlm.balance(&beneficiary), 10_000);
+}
+
+#[test]
+fn arbitration_fee_is_held_at_creation_and_returned_without_a_dispute() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_600);
+
+    let options = EscrowCreateOptions { arbitration_fee: ArbitrationFee::Flat(500), ..EscrowCreateOptions::new(&env) };
+    let escrow_id = client.create_escrow_with_options(&depositor, &escrow_params(&env, Some(beneficiary.clone()), 10_000), &options);
+    assert_eq!(xlm.balance(&depositor), 0);
+    assert_eq!(client.get_escrowed_amount(&None), 10_600);
+    assert_eq!(client.get_arbitration_deposit(&escrow_id).map(|deposit| deposit.held), Some(500));
+
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Done"), &None, &beneficiary);
+    client.approve_milestone(&escrow_id, &0, &depositor);
+    assert_eq!(xlm.balance(&beneficiary), 10_000);
+    assert_eq!(xlm.balance(&depositor), 500);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+
+    let excessive = EscrowCreateOptions { arbitration_fee: ArbitrationFee::BasisPoints(10_001), ..EscrowCreateOptions::new(&env) };
+    mint(&env, &native, &depositor, 10_100);
+    assert_eq!(
+        client.try_create_escrow_with_options(&depositor, &escrow_params(&env, Some(beneficiary), 10_000), &excessive),
+        Err(Ok(contract_error(DeCentPayError::InvalidParameter)))
+    );
+}
+
+#[test]
+fn application_requirements_are_set_at_creation() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    mint(&env, &native, &depositor, 20_200);
+
+    let options = EscrowCreateOptions {
+        min_reputation: 40,
+        ..EscrowCreateOptions::new(&env)
+    };
+    let escrow_id = client.create_escrow_with_options(&depositor, &escrow_params(&env, None, 10_000), &options);
+    assert_eq!(client.get_application_requirements(&escrow_id).map(|requirements| requirements.min_reputation), Some(40));
+
+    let newcomer = Address::generate(&env);
+    assert_eq!(
+        client.try_apply_to_job(&escrow_id, &String::from_str(&env, "Cover letter"), &7, &newcomer),
+        Err(Ok(contract_error(DeCentPayError::InsufficientReputation)))
+    );
+    let established = Address::generate(&env);
+    env.as_contract(&client.address, || crate::escrow_core::set_reputation(&env, established.clone(), 40));
+    client.apply_to_job(&escrow_id, &String::from_str(&env, "Cover letter"), &7, &established);
+    assert_eq!(client.get_applications(&escrow_id).len(), 1);
+
+    // Only open jobs take applications
+    let beneficiary = Address::generate(&env);
+    assert_eq!(
+        client.try_create_escrow_with_options(&depositor, &escrow_params(&env, Some(beneficiary), 10_000), &options),
+        Err(Ok(contract_error(DeCentPayError::NotOpenJob)))
+    );
+}
+
+#[test]
+fn referrer_set_at_creation_earns_a_share_of_the_platform_fee() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let referrer = Address::generate(&env);
+    client.set_referral_share_bp(&2_000, &owner);
+    mint(&env, &native, &depositor, 10_100);
+
+    let options = EscrowCreateOptions { referrer: Some(referrer.clone()), ..EscrowCreateOptions::new(&env) };
+    let escrow_id = client.create_escrow_with_options(&depositor, &escrow_params(&env, Some(beneficiary.clone()), 10_000), &options);
+    assert_eq!(client.get_referrer(&escrow_id), Some(referrer.clone()));
+    assert_eq!(client.get_referral_earnings(&referrer, &None), 0);
+
+    // The fee is booked, and the referrer's share credited, when work starts
+    client.start_work(&escrow_id, &beneficiary);
+    assert_eq!(client.get_referral_earnings(&referrer, &None), 20);
+    assert_eq!(client.claim_referral_earnings(&referrer, &None), 20);
+    assert_eq!(xlm.balance(&referrer), 20);
+    assert_eq!(
+        client.try_claim_referral_earnings(&referrer, &None),
+        Err(Ok(contract_error(DeCentPayError::InsufficientFees)))
+    );
+
+    let self_referral = EscrowCreateOptions { referrer: Some(depositor.clone()), ..EscrowCreateOptions::new(&env) };
+    mint(&env, &native, &depositor, 10_100);
+    assert_eq!(
+        client.try_create_escrow_with_options(&depositor, &escrow_params(&env, Some(beneficiary), 10_000), &self_referral),
+        Err(Ok(contract_error(DeCentPayError::InvalidAddress)))
+    );
+}
+
+#[test]
+fn application_stake_set_at_creation_is_forfeited_after_the_grace_period() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let hired = Address::generate(&env);
+    let passed_over = Address::generate(&env);
+    mint(&env, &native, &depositor, 20_200);
+    mint(&env, &native, &hired, 500);
+    mint(&env, &native, &passed_over, 500);
+
+    let options = EscrowCreateOptions { application_stake: 500, stake_grace_period: 3600, ..EscrowCreateOptions::new(&env) };
+    let escrow_id = client.create_escrow_with_options(&depositor, &escrow_params(&env, None, 10_000), &options);
+    let depositor_balance = xlm.balance(&depositor);
+    for freelancer in [&hired, &passed_over] {
+        client.apply_to_job(&escrow_id, &String::from_str(&env, "Cover letter"), &7, freelancer);
+        assert_eq!(client.get_locked_stake(&escrow_id, freelancer), 500);
+        assert_eq!(xlm.balance(freelancer), 0);
+    }
+
+    client.accept_freelancer(&escrow_id, &hired, &depositor);
+    assert_eq!(xlm.balance(&passed_over), 500);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3600);
+    assert_eq!(
+        client.try_slash_application_stake(&escrow_id, &depositor),
+        Err(Ok(contract_error(DeCentPayError::StakeGracePeriodActive)))
+    );
+    env.ledger().with_mut(|ledger| ledger.timestamp += 1);
+    client.slash_application_stake(&escrow_id, &depositor);
+    assert_eq!(xlm.balance(&depositor), depositor_balance + 500);
+    assert_eq!(client.get_locked_stake(&escrow_id, &hired), 0);
+
+    // Only open jobs take applications
+    let beneficiary = Address::generate(&env);
+    assert_eq!(
+        client.try_create_escrow_with_options(&depositor, &escrow_params(&env, Some(beneficiary), 10_000), &options),
+        Err(Ok(contract_error(DeCentPayError::NotOpenJob)))
+    );
+}
+
+#[test]
+fn category_reputation_is_credited_to_the_freelancer_only() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 101_000);
+
+    // Large enough to earn reputation
+    let params = escrow_params(&env, Some(beneficiary.clone()), 100_000);
+    let options = EscrowCreateOptions { category: JobCategory::Design, ..EscrowCreateOptions::new(&env) };
+    let escrow_id = client.create_escrow_with_options(&depositor, &params, &options);
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Done"), &None, &beneficiary);
+    client.approve_milestone(&escrow_id, &0, &depositor);
+
+    assert!(client.get_reputation_by_category(&beneficiary, &JobCategory::Design) > 0);
+    assert!(client.get_reputation(&depositor) > 0);
+    assert_eq!(client.get_reputation_by_category(&depositor, &JobCategory::Design), 0);
+}
+
+#[test]
+fn fee_quote_matches_what_an_open_job_costs() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    client.set_posting_fee(&None, &50, &owner);
+    client.set_seriousness_deposit(&None, &200, &owner);
+
+    let quote = client.quote_fees(
+        &10_000,
+        &None,
+        &EscrowOptions { is_open_job: true, arbitration_fee: ArbitrationFee::Flat(300), depositor: Some(depositor.clone()), retainage_bp: 1_000 },
+    );
+    assert_eq!(quote.seriousness_deposit, 200);
+    assert_eq!(quote.depositor_total, 10_000 + 100 + 50 + 200 + 300);
+    assert_eq!(quote.retainage, 1_000);
+    assert_eq!(quote.beneficiary_net, 9_000);
+
+    mint(&env, &native, &depositor, quote.depositor_total);
+    let options = EscrowCreateOptions { arbitration_fee: ArbitrationFee::Flat(300), ..EscrowCreateOptions::new(&env) };
+    client.create_escrow_with_options(&depositor, &escrow_params(&env, None, 10_000), &options);
+    assert_eq!(xlm.balance(&depositor), 0);
+
+    // An exempt depositor is quoted the platform fee as a discount
+    client.set_fee_exempt(&depositor, &true, &owner);
+    let exempt = client.quote_fees(
+        &10_000,
+        &None,
+        &EscrowOptions { is_open_job: false, arbitration_fee: ArbitrationFee::None, depositor: Some(depositor), retainage_bp: 0 },
+    );
+    assert_eq!(exempt.discount, exempt.platform_fee);
+    assert_eq!(exempt.depositor_total, 10_000);
+}
+
+#[test]
+fn escrow_quote_withholds_retainage_per_milestone() {
+    let env = Env::default();
+    let (client, _, _) = setup(&env);
+    let options = EscrowOptions { is_open_job: false, arbitration_fee: ArbitrationFee::None, depositor: None, retainage_bp: 1_000 };
+
+    let quote = client.quote_escrow(&10_000, &None, &vec![&env, 3_000, 7_000], &options);
+    assert_eq!(quote.milestone_payouts, vec![&env, 2_700, 6_300]);
+    assert_eq!(quote.retainage, 1_000);
+    assert_eq!(quote.beneficiary_total, 9_000);
+    assert_eq!(quote.depositor_total, 10_100);
+
+    assert_eq!(
+        client.try_quote_escrow(&10_000, &None, &vec![&env, 3_000, 6_000], &options),
+        Err(Ok(contract_error(DeCentPayError::MilestoneTotalMismatch)))
+    );
+}
+
+#[test]
+fn escrow_milestones_must_add_up_to_the_total() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let mut params = escrow_params(&env, Some(Address::generate(&env)), 10_000);
+    params.milestones = vec![&env, (3_000, String::from_str(&env, "Design")), (6_000, String::from_str(&env, "Build"))];
+    assert_eq!(
+        client.try_create_escrow_with_options(&depositor, &params, &EscrowCreateOptions::new(&env)),
+        Err(Ok(contract_error(DeCentPayError::MilestoneTotalMismatch)))
+    );
+
+    params.milestones = vec![&env, (11_000, String::from_str(&env, "Design")), (-1_000, String::from_str(&env, "Build"))];
+    assert_eq!(
+        client.try_create_escrow_with_options(&depositor, &params, &EscrowCreateOptions::new(&env)),
+        Err(Ok(contract_error(DeCentPayError::InvalidAmount)))
+    );
+}
+
+#[test]
+fn retainer_periods_are_claimable_once_ended_and_pause_pushes_them_back() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 2_020);
+
+    let escrow_id = client.create_retainer(&depositor, &beneficiary, &None, &1_000, &3600, &2);
+    client.start_work(&escrow_id, &beneficiary);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3599);
+    assert_eq!(
+        client.try_claim_retainer_period(&escrow_id, &0, &beneficiary),
+        Err(Ok(contract_error(DeCentPayError::PeriodNotEnded)))
+    );
+    env.ledger().with_mut(|ledger| ledger.timestamp += 1);
+    client.claim_retainer_period(&escrow_id, &0, &beneficiary);
+    assert_eq!(xlm.balance(&beneficiary), 1_000);
+
+    // A 600 second pause pushes the end of the second period back by as much
+    client.pause_retainer(&escrow_id, &depositor);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 600);
+    assert_eq!(
+        client.try_claim_retainer_period(&escrow_id, &1, &beneficiary),
+        Err(Ok(contract_error(DeCentPayError::RetainerPaused)))
+    );
+    client.resume_retainer(&escrow_id, &depositor);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3599);
+    assert_eq!(
+        client.try_claim_retainer_period(&escrow_id, &1, &beneficiary),
+        Err(Ok(contract_error(DeCentPayError::PeriodNotEnded)))
+    );
+    env.ledger().with_mut(|ledger| ledger.timestamp += 1);
+    client.claim_retainer_period(&escrow_id, &1, &beneficiary);
+    assert_eq!(xlm.balance(&beneficiary), 2_000);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.status), Some(EscrowStatus::Released));
+}
+
+#[test]
+fn time_and_materials_pays_approved_hours_and_refunds_the_unbilled_cap() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 1_010);
+
+    let escrow_id = client.create_time_and_materials(&depositor, &beneficiary, &None, &100, &1_000, &3600);
+    client.start_work(&escrow_id, &beneficiary);
+    let entry = client.log_hours(&escrow_id, &4, &String::from_str(&env, "Setup"), &beneficiary);
+    // Entries awaiting approval count against the cap
+    assert_eq!(
+        client.try_log_hours(&escrow_id, &7, &String::from_str(&env, "Build"), &beneficiary),
+        Err(Ok(contract_error(DeCentPayError::ExceedsCap)))
+    );
+    assert_eq!(
+        client.try_close_time_and_materials(&escrow_id, &depositor),
+        Err(Ok(contract_error(DeCentPayError::EntriesOutstanding)))
+    );
+
+    client.approve_time_entries(&escrow_id, &vec![&env, entry], &depositor);
+    assert_eq!(xlm.balance(&beneficiary), 400);
+    client.close_time_and_materials(&escrow_id, &depositor);
+    assert_eq!(xlm.balance(&depositor), 600);
+    assert_eq!(client.get_escrowed_amount(&None), 0);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.status), Some(EscrowStatus::Released));
+}
+
+/// Yield adapter that records the principal of each position and pays its whole balance of the
+/// token out on withdrawal, so minting to it simulates accrued yield
+#[contract]
+struct MockYieldAdapter;
+
+#[contractimpl]
+impl MockYieldAdapter {
+    pub fn deposit(env: Env, position: u32, token: Address, amount: i128) {
+        env.storage().instance().set(&position, &(token, amount));
+    }
+
+    pub fn withdraw(env: Env, position: u32, token: Address, to: Address) -> i128 {
+        env.storage().instance().remove(&position);
+        let token = token::Client::new(&env, &token);
+        let balance = token.balance(&env.current_contract_address());
+        token.transfer(&env.current_contract_address(), &to, &balance);
+        balance
+    }
+}
+
+#[test]
+fn idle_funds_return_with_their_yield_split_between_the_parties() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    assert_eq!(
+        client.try_deposit_idle_funds(&escrow_id, &4_000, &YieldRecipient::Depositor, &depositor),
+        Err(Ok(contract_error(DeCentPayError::YieldAdapterNotSet)))
+    );
+    let adapter = env.register(MockYieldAdapter, ());
+    client.set_yield_adapter(&Some(adapter.clone()), &owner);
+    assert_eq!(
+        client.try_deposit_idle_funds(&escrow_id, &10_001, &YieldRecipient::Depositor, &depositor),
+        Err(Ok(contract_error(DeCentPayError::InvalidAmount)))
+    );
+
+    client.deposit_idle_funds(&escrow_id, &4_000, &YieldRecipient::Split(5_000), &depositor);
+    assert_eq!(xlm.balance(&adapter), 4_000);
+    mint(&env, &native, &adapter, 200);
+    client.withdraw_idle_funds(&escrow_id, &beneficiary);
+    assert_eq!(xlm.balance(&beneficiary), 100);
+    assert_eq!(xlm.balance(&depositor), 100);
+    assert_eq!(client.get_yield_position(&escrow_id), None);
+
+    // Funds that come back short of the principal are refused, keeping the position open
+    client.deposit_idle_funds(&escrow_id, &4_000, &YieldRecipient::Depositor, &depositor);
+    xlm.transfer(&adapter, Address::generate(&env), &1);
+    assert_eq!(
+        client.try_withdraw_idle_funds(&escrow_id, &depositor),
+        Err(Ok(contract_error(DeCentPayError::YieldShortfall)))
+    );
+    assert!(client.get_yield_position(&escrow_id).is_some());
+}
+
+/// Release a native XLM escrow with a single milestone worth 10 000 to `beneficiary`
+fn create_released_escrow(env: &Env, client: &DeCentPayClient, native: &Address, depositor: &Address, beneficiary: &Address) -> u32 {
+    mint(env, native, depositor, 10_100);
+    let escrow_id = create_escrow(env, client, depositor, beneficiary, 10_000);
+    client.start_work(&escrow_id, beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(env, "Done"), &None, beneficiary);
+    client.approve_milestone(&escrow_id, &0, depositor);
+    escrow_id
+}
+
+#[test]
+fn rating_edit_window_is_counted_in_seconds() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let escrow_id = create_released_escrow(&env, &client, &native, &depositor, &beneficiary);
+
+    client.submit_rating(&escrow_id, &3, &String::from_str(&env, "Fine"), &depositor);
+    assert_eq!(client.get_rating_edit_window(), 604_800);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 604_800);
+    client.update_rating(&escrow_id, &4, &String::from_str(&env, "Good"), &depositor);
+    assert_eq!(client.get_average_rating(&beneficiary), (4, 1));
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 1);
+    assert_eq!(
+        client.try_update_rating(&escrow_id, &5, &String::from_str(&env, "Great"), &depositor),
+        Err(Ok(contract_error(DeCentPayError::RatingEditWindowClosed)))
+    );
+}
+
+#[test]
+fn edited_five_star_rating_counts_once() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let escrow_id = create_released_escrow(&env, &client, &native, &depositor, &beneficiary);
+    let five_star_count = || {
+        env.as_contract(&client.address, || {
+            env.storage().instance().get::<DataKey, u32>(&DataKey::FiveStarCount(beneficiary.clone())).unwrap_or(0)
+        })
+    };
+
+    client.submit_rating(&escrow_id, &5, &String::from_str(&env, "Great"), &depositor);
+    for _ in 0..3 {
+        client.update_rating(&escrow_id, &5, &String::from_str(&env, "Still great"), &depositor);
+    }
+    assert_eq!(five_star_count(), 1);
+
+    client.update_rating(&escrow_id, &4, &String::from_str(&env, "Good"), &depositor);
+    assert_eq!(five_star_count(), 0);
+}
+
+#[test]
+fn overdue_milestone_is_refunded_to_the_depositor() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let mut params = escrow_params(&env, Some(beneficiary.clone()), 10_000);
+    params.milestones = vec![
+        &env,
+        (6_000, String::from_str(&env, "Design")),
+        (4_000, String::from_str(&env, "Build")),
+    ];
+    let escrow_id = client.create_escrow_with_options(&depositor, &params, &EscrowCreateOptions::new(&env));
+    client.start_work(&escrow_id, &beneficiary);
+    client.set_milestone_due_date(&escrow_id, &1, &100, &depositor);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 100);
+    assert_eq!(
+        client.try_refund_overdue_milestone(&escrow_id, &1, &depositor),
+        Err(Ok(contract_error(DeCentPayError::MilestoneNotOverdue)))
+    );
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 1);
+    client.refund_overdue_milestone(&escrow_id, &1, &depositor);
+    assert_eq!(xlm.balance(&depositor), 4_000);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.total_amount), Some(6_000));
+    assert_eq!(client.get_escrowed_amount(&None), 6_000);
+    assert_eq!(last_activity(&client, escrow_id), Some(ActivityAction::MilestoneRefunded(1)));
+    assert_eq!(
+        client.try_refund_overdue_milestone(&escrow_id, &1, &depositor),
+        Err(Ok(contract_error(DeCentPayError::MilestoneNotOverdue)))
+    );
+}
+
+#[test]
+fn batch_creation_pulls_every_deposit_with_one_signature() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    mint(&env, &native, &depositor, 15_150);
+
+    let escrow_ids = client.create_escrows_batch(
+        &depositor,
+        &vec![&env, escrow_params(&env, Some(first.clone()), 10_000), escrow_params(&env, Some(second.clone()), 5_000)],
+    );
+    assert_signed_only_by(&env, &depositor);
+
+    assert_eq!(escrow_ids.len(), 2);
+    assert_eq!(xlm.balance(&depositor), 0);
+    assert_eq!(xlm.balance(&client.address), 15_150);
+    for (escrow_id, beneficiary, amount) in [(escrow_ids.get(0), first, 10_000), (escrow_ids.get(1), second, 5_000)] {
+        let escrow = escrow_id.and_then(|escrow_id| client.get_escrow(&escrow_id)).unwrap();
+        assert_eq!(escrow.beneficiary, Some(beneficiary));
+        assert_eq!(escrow.total_amount, amount);
+        assert_eq!(escrow.status, EscrowStatus::Pending);
+    }
+}
+
+#[test]
+fn batch_creation_prepays_open_job_posting_costs() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    client.set_posting_fee(&None, &50, &owner);
+    client.set_seriousness_deposit(&None, &200, &owner);
+    let depositor = Address::generate(&env);
+    mint(&env, &native, &depositor, 15_400);
+
+    let escrow_ids = client.create_escrows_batch(
+        &depositor,
+        &vec![&env, escrow_params(&env, None, 10_000), escrow_params(&env, Some(Address::generate(&env)), 5_000)],
+    );
+    let auths = env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths.first().map(|(_, invocation)| invocation.sub_invocations.len()), Some(1));
+
+    assert_eq!(escrow_ids.len(), 2);
+    assert_eq!(xlm.balance(&depositor), 0);
+    assert_eq!(xlm.balance(&owner), 50);
+    assert_eq!(xlm.balance(&client.address), 15_350);
+    assert_eq!(escrow_ids.get(0).map(|escrow_id| client.get_seriousness_deposit_held(&escrow_id)), Some(200));
+}
+
+#[test]
+fn unreviewed_submission_is_released_once_the_review_period_ends() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let params = escrow_params(&env, Some(beneficiary.clone()), 10_000);
+    let options = EscrowCreateOptions { review_period: 3_600, ..EscrowCreateOptions::new(&env) };
+    let escrow_id = client.create_escrow_with_options(&depositor, &params, &options);
+    assert_eq!(client.get_review_period(&escrow_id), Some(3_600));
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Done"), &None, &beneficiary);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3_599);
+    assert_eq!(
+        client.try_auto_release_milestone(&escrow_id, &0),
+        Err(Ok(contract_error(DeCentPayError::ReleaseNotDue)))
+    );
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 1);
+    client.auto_release_milestone(&escrow_id, &0);
+    assert!(env.auths().is_empty());
+    assert_eq!(xlm.balance(&beneficiary), 10_000);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.status), Some(EscrowStatus::Released));
+
+    // Without a review period only the depositor can release
+    mint(&env, &native, &depositor, 10_100);
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Done"), &None, &beneficiary);
+    assert_eq!(
+        client.try_auto_release_milestone(&escrow_id, &0),
+        Err(Ok(contract_error(DeCentPayError::NoReviewPeriod)))
+    );
+}
+
+#[test]
+fn category_and_skill_tags_are_set_at_creation() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 30_300);
+
+    let params = escrow_params(&env, Some(beneficiary.clone()), 10_000);
+    let skills = vec![&env, Symbol::new(&env, "rust"), Symbol::new(&env, "soroban")];
+    let options = EscrowCreateOptions { category: JobCategory::SmartContracts, skill_tags: skills.clone(), ..EscrowCreateOptions::new(&env) };
+    let escrow_id = client.create_escrow_with_options(&depositor, &params, &options);
+    assert_eq!(client.get_job_category(&escrow_id), Some(JobCategory::SmartContracts));
+    assert_eq!(client.get_skill_tags(&escrow_id), skills);
+    let listings = client.list_jobs_by_category(&JobCategory::SmartContracts, &0, &10);
+    assert_eq!(listings.len(), 1);
+
+    // Uncategorized jobs stay out of the category listings
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    assert_eq!(client.get_job_category(&escrow_id), None);
+    assert!(client.list_jobs_by_category(&JobCategory::Uncategorized, &0, &10).is_empty());
+
+    let mut too_many = Vec::new(&env);
+    for skill in ["a", "b", "c", "d", "e", "f"] {
+        too_many.push_back(Symbol::new(&env, skill));
+    }
+    let options = EscrowCreateOptions { skill_tags: too_many, ..EscrowCreateOptions::new(&env) };
+    assert_eq!(
+        client.try_create_escrow_with_options(&depositor, &params, &options),
+        Err(Ok(contract_error(DeCentPayError::TooManySkillTags)))
+    );
+}
+
+#[test]
+fn team_splits_are_validated_at_creation() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let lead = Address::generate(&env);
+    let partner = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let params = escrow_params(&env, Some(lead.clone()), 10_000);
+    for splits in [
+        vec![&env, (lead.clone(), 6_000), (partner.clone(), 3_000)],
+        vec![&env, (partner.clone(), 10_000)],
+        vec![&env, (lead.clone(), 6_000), (depositor.clone(), 4_000)],
+    ] {
+        let options = EscrowCreateOptions { team_splits: splits, ..EscrowCreateOptions::new(&env) };
+        assert_eq!(
+            client.try_create_escrow_with_options(&depositor, &params, &options),
+            Err(Ok(contract_error(DeCentPayError::InvalidParameter)))
+        );
+    }
+
+    let options = EscrowCreateOptions {
+        team_splits: vec![&env, (lead.clone(), 6_000), (partner.clone(), 4_000)],
+        ..EscrowCreateOptions::new(&env)
+    };
+    let escrow_id = client.create_escrow_with_options(&depositor, &params, &options);
+    assert_eq!(client.get_team_splits(&escrow_id), Some(options.team_splits));
+}
+
+#[test]
+fn application_deadline_is_fixed_at_creation() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 30_000);
+
+    let options = EscrowCreateOptions { application_period: 600, ..EscrowCreateOptions::new(&env) };
+    assert_eq!(
+        client.try_create_escrow_with_options(&depositor, &escrow_params(&env, Some(beneficiary), 10_000), &options),
+        Err(Ok(contract_error(DeCentPayError::NotOpenJob)))
+    );
+
+    let escrow_id = client.create_escrow_with_options(&depositor, &escrow_params(&env, None, 10_000), &options);
+    assert_eq!(client.get_application_deadline(&escrow_id), Some(600));
+    env.ledger().with_mut(|ledger| ledger.timestamp += 599);
+    client.apply_to_job(&escrow_id, &String::from_str(&env, "Cover letter"), &7, &Address::generate(&env));
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 1);
+    assert_eq!(
+        client.try_apply_to_job(&escrow_id, &String::from_str(&env, "Cover letter"), &7, &Address::generate(&env)),
+        Err(Ok(contract_error(DeCentPayError::ApplicationDeadlinePassed)))
+    );
+    client.expire_open_job(&escrow_id);
+}
+
+#[test]
+fn posting_gate_holds_back_high_value_escrows_from_new_clients() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    client.set_posting_gate(&Some(PostingGate { value_threshold: 5_000, min_reputation: 1_000, min_completed_escrows: 1 }), &owner);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    assert_eq!(
+        client.try_create_escrow_with_options(&depositor, &escrow_params(&env, Some(beneficiary.clone()), 10_000), &EscrowCreateOptions::new(&env)),
+        Err(Ok(contract_error(DeCentPayError::InsufficientClientReputation)))
+    );
+    // Postings at or below the threshold are not gated
+    create_escrow(&env, &client, &depositor, &beneficiary, 5_000);
+}
+
+#[test]
+fn posting_gate_admits_clients_with_a_completed_escrow() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    // Let the small test escrow count towards the depositor's track record
+    client.set_value_thresholds(&None, &ValueThresholds { min_escrow_value: 0, reputation_eligible_value: 0 }, &owner);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    create_released_escrow(&env, &client, &native, &depositor, &beneficiary);
+    client.set_posting_gate(&Some(PostingGate { value_threshold: 5_000, min_reputation: 1_000, min_completed_escrows: 1 }), &owner);
+
+    mint(&env, &native, &depositor, 10_100);
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.total_amount), Some(10_000));
+}
+
+/// Dispute the single 10 000 milestone of a delivered escrow ruled on by `arbiters`
+fn disputed_escrow(env: &Env, client: &DeCentPayClient, native: &Address, arbiters: &Vec<Address>, required: u32) -> (u32, Address, Address) {
+    let depositor = Address::generate(env);
+    let beneficiary = Address::generate(env);
+    mint(env, native, &depositor, 10_100);
+
+    let escrow_id = client.create_escrow(
+        &depositor,
+        &Some(beneficiary.clone()),
+        arbiters,
+        &required,
+        &vec![env, (10_000, String::from_str(env, "Build"))],
+        &None,
+        &10_000,
+        &3600,
+        &String::from_str(env, "Disputed job"),
+        &String::from_str(env, "Paid in XLM"),
+    );
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(env, "Done"), &None, &beneficiary);
+    client.dispute_milestone(&escrow_id, &0, &String::from_str(env, "Incomplete"), &depositor);
+    (escrow_id, depositor, beneficiary)
+}
+
+#[test]
+fn dispute_ruled_for_the_beneficiary_releases_the_milestone() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let arbiter = Address::generate(&env);
+    let (escrow_id, depositor, beneficiary) = disputed_escrow(&env, &client, &native, &vec![&env, arbiter.clone()], 1);
+
+    client.resolve_dispute(&escrow_id, &0, &DisputeRuling::Release, &arbiter);
+    assert_eq!(xlm.balance(&beneficiary), 10_000);
+    assert_eq!(xlm.balance(&depositor), 0);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.status), Some(EscrowStatus::Released));
+}
+
+#[test]
+fn dispute_ruled_for_the_depositor_refunds_the_milestone() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let arbiter = Address::generate(&env);
+    let (escrow_id, depositor, beneficiary) = disputed_escrow(&env, &client, &native, &vec![&env, arbiter.clone()], 1);
+
+    client.resolve_dispute(&escrow_id, &0, &DisputeRuling::Refund, &arbiter);
+    assert_eq!(xlm.balance(&beneficiary), 0);
+    assert_eq!(xlm.balance(&depositor), 10_000);
+}
+
+#[test]
+fn split_ruling_divides_the_milestone() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let arbiter = Address::generate(&env);
+    let (escrow_id, depositor, beneficiary) = disputed_escrow(&env, &client, &native, &vec![&env, arbiter.clone()], 1);
+
+    assert_eq!(
+        client.try_resolve_dispute(&escrow_id, &0, &DisputeRuling::Split(10_001), &arbiter),
+        Err(Ok(contract_error(DeCentPayError::InvalidAmount)))
+    );
+    client.resolve_dispute(&escrow_id, &0, &DisputeRuling::Split(4_000), &arbiter);
+    assert_eq!(xlm.balance(&beneficiary), 4_000);
+    assert_eq!(xlm.balance(&depositor), 6_000);
+}
+
+#[test]
+fn dispute_ruling_waits_for_the_arbiter_threshold() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    let third = Address::generate(&env);
+    let panel = vec![&env, first.clone(), second.clone(), third.clone()];
+    let (escrow_id, _, beneficiary) = disputed_escrow(&env, &client, &native, &panel, 2);
+
+    client.resolve_dispute(&escrow_id, &0, &DisputeRuling::Release, &first);
+    assert_eq!(
+        client.try_resolve_dispute(&escrow_id, &0, &DisputeRuling::Release, &first),
+        Err(Ok(contract_error(DeCentPayError::AlreadyVoted)))
+    );
+    // A dissenting vote does not count towards the ruling
+    client.resolve_dispute(&escrow_id, &0, &DisputeRuling::Refund, &second);
+    assert_eq!(client.get_dispute_votes(&escrow_id, &0).len(), 2);
+    assert_eq!(xlm.balance(&beneficiary), 0);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.status), Some(EscrowStatus::Disputed));
+
+    client.resolve_dispute(&escrow_id, &0, &DisputeRuling::Release, &third);
+    assert_eq!(xlm.balance(&beneficiary), 10_000);
+}
+
+#[test]
+fn only_the_milestone_arbiters_can_rule_on_a_dispute() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let arbiter = Address::generate(&env);
+    let (escrow_id, depositor, beneficiary) = disputed_escrow(&env, &client, &native, &vec![&env, arbiter], 1);
+    // Platform arbiters cannot override a panel the parties chose
+    let platform_arbiter = Address::generate(&env);
+    client.authorize_arbiter(&platform_arbiter, &owner);
+
+    for outsider in [depositor, beneficiary, platform_arbiter] {
+        assert_eq!(
+            client.try_resolve_dispute(&escrow_id, &0, &DisputeRuling::Release, &outsider),
+            Err(Ok(contract_error(DeCentPayError::OnlyArbiter)))
+        );
+    }
+}
+
+#[test]
+fn split_settlement_pays_both_sides_of_the_unpaid_remainder() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let arbiter = Address::generate(&env);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = client.create_escrow(
+        &depositor,
+        &Some(beneficiary.clone()),
+        &vec![&env, arbiter.clone()],
+        &1,
+        &vec![&env, (6_000, String::from_str(&env, "Design")), (4_000, String::from_str(&env, "Build"))],
+        &None,
+        &10_000,
+        &3600,
+        &String::from_str(&env, "Disputed job"),
+        &String::from_str(&env, "Paid in XLM"),
+    );
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Designed"), &None, &beneficiary);
+    client.approve_milestone(&escrow_id, &0, &depositor);
+    client.submit_milestone(&escrow_id, &1, &String::from_str(&env, "Built"), &None, &beneficiary);
+    client.dispute_milestone(&escrow_id, &1, &String::from_str(&env, "Incomplete"), &depositor);
+
+    assert_eq!(
+        client.try_resolve_with_split(&escrow_id, &10_001, &arbiter),
+        Err(Ok(contract_error(DeCentPayError::InvalidAmount)))
+    );
+    client.resolve_with_split(&escrow_id, &2_500, &arbiter);
+
+    // A quarter of the unpaid 4 000 goes to the beneficiary; the platform keeps its fee on the escrow
+    assert_eq!(xlm.balance(&beneficiary), 7_000);
+    assert_eq!(xlm.balance(&depositor), 3_000);
+    assert_eq!(client.get_accrued_fees(&None), 100);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.status), Some(EscrowStatus::Released));
+}
+
+#[test]
+fn fee_collector_withdraws_accrued_fees() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let collector = Address::generate(&env);
+    client.set_fee_collector(&collector, &owner);
+    create_released_escrow(&env, &client, &native, &Address::generate(&env), &Address::generate(&env));
+
+    assert_eq!(
+        client.try_withdraw_fees(&None, &101),
+        Err(Ok(contract_error(DeCentPayError::InsufficientFees)))
+    );
+    client.withdraw_fees(&None, &100);
+    assert_signed_only_by(&env, &collector);
+    assert_eq!(xlm.balance(&collector), 100);
+    assert_eq!(xlm.balance(&owner), 0);
+    assert_eq!(client.get_accrued_fees(&None), 0);
+    // Withdrawals leave the lifetime total untouched
+    assert_eq!(client.get_collected_fees(&None), 100);
+}
+
+#[test]
+fn only_the_fee_collector_can_withdraw_fees() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    create_released_escrow(&env, &client, &native, &Address::generate(&env), &Address::generate(&env));
+
+    let stranger = Address::generate(&env);
+    env.mock_auths(&[MockAuth {
+        address: &stranger,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "withdraw_fees",
+            args: (None::<Address>, 100_i128).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client.try_withdraw_fees(&None, &100).is_err());
+    assert_eq!(client.get_accrued_fees(&None), 100);
+}
+
+#[test]
+fn cancelling_an_open_job_refunds_the_depositor() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    client.apply_to_job(&escrow_id, &String::from_str(&env, "Cover letter"), &7, &Address::generate(&env));
+    client.cancel_open_job(&escrow_id, &depositor);
+
+    assert_eq!(xlm.balance(&depositor), 10_100);
+    assert_eq!(client.get_accrued_fees(&None), 0);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.status), Some(EscrowStatus::Cancelled));
+}
+
+#[test]
+fn only_pending_applicants_can_be_hired() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    assert_eq!(
+        client.try_accept_freelancer(&escrow_id, &freelancer, &depositor),
+        Err(Ok(contract_error(DeCentPayError::FreelancerNotApplied)))
+    );
+
+    client.apply_to_job(&escrow_id, &String::from_str(&env, "Cover letter"), &7, &freelancer);
+    client.accept_freelancer(&escrow_id, &freelancer, &depositor);
+    assert_eq!(client.get_escrow(&escrow_id).and_then(|escrow| escrow.beneficiary), Some(freelancer));
+}
+
+#[test]
+fn open_job_cannot_be_cancelled_once_a_freelancer_is_hired() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    client.apply_to_job(&escrow_id, &String::from_str(&env, "Cover letter"), &7, &freelancer);
+    client.accept_freelancer(&escrow_id, &freelancer, &depositor);
+
+    // Hiring turns the posting into a regular escrow
+    assert_eq!(
+        client.try_cancel_open_job(&escrow_id, &depositor),
+        Err(Ok(contract_error(DeCentPayError::NotOpenJob)))
+    );
+    assert_eq!(xlm.balance(&depositor), 0);
+}
+
+#[test]
+fn scheduled_release_pays_out_once_due_without_a_signature() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.start_work(&escrow_id, &beneficiary);
+
+    let release_at = env.ledger().timestamp() + 1_000;
+    client.schedule_milestone_release(&escrow_id, &0, &release_at, &depositor);
+    assert_eq!(
+        client.try_execute_scheduled_release(&escrow_id, &0),
+        Err(Ok(contract_error(DeCentPayError::ReleaseNotDue)))
+    );
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = release_at);
+    client.execute_scheduled_release(&escrow_id, &0);
+    assert!(env.auths().is_empty());
+    assert_eq!(xlm.balance(&beneficiary), 10_000);
+    assert_eq!(client.get_scheduled_release(&escrow_id, &0), None);
+}
+
+#[test]
+fn cancelled_scheduled_release_never_pays_out() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.start_work(&escrow_id, &beneficiary);
+
+    let release_at = env.ledger().timestamp() + 1_000;
+    client.schedule_milestone_release(&escrow_id, &0, &release_at, &depositor);
+    client.cancel_scheduled_release(&escrow_id, &0, &depositor);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = release_at);
+    assert_eq!(
+        client.try_execute_scheduled_release(&escrow_id, &0),
+        Err(Ok(contract_error(DeCentPayError::NoScheduledRelease)))
+    );
+    assert_eq!(xlm.balance(&beneficiary), 0);
+}
+
+#[test]
+fn standing_approval_pays_milestones_within_its_cap_and_budget() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let milestones = vec![
+        &env,
+        (3_000, String::from_str(&env, "Design")),
+        (3_000, String::from_str(&env, "Build")),
+        (4_000, String::from_str(&env, "Launch")),
+    ];
+    let escrow_id = client.create_escrow(
+        &depositor,
+        &Some(beneficiary.clone()),
+        &vec![&env],
+        &0,
+        &milestones,
+        &None,
+        &10_000,
+        &3600,
+        &String::from_str(&env, "Native job"),
+        &String::from_str(&env, "Paid in XLM"),
+    );
+    client.grant_standing_approval(&escrow_id, &3_500, &5_000, &100, &depositor);
+    client.start_work(&escrow_id, &beneficiary);
+    for milestone_index in 0..3 {
+        client.submit_milestone(&escrow_id, &milestone_index, &String::from_str(&env, "Done"), &None, &beneficiary);
+    }
+
+    assert_eq!(
+        client.try_auto_approve_milestone(&escrow_id, &0),
+        Err(Ok(contract_error(DeCentPayError::ReleaseNotDue)))
+    );
+    env.ledger().with_mut(|ledger| ledger.timestamp += 100);
+    client.auto_approve_milestone(&escrow_id, &0);
+    assert_eq!(xlm.balance(&beneficiary), 3_000);
+    assert_eq!(client.get_standing_approval(&escrow_id).map(|approval| approval.budget_remaining), Some(2_000));
+
+    // The second milestone is over the remaining budget and the third over the cap
+    for milestone_index in [1, 2] {
+        assert_eq!(
+            client.try_auto_approve_milestone(&escrow_id, &milestone_index),
+            Err(Ok(contract_error(DeCentPayError::ExceedsStandingApproval)))
+        );
+    }
+    assert_eq!(xlm.balance(&beneficiary), 3_000);
+}
+
+#[test]
+fn purchased_revision_is_paid_to_the_beneficiary_on_approval() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_600);
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.start_work(&escrow_id, &beneficiary);
+
+    let stranger = Address::generate(&env);
+    mint(&env, &native, &stranger, 500);
+    assert_eq!(
+        client.try_purchase_revision(&escrow_id, &0, &500, &stranger),
+        Err(Ok(contract_error(DeCentPayError::OnlyDepositor)))
+    );
+
+    client.purchase_revision(&escrow_id, &0, &500, &depositor);
+    assert_signed_only_by(&env, &depositor);
+    assert_eq!(xlm.balance(&depositor), 0);
+    assert_eq!(client.get_revision_fee(&escrow_id, &0), 500);
+
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Done"), &None, &beneficiary);
+    client.approve_milestone(&escrow_id, &0, &depositor);
+    assert_eq!(xlm.balance(&beneficiary), 10_500);
+    assert_eq!(client.get_revision_fee(&escrow_id, &0), 0);
+}
+
+#[test]
+fn returned_funds_refund_the_unpaid_balance() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = client.create_escrow(
+        &depositor,
+        &Some(beneficiary.clone()),
+        &vec![&env],
+        &0,
+        &vec![&env, (6_000, String::from_str(&env, "Design")), (4_000, String::from_str(&env, "Build"))],
+        &None,
+        &10_000,
+        &3600,
+        &String::from_str(&env, "Native job"),
+        &String::from_str(&env, "Paid in XLM"),
+    );
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Designed"), &None, &beneficiary);
+    client.approve_milestone(&escrow_id, &0, &depositor);
+
+    assert_eq!(
+        client.try_return_funds(&escrow_id, &depositor),
+        Err(Ok(contract_error(DeCentPayError::OnlyBeneficiary)))
+    );
+    client.return_funds(&escrow_id, &beneficiary);
+    assert_signed_only_by(&env, &beneficiary);
+
+    // The platform fee was collected when work started
+    assert_eq!(xlm.balance(&depositor), 4_000);
+    assert_eq!(xlm.balance(&beneficiary), 6_000);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.status), Some(EscrowStatus::Refunded));
+    assert_eq!(last_activity(&client, escrow_id), Some(ActivityAction::FundsReturned));
+}
+
+#[test]
+fn depositor_completes_a_milestone_without_a_submission() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.start_work(&escrow_id, &beneficiary);
+
+    assert_eq!(
+        client.try_mark_milestone_complete(&escrow_id, &0, &beneficiary),
+        Err(Ok(contract_error(DeCentPayError::OnlyDepositor)))
+    );
+    client.mark_milestone_complete(&escrow_id, &0, &depositor);
+    assert_signed_only_by(&env, &depositor);
+    assert_eq!(xlm.balance(&beneficiary), 10_000);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.status), Some(EscrowStatus::Released));
+
+    assert_eq!(
+        client.try_mark_milestone_complete(&escrow_id, &0, &depositor),
+        Err(Ok(contract_error(DeCentPayError::EscrowNotActive)))
+    );
+}
+
+#[test]
+fn refunds_go_to_the_registered_refund_address() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let arbiter = Address::generate(&env);
+    let (escrow_id, depositor, beneficiary) = disputed_escrow(&env, &client, &native, &vec![&env, arbiter.clone()], 1);
+
+    let treasury = Address::generate(&env);
+    assert_eq!(
+        client.try_set_refund_address(&escrow_id, &Some(treasury.clone()), &beneficiary),
+        Err(Ok(contract_error(DeCentPayError::OnlyDepositor)))
+    );
+    client.set_refund_address(&escrow_id, &Some(treasury.clone()), &depositor);
+    client.resolve_dispute(&escrow_id, &0, &DisputeRuling::Refund, &arbiter);
+
+    assert_eq!(xlm.balance(&treasury), 10_000);
+    assert_eq!(xlm.balance(&depositor), 0);
+}
+
+#[test]
+fn contributor_majority_approves_a_crowdfunded_milestone() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let small_backer = Address::generate(&env);
+    let large_backer = Address::generate(&env);
+    mint(&env, &native, &depositor, 2_100);
+    mint(&env, &native, &small_backer, 3_000);
+    mint(&env, &native, &large_backer, 5_000);
+
+    let escrow_id = create_unfunded_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.contribute(&escrow_id, &2_000, &depositor);
+    client.contribute(&escrow_id, &3_000, &small_backer);
+    client.contribute(&escrow_id, &5_000, &large_backer);
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Done"), &None, &beneficiary);
+
+    assert_eq!(
+        client.try_vote_approve_milestone(&escrow_id, &0, &beneficiary),
+        Err(Ok(contract_error(DeCentPayError::Unauthorized)))
+    );
+    client.vote_approve_milestone(&escrow_id, &0, &small_backer);
+    assert_eq!(
+        client.try_vote_approve_milestone(&escrow_id, &0, &small_backer),
+        Err(Ok(contract_error(DeCentPayError::AlreadyVoted)))
+    );
+    // Exactly half of the contributions is not a majority
+    client.vote_approve_milestone(&escrow_id, &0, &depositor);
+    assert_eq!(xlm.balance(&beneficiary), 0);
+
+    client.vote_approve_milestone(&escrow_id, &0, &large_backer);
+    assert_eq!(xlm.balance(&beneficiary), 10_000);
+}
+
+#[test]
+fn abandoned_job_forfeits_its_seriousness_deposit_to_applicants() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    client.set_seriousness_deposit(&None, &1_000, &owner);
+    let depositor = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    mint(&env, &native, &depositor, 11_100);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    assert_eq!(xlm.balance(&depositor), 0);
+    assert_eq!(client.get_seriousness_deposit_held(&escrow_id), 1_000);
+    for freelancer in [&first, &second] {
+        client.apply_to_job(&escrow_id, &String::from_str(&env, "Cover letter"), &7, freelancer);
+    }
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3599);
+    assert_eq!(
+        client.try_claim_abandoned_deposit(&escrow_id),
+        Err(Ok(contract_error(DeCentPayError::JobNotAbandoned)))
+    );
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 1);
+    client.claim_abandoned_deposit(&escrow_id);
+    assert_eq!(xlm.balance(&first), 500);
+    assert_eq!(xlm.balance(&second), 500);
+    assert_eq!(client.get_seriousness_deposit_held(&escrow_id), 0);
+    assert_eq!(
+        client.try_claim_abandoned_deposit(&escrow_id),
+        Err(Ok(contract_error(DeCentPayError::NoBond)))
+    );
+}
+
+#[test]
+fn invited_freelancer_is_hired_on_accepting() {
+    let env = Env::default();
+    let (client, _, native) = setup(&env);
+    let depositor = Address::generate(&env);
+    let invited = Address::generate(&env);
+    let late = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    client.invite_freelancer(&escrow_id, &invited, &600, &depositor);
+    client.invite_freelancer(&escrow_id, &late, &100, &depositor);
+
+    assert_eq!(
+        client.try_accept_invitation(&escrow_id, &Address::generate(&env)),
+        Err(Ok(contract_error(DeCentPayError::NotInvited)))
+    );
+    env.ledger().with_mut(|ledger| ledger.timestamp += 100);
+    assert_eq!(
+        client.try_accept_invitation(&escrow_id, &late),
+        Err(Ok(contract_error(DeCentPayError::InvitationExpired)))
+    );
+
+    client.accept_invitation(&escrow_id, &invited);
+    assert_signed_only_by(&env, &invited);
+    let escrow = client.get_escrow(&escrow_id).unwrap();
+    assert_eq!(escrow.beneficiary, Some(invited.clone()));
+    assert!(!escrow.is_open_job);
+    assert!(client.get_invitation(&escrow_id, &invited).is_some_and(|invitation| invitation.accepted));
+}
+
+#[test]
+fn refundable_posting_fee_is_returned_on_hire() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    client.set_posting_fee(&None, &50, &owner);
+    client.set_posting_fee_refundable(&true, &owner);
+    let depositor = Address::generate(&env);
+    let freelancer = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_150);
+
+    let escrow_id = create_open_job(&env, &client, &depositor, 10_000);
+    assert_eq!(xlm.balance(&depositor), 0);
+    assert_eq!(client.get_posting_fee_held(&escrow_id), 50);
+
+    client.apply_to_job(&escrow_id, &String::from_str(&env, "Cover letter"), &7, &freelancer);
+    client.accept_freelancer(&escrow_id, &freelancer, &depositor);
+    assert_eq!(xlm.balance(&depositor), 50);
+    assert_eq!(xlm.balance(&owner), 0);
+    assert_eq!(client.get_posting_fee_held(&escrow_id), 0);
+}
+
+/// Attestation contract vouching for whichever addresses were verified with it
+#[contract]
+struct MockAttestation;
+
+#[contractimpl]
+impl MockAttestation {
+    pub fn verify(env: Env, subject: Address) {
+        env.storage().instance().set(&subject, &true);
+    }
+
+    pub fn has_kyc(env: Env, subject: Address) -> bool {
+        env.storage().instance().has(&subject)
+    }
+}
+
+#[test]
+fn high_value_escrow_requires_kyc_from_both_parties() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let attestation = env.register(MockAttestation, ());
+    client.set_kyc_config(&Some(KycConfig { attestation_contract: attestation.clone(), value_threshold: 5_000 }), &owner);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+    MockAttestationClient::new(&env, &attestation).verify(&depositor);
+
+    assert_eq!(
+        client.try_create_escrow_with_options(
+            &depositor,
+            &escrow_params(&env, Some(beneficiary), 10_000),
+            &EscrowCreateOptions::new(&env),
+        ),
+        Err(Ok(contract_error(DeCentPayError::KycRequired)))
+    );
+    assert_eq!(xlm.balance(&depositor), 10_100);
+}
+
+#[test]
+fn verified_parties_pass_the_kyc_gate() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let attestation = env.register(MockAttestation, ());
+    client.set_kyc_config(&Some(KycConfig { attestation_contract: attestation.clone(), value_threshold: 5_000 }), &owner);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let attestation = MockAttestationClient::new(&env, &attestation);
+    attestation.verify(&depositor);
+    attestation.verify(&beneficiary);
+
+    let escrow_id = create_released_escrow(&env, &client, &native, &depositor, &beneficiary);
+    assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.status), Some(EscrowStatus::Released));
+}
+
+/// Screening contract flagging whichever addresses were reported to it
+#[contract]
+struct MockScreening;
+
+#[contractimpl]
+impl MockScreening {
+    pub fn flag(env: Env, account: Address) {
+        env.storage().instance().set(&account, &true);
+    }
+
+    pub fn is_flagged(env: Env, account: Address) -> bool {
+        env.storage().instance().has(&account)
+    }
+}
+
+#[test]
+fn screening_blocks_payouts_to_a_flagged_beneficiary() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let screening = env.register(MockScreening, ());
+    client.set_screening_contract(&Some(screening.clone()), &owner);
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &native, &depositor, 10_100);
+
+    let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, 10_000);
+    client.start_work(&escrow_id, &beneficiary);
+    client.submit_milestone(&escrow_id, &0, &String::from_str(&env, "Done"), &None, &beneficiary);
+    MockScreeningClient::new(&env, &screening).flag(&beneficiary);
+
+    assert_eq!(
+        client.try_approve_milestone(&escrow_id, &0, &depositor),
+        Err(Ok(contract_error(DeCentPayError::AddressFlagged)))
+    );
+    assert_eq!(xlm.balance(&beneficiary), 0);
+}
+
+#[test]
+fn screening_lets_unflagged_parties_transact() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let screening = env.register(MockScreening, ());
+    client.set_screening_contract(&Some(screening.clone()), &owner);
+    MockScreeningClient::new(&env, &screening).flag(&Address::generate(&env));
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+
+    create_released_escrow(&env, &client, &native, &depositor, &beneficiary);
+    assert_eq!(xlm.balance(&beneficiary), 10_000);
+}
+
+#[test]
+fn screening_holds_a_crowdfunded_refund_with_a_flagged_contributor() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    let screening = env.register(MockScreening, ());
+    client.set_screening_contract(&Some(screening.clone()), &owner);
+    let depositor = Address::generate(&env);
+    let backer = Address::generate(&env);
+    mint(&env, &native, &depositor, 2_100);
+    mint(&env, &native, &backer, 3_000);
+
+    let escrow_id = create_unfunded_escrow(&env, &client, &depositor, &Address::generate(&env), 10_000);
+    client.contribute(&escrow_id, &2_000, &depositor);
+    client.contribute(&escrow_id, &3_000, &backer);
+    MockScreeningClient::new(&env, &screening).flag(&backer);
+
+    assert!(!client.get_allowed_actions(&escrow_id, &depositor).contains(EscrowAction::RefundEscrow));
+    assert_eq!(
+        client.try_refund_escrow(&escrow_id, &depositor),
+        Err(Ok(contract_error(DeCentPayError::AddressFlagged)))
+    );
+    assert_eq!(xlm.balance(&backer), 0);
+    assert_eq!(xlm.balance(&client.address), 5_100);
+}
+
+#[test]
+fn token_fee_override_and_volume_tiers_set_the_fee_charged() {
+    let env = Env::default();
+    let (client, owner, native) = setup(&env);
+    let xlm = token::Client::new(&env, &native);
+    client.set_token_fee_bp(&None, &200, &owner);
+    client.set_fee_tiers(
+        &None,
+        &vec![&env, FeeTier { min_amount: 20_000, fee_bp: 150 }, FeeTier { min_amount: 50_000, fee_bp: 50 }],
+        &owner,
+    );
+    let beneficiary = Address::generate(&env);
+
+    // Below the first tier the token's 2% override applies instead of the global 1%
+    for (amount, fee) in [(10_000, 200), (19_999, 399), (20_000, 300), (50_000, 250)] {
+        let depositor = Address::generate(&env);
+        mint(&env, &native, &depositor, amount + fee);
+        let escrow_id = create_escrow(&env, &client, &depositor, &beneficiary, amount);
+        assert_eq!(xlm.balance(&depositor), 0);
+        assert_eq!(client.get_escrow(&escrow_id).map(|escrow| escrow.platform_fee), Some(fee));
+    }
+}
+
+#[test]
+fn admins_delegate_operational_roles_but_not_admin() {
+    let env = Env::default();
+    let (client, owner, _) = setup(&env);
+    let admin = Address::generate(&env);
+    let moderator = Address::generate(&env);
+    client.grant_role(&admin, &Role::Admin, &owner);
+
+    client.grant_role(&moderator, &Role::Moderator, &admin);
+    assert!(client.has_role(&moderator, &Role::Moderator));
+    client.set_posting_gate(&Some(PostingGate { value_threshold: 5_000, min_reputation: 0, min_completed_escrows: 0 }), &admin);
+    assert!(client.get_posting_gate().is_some());
+
+    let result = client.try_grant_role(&Address::generate(&env), &Role::Admin, &admin);
+    assert_eq!(result, Err(Ok(contract_error(DeCentPayError::MissingRole))));
+    let result = client.try_set_posting_gate(&None, &moderator);
+    assert_eq!(result, Err(Ok(contract_error(DeCentPayError::MissingRole))));
+}
+
+#[test]
+fn policy_and_fee_setters_check_the_caller_role() {
+    let env = Env::default();
+    let (client, owner, _) = setup(&env);
+    let admin = Address::generate(&env);
+    let treasurer = Address::generate(&env);
+    client.grant_role(&admin, &Role::Admin, &owner);
+    client.grant_role(&treasurer, &Role::Treasurer, &owner);
+
+    client.set_max_review_length(&300, &admin);
+    assert_eq!(client.get_max_review_length(), 300);
+    client.set_fee_collector(&treasurer, &treasurer);
+
+    let result = client.try_set_max_review_length(&400, &treasurer);
+    assert_eq!(result, Err(Ok(contract_error(DeCentPayError::MissingRole))));
+    let result = client.try_set_screening_contract(&None, &treasurer);
+    assert_eq!(result, Err(Ok(contract_error(DeCentPayError::MissingRole))));
+    let result = client.try_set_fee_collector(&owner, &Address::generate(&env));
+    assert_eq!(result, Err(Ok(contract_error(DeCentPayError::MissingRole))));
+}
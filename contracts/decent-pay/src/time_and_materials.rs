@@ -0,0 +1,211 @@
+use crate::escrow_core;
+use crate::escrow_management::{self, Deposit};
+use crate::idle_yield;
+use crate::refund_system;
+use crate::work_lifecycle;
+use crate::storage_types::{
+    DataKey, DeCentPayError, EscrowCreateOptions, EscrowKey, EscrowStatus, Milestone, MilestoneStatus, TimeAndMaterials,
+    INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env, Error, String, Vec};
+
+const MAX_TIME_ENTRIES: u32 = 50;
+
+/// Create a time and materials escrow funding up to `cap`, billed at `hourly_rate` per logged hour.
+/// Time entries are milestones, so they are approved, rejected and disputed like any other.
+pub fn create_time_and_materials(
+    env: &Env,
+    depositor: Address,
+    beneficiary: Address,
+    token: Option<Address>,
+    hourly_rate: i128,
+    cap: i128,
+    duration: u32,
+) -> Result<u32, Error> {
+    if hourly_rate <= 0 || cap < hourly_rate {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    let escrow_id = escrow_management::create_escrow(
+        env,
+        depositor,
+        Some(beneficiary),
+        Vec::new(env),
+        0,
+        Vec::new(env),
+        Vec::new(env),
+        token,
+        cap,
+        duration,
+        String::from_str(env, "Time and materials"),
+        String::from_str(env, ""),
+        Deposit::Transfer,
+        EscrowCreateOptions::new(env),
+    )?;
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().set(
+        &EscrowKey::TimeAndMaterials(escrow_id),
+        &TimeAndMaterials {
+            hourly_rate,
+            hours_logged: 0,
+        },
+    );
+    Ok(escrow_id)
+}
+
+/// Get the billing terms of a time and materials escrow
+pub fn get_time_and_materials(env: &Env, escrow_id: u32) -> Option<TimeAndMaterials> {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage().instance().get(&EscrowKey::TimeAndMaterials(escrow_id))
+}
+
+/// Log hours worked as a new submitted time entry, returning its milestone index
+pub fn log_hours(env: &Env, escrow_id: u32, hours: u32, description: String, beneficiary: Address) -> Result<u32, Error> {
+    beneficiary.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.beneficiary.as_ref() != Some(&beneficiary) {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyBeneficiary as u32));
+    }
+
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let mut terms = get_time_and_materials(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::NotTimeAndMaterials as u32))?;
+
+    if hours == 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+    }
+
+    if escrow.milestone_count >= MAX_TIME_ENTRIES {
+        return Err(Error::from_contract_error(DeCentPayError::TooManyMilestones as u32));
+    }
+
+    // Entries awaiting approval already count against the cap
+    let amount = terms.hourly_rate * i128::from(hours);
+    if escrow.paid_amount + outstanding(env, escrow_id) + amount > escrow.total_amount {
+        return Err(Error::from_contract_error(DeCentPayError::ExceedsCap as u32));
+    }
+
+    let entry_index = escrow.milestone_count;
+    let entry = Milestone {
+        description,
+        amount,
+        status: MilestoneStatus::Submitted,
+        submitted_at: env.ledger().timestamp(),
+        approved_at: 0,
+        disputed_at: 0,
+        disputed_by: None,
+        dispute_reason: None,
+        rejection_reason: None,
+        completed_by_client: false,
+        manifest: Vec::new(env),
+        released: 0,
+        due_at: 0,
+    };
+    env.storage()
+        .instance()
+        .set(&DataKey::Milestone(escrow_id, entry_index), &entry);
+
+    terms.hours_logged += hours;
+    env.storage()
+        .instance()
+        .set(&EscrowKey::TimeAndMaterials(escrow_id), &terms);
+
+    escrow.milestone_count += 1;
+    escrow_core::save_escrow(env, escrow_id, &escrow);
+    Ok(entry_index)
+}
+
+/// Approve a batch of submitted time entries and pay them out
+pub fn approve_time_entries(env: &Env, escrow_id: u32, entry_indexes: Vec<u32>, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    if get_time_and_materials(env, escrow_id).is_none() {
+        return Err(Error::from_contract_error(DeCentPayError::NotTimeAndMaterials as u32));
+    }
+
+    for entry_index in entry_indexes.iter() {
+        let entry = work_lifecycle::get_milestone(env, escrow_id, entry_index)
+            .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+        if entry.status != MilestoneStatus::Submitted {
+            return Err(Error::from_contract_error(DeCentPayError::MilestoneNotSubmitted as u32));
+        }
+
+        // Each payout updates the escrow, so reload it for the next entry
+        let escrow = escrow_core::get_escrow(env, escrow_id)
+            .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+        work_lifecycle::approve_and_pay(env, escrow_id, entry_index, escrow, entry)?;
+    }
+    Ok(())
+}
+
+/// End a time and materials engagement, refunding the unbilled part of the cap to the depositor
+pub fn close_time_and_materials(env: &Env, escrow_id: u32, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    if get_time_and_materials(env, escrow_id).is_none() {
+        return Err(Error::from_contract_error(DeCentPayError::NotTimeAndMaterials as u32));
+    }
+
+    if outstanding(env, escrow_id) > 0 {
+        return Err(Error::from_contract_error(DeCentPayError::EntriesOutstanding as u32));
+    }
+
+    let unbilled = escrow.total_amount - escrow.paid_amount;
+    if unbilled > 0 {
+        idle_yield::unwind(env, escrow_id, &escrow)?;
+        escrow.total_amount -= unbilled;
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -unbilled)?;
+        refund_system::refund_to_depositor(env, escrow_id, &escrow, unbilled)?;
+    }
+    work_lifecycle::complete_if_paid(env, escrow_id, &mut escrow)?;
+    escrow_core::save_escrow(env, escrow_id, &escrow);
+    Ok(())
+}
+
+/// Sum of time entries logged but not yet settled
+fn outstanding(env: &Env, escrow_id: u32) -> i128 {
+    let mut total = 0;
+    for entry in work_lifecycle::get_milestones(env, escrow_id).iter() {
+        match entry.status {
+            MilestoneStatus::Approved | MilestoneStatus::Resolved | MilestoneStatus::Removed => {}
+            _ => total += entry.amount,
+        }
+    }
+    total
+}
@@ -0,0 +1,400 @@
+use crate::admin;
+use crate::cosign;
+use crate::dispute_resolution;
+use crate::escrow_core;
+use crate::escrow_management;
+use crate::marketplace;
+use crate::netting;
+use crate::ratings;
+use crate::referrals;
+use crate::refund_system;
+use crate::retainage;
+use crate::storage_types::{
+    AccountKey, Dashboard, DeCentPayError, DisputeSummary, JobCategory, EscrowAction, EscrowData, EscrowListing, EscrowStatus,
+    EscrowStatusFilter, EscrowSummary, ListingIndex, MilestoneRef, MilestoneStatus, NegotiationStatus, NettingCredit, TokenBalance, VestedBalance,
+};
+use crate::streaming;
+use crate::work_lifecycle;
+use soroban_sdk::{Address, Env, Error, Vec};
+
+const MAX_PAGE_SIZE: u32 = 50;
+
+/// Page through escrows from `start_id` upwards, optionally only those in one status
+pub fn list_escrows(env: &Env, start_id: u32, limit: u32, filter: EscrowStatusFilter) -> Vec<EscrowListing> {
+    match filter {
+        EscrowStatusFilter::All => {
+            let next_id = escrow_core::get_next_escrow_id(env);
+            let mut ids = Vec::new(env);
+            for escrow_id in start_id.max(1)..next_id {
+                if ids.len() >= limit.min(MAX_PAGE_SIZE) {
+                    break;
+                }
+                ids.push_back(escrow_id);
+            }
+            load_listings(env, &ids, 0, limit)
+        }
+        EscrowStatusFilter::Status(status) => {
+            let ids = escrow_core::get_index(env, &ListingIndex::Status(status), start_id, limit.min(MAX_PAGE_SIZE));
+            load_listings(env, &ids, 0, limit)
+        }
+    }
+}
+
+/// Page through open jobs still taking applications, from escrow `start_id` upwards
+pub fn list_open_jobs(env: &Env, start_id: u32, limit: u32) -> Vec<EscrowListing> {
+    let ids = escrow_core::get_index(env, &ListingIndex::OpenJobs, start_id, limit.min(MAX_PAGE_SIZE));
+    load_listings(env, &ids, 0, limit)
+}
+
+/// Page through jobs in a category, from escrow `start_id` upwards
+pub fn list_jobs_by_category(env: &Env, category: JobCategory, start_id: u32, limit: u32) -> Vec<EscrowListing> {
+    let ids = escrow_core::get_index(env, &ListingIndex::Category(category), start_id, limit.min(MAX_PAGE_SIZE));
+    load_listings(env, &ids, 0, limit)
+}
+
+/// Page through the escrows a user created, from escrow `start_id` upwards
+pub fn get_escrows_as_depositor(env: &Env, user: Address, start_id: u32, limit: u32, filter: EscrowStatusFilter) -> Vec<EscrowListing> {
+    let index = escrow_core::get_role_escrows(env, &AccountKey::DepositorEscrows(user));
+    load_filtered_listings(env, &index, start_id, limit, &filter)
+}
+
+/// Page through the escrows a user was hired on, from escrow `start_id` upwards
+pub fn get_escrows_as_beneficiary(env: &Env, user: Address, start_id: u32, limit: u32, filter: EscrowStatusFilter) -> Vec<EscrowListing> {
+    let index = escrow_core::get_role_escrows(env, &AccountKey::BeneficiaryEscrows(user));
+    load_filtered_listings(env, &index, start_id, limit, &filter)
+}
+
+fn load_filtered_listings(env: &Env, ids: &Vec<u32>, start_id: u32, limit: u32, filter: &EscrowStatusFilter) -> Vec<EscrowListing> {
+    let mut listings = Vec::new(env);
+    for escrow_id in ids.iter().filter(|escrow_id| *escrow_id >= start_id) {
+        if listings.len() >= limit.min(MAX_PAGE_SIZE) {
+            break;
+        }
+        let Some(escrow) = escrow_core::get_escrow(env, escrow_id) else {
+            continue;
+        };
+        if let EscrowStatusFilter::Status(status) = filter {
+            if escrow.status != *status {
+                continue;
+            }
+        }
+        listings.push_back(EscrowListing { escrow_id, escrow });
+    }
+    listings
+}
+
+fn load_listings(env: &Env, ids: &Vec<u32>, start_id: u32, limit: u32) -> Vec<EscrowListing> {
+    let mut listings = Vec::new(env);
+    for escrow_id in ids.iter().filter(|escrow_id| *escrow_id >= start_id) {
+        if listings.len() >= limit.min(MAX_PAGE_SIZE) {
+            break;
+        }
+        if let Some(escrow) = escrow_core::get_escrow(env, escrow_id) {
+            listings.push_back(EscrowListing { escrow_id, escrow });
+        }
+    }
+    listings
+}
+
+/// Get an escrow with its milestones, application count, open disputes and remaining balance
+pub fn get_escrow_summary(env: &Env, escrow_id: u32) -> Result<EscrowSummary, Error> {
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let milestones = work_lifecycle::get_milestones(env, escrow_id);
+
+    let mut disputes = Vec::new(env);
+    for (index, milestone) in milestones.iter().enumerate() {
+        if milestone.status != MilestoneStatus::Disputed {
+            continue;
+        }
+        let milestone_index = u32::try_from(index).unwrap_or(u32::MAX);
+        disputes.push_back(DisputeSummary {
+            milestone_index,
+            disputed_by: milestone.disputed_by,
+            reason: milestone.dispute_reason,
+            disputed_at: milestone.disputed_at,
+            votes: dispute_resolution::get_dispute_votes(env, escrow_id, milestone_index),
+            appointed_arbiter: dispute_resolution::get_appointed_arbiter(env, escrow_id, milestone_index),
+        });
+    }
+
+    Ok(EscrowSummary {
+        escrow_id,
+        remaining_balance: escrow_management::get_funded_amount(env, escrow_id, &escrow) - escrow.paid_amount,
+        application_count: marketplace::get_application_count(env, escrow_id),
+        escrow,
+        milestones,
+        disputes,
+    })
+}
+
+/// Get the actions `caller` can currently take on an escrow.
+/// Mirrors the checks performed by each entrypoint so frontends don't have to.
+pub fn get_allowed_actions(env: &Env, escrow_id: u32, caller: Address) -> Vec<EscrowAction> {
+    let mut actions = Vec::new(env);
+    let Some(escrow) = escrow_core::get_escrow(env, escrow_id) else {
+        return actions;
+    };
+
+    // Refunds stay open while the contract is paused; everything else needs it running
+    if escrow_core::require_not_frozen(env, escrow_id).is_err() {
+        return actions;
+    }
+    let mutable = escrow_core::require_mutable_escrow(env, escrow_id).is_ok();
+
+    let is_depositor = escrow.depositor == caller;
+    let is_beneficiary = escrow.beneficiary == Some(caller.clone());
+
+    // Refunds
+    if refund_system::check_refund(env, escrow_id, &escrow, &caller).is_ok() {
+        actions.push_back(EscrowAction::RefundEscrow);
+    }
+    if refund_system::check_emergency_refund(env, escrow_id, &escrow, &caller).is_ok() {
+        actions.push_back(EscrowAction::EmergencyRefund);
+    }
+    if refund_system::check_return_funds(env, escrow_id, &escrow, &caller).is_ok() {
+        actions.push_back(EscrowAction::ReturnFunds);
+    }
+    if marketplace::check_listed_job(&escrow, &caller).is_ok() {
+        actions.push_back(EscrowAction::CancelOpenJob);
+    }
+
+    if !mutable {
+        return actions;
+    }
+
+    // Open job
+    if marketplace::check_listed_job(&escrow, &caller).is_ok() && marketplace::has_pending_application(env, escrow_id) {
+        actions.push_back(EscrowAction::AcceptFreelancer);
+    }
+    if marketplace::check_can_apply(env, escrow_id, &escrow, &caller).is_ok() {
+        actions.push_back(EscrowAction::ApplyToJob);
+    }
+    if marketplace::check_invitation(env, escrow_id, &escrow, &caller).is_ok() {
+        actions.push_back(EscrowAction::AcceptInvitation);
+    }
+    if marketplace::check_terms(env, escrow_id, &escrow, &caller).is_ok() {
+        actions.push_back(EscrowAction::AcceptTerms);
+    }
+    if marketplace::check_expirable(env, escrow_id, &escrow).is_ok() {
+        actions.push_back(EscrowAction::ExpireOpenJob);
+    }
+
+    // Installments
+    if is_depositor && escrow.status == EscrowStatus::Funding {
+        actions.push_back(EscrowAction::FundEscrow);
+    }
+
+    // Work start
+    if is_beneficiary && escrow.status == EscrowStatus::Pending && !escrow.work_started {
+        actions.push_back(EscrowAction::StartWork);
+    }
+
+    // Milestones
+    for (index, milestone) in work_lifecycle::get_milestones(env, escrow_id).iter().enumerate() {
+        let index = u32::try_from(index).unwrap_or(u32::MAX);
+        if escrow.status == EscrowStatus::InProgress {
+            match milestone.status {
+                MilestoneStatus::NotStarted if is_beneficiary => {
+                    actions.push_back(EscrowAction::SubmitMilestone(index));
+                }
+                MilestoneStatus::Rejected | MilestoneStatus::PartiallyApproved if is_beneficiary => {
+                    actions.push_back(EscrowAction::ResubmitMilestone(index));
+                }
+                MilestoneStatus::NotStarted | MilestoneStatus::Rejected | MilestoneStatus::PartiallyApproved if is_depositor => {
+                    actions.push_back(EscrowAction::MarkMilestoneComplete(index));
+                }
+                MilestoneStatus::Submitted if is_depositor => {
+                    actions.push_back(EscrowAction::ApproveMilestone(index));
+                    actions.push_back(EscrowAction::RejectMilestone(index));
+                }
+                _ => {}
+            }
+        }
+        if work_lifecycle::check_partial_approval(env, escrow_id, index, &escrow, &caller).is_ok() {
+            actions.push_back(EscrowAction::ApproveMilestonePartial(index));
+        }
+        if work_lifecycle::check_dispute(env, escrow_id, index, &escrow, &caller).is_ok() {
+            actions.push_back(EscrowAction::DisputeMilestone(index));
+        }
+        if streaming::check_claimable(env, escrow_id, index, &escrow, &caller).is_ok() {
+            actions.push_back(EscrowAction::ClaimVested(index));
+        }
+        if dispute_resolution::check_can_rule(env, escrow_id, index, &escrow, &caller).is_ok() {
+            actions.push_back(EscrowAction::ResolveDispute(index));
+        }
+        if cosign::check_cosign(env, escrow_id, index, &escrow, &caller).is_ok() {
+            actions.push_back(EscrowAction::CosignMilestone(index));
+        }
+    }
+
+    if is_depositor {
+        // Deadline extension
+        if escrow.status == EscrowStatus::Pending || escrow.status == EscrowStatus::InProgress {
+            actions.push_back(EscrowAction::ExtendDeadline);
+        }
+
+        // Rating
+        match ratings::get_rating(env, escrow_id) {
+            None if escrow.status == EscrowStatus::Released && escrow.beneficiary.is_some() => {
+                actions.push_back(EscrowAction::SubmitRating);
+            }
+            Some(rating) if ratings::is_rating_editable(env, &rating) => {
+                actions.push_back(EscrowAction::EditRating);
+            }
+            _ => {}
+        }
+    }
+
+    // Released escrows
+    if work_lifecycle::check_bonus(env, &escrow, &caller).is_ok() {
+        actions.push_back(EscrowAction::SendBonus);
+    }
+    if retainage::check_release_retention(env, escrow_id, &escrow, &caller).is_ok() {
+        actions.push_back(EscrowAction::ReleaseRetention);
+    }
+
+    if is_beneficiary && ratings::get_rating(env, escrow_id).is_some_and(|rating| rating.response.is_none()) {
+        actions.push_back(EscrowAction::RespondToRating);
+    }
+
+    actions
+}
+
+/// Get the milestones of an in-progress escrow that wait on `user`:
+/// submissions to review as depositor, work to (re)submit as beneficiary
+pub fn milestones_awaiting(env: &Env, escrow_id: u32, escrow: &EscrowData, user: &Address) -> Vec<MilestoneRef> {
+    let mut awaiting = Vec::new(env);
+    if escrow.status != EscrowStatus::InProgress {
+        return awaiting;
+    }
+
+    let is_depositor = escrow.depositor == *user;
+    let is_beneficiary = escrow.beneficiary.as_ref() == Some(user);
+    for (index, milestone) in work_lifecycle::get_milestones(env, escrow_id).iter().enumerate() {
+        let waits_on_user = match milestone.status {
+            MilestoneStatus::Submitted => is_depositor,
+            MilestoneStatus::NotStarted | MilestoneStatus::Rejected | MilestoneStatus::PartiallyApproved => is_beneficiary,
+            _ => false,
+        };
+        if waits_on_user {
+            awaiting.push_back(MilestoneRef {
+                escrow_id,
+                milestone_index: u32::try_from(index).unwrap_or(u32::MAX),
+                status: milestone.status,
+            });
+        }
+    }
+    awaiting
+}
+
+/// Get every milestone across a user's escrows that is waiting on them
+pub fn get_pending_approvals(env: &Env, user: Address) -> Vec<MilestoneRef> {
+    let mut pending = Vec::new(env);
+    for escrow_id in escrow_core::get_user_escrows(env, user.clone()).iter() {
+        if let Some(escrow) = escrow_core::get_escrow(env, escrow_id) {
+            pending.append(&milestones_awaiting(env, escrow_id, &escrow, &user));
+        }
+    }
+    pending
+}
+
+/// Get everything a user needs to act on across their escrows, the offers waiting on them and
+/// what the contract holds for them to claim
+pub fn get_dashboard(env: &Env, user: Address) -> Dashboard {
+    let mut dashboard = Dashboard {
+        active_escrows: Vec::new(env),
+        awaiting_action: Vec::new(env),
+        open_disputes: Vec::new(env),
+        jobs_awaiting_hire: Vec::new(env),
+        pending_offers: Vec::new(env),
+        invitations: Vec::new(env),
+        unclaimed_vested: Vec::new(env),
+        netting_credits: Vec::new(env),
+        referral_earnings: Vec::new(env),
+    };
+
+    for escrow_id in escrow_core::get_user_escrows(env, user.clone()).iter() {
+        let Some(escrow) = escrow_core::get_escrow(env, escrow_id) else {
+            continue;
+        };
+
+        match escrow.status {
+            EscrowStatus::Pending | EscrowStatus::InProgress => dashboard.active_escrows.push_back(escrow_id),
+            EscrowStatus::Disputed => {
+                dashboard.active_escrows.push_back(escrow_id);
+                dashboard.open_disputes.push_back(escrow_id);
+            }
+            _ => {}
+        }
+
+        dashboard
+            .awaiting_action
+            .append(&milestones_awaiting(env, escrow_id, &escrow, &user));
+
+        if escrow.is_open_job
+            && escrow.status == EscrowStatus::Pending
+            && escrow.depositor == user
+            && !marketplace::get_applications(env, escrow_id).is_empty()
+        {
+            dashboard.jobs_awaiting_hire.push_back(escrow_id);
+        }
+
+        if escrow.beneficiary.as_ref() == Some(&user) {
+            for milestone_index in 0..escrow.milestone_count {
+                if let Some(stream) = streaming::get_vesting_stream(env, escrow_id, milestone_index) {
+                    let amount = streaming::vested_amount(env, &stream) - stream.claimed;
+                    if amount > 0 {
+                        dashboard.unclaimed_vested.push_back(VestedBalance { escrow_id, milestone_index, amount });
+                    }
+                }
+            }
+        }
+
+        // Netting credits are held per counterparty and token
+        let counterparty = if escrow.depositor == user {
+            escrow.beneficiary.clone()
+        } else {
+            Some(escrow.depositor.clone())
+        };
+        if let Some(counterparty) = counterparty {
+            let listed = dashboard
+                .netting_credits
+                .iter()
+                .any(|credit| credit.counterparty == counterparty && credit.token == escrow.token);
+            let amount = netting::get_netting_balance(env, user.clone(), counterparty.clone(), escrow.token.clone());
+            if !listed && amount > 0 {
+                dashboard.netting_credits.push_back(NettingCredit { counterparty, token: escrow.token.clone(), amount });
+            }
+        }
+    }
+
+    // Offers are only open while a job is still listed
+    let now = env.ledger().timestamp();
+    for escrow_id in escrow_core::get_index(env, &ListingIndex::OpenJobs, 0, u32::MAX).iter() {
+        if marketplace::get_negotiation(env, escrow_id, user.clone())
+            .is_some_and(|negotiation| negotiation.status == NegotiationStatus::Proposed)
+        {
+            dashboard.pending_offers.push_back(escrow_id);
+        }
+        if marketplace::get_invitation(env, escrow_id, user.clone())
+            .is_some_and(|invitation| !invitation.accepted && now < invitation.expires_at)
+        {
+            dashboard.invitations.push_back(escrow_id);
+        }
+    }
+
+    // Referral fees accrue in native XLM or any whitelisted token
+    let mut add_earnings = |token: Option<Address>| {
+        let amount = referrals::get_referral_earnings(env, user.clone(), token.clone());
+        if amount > 0 {
+            dashboard.referral_earnings.push_back(TokenBalance { token, amount });
+        }
+    };
+    add_earnings(None);
+    for token in admin::get_whitelisted_token_list(env).iter() {
+        add_earnings(Some(token));
+    }
+
+    dashboard
+}
@@ -1,19 +1,45 @@
+use crate::admin;
+use crate::achievements;
+use crate::bonds;
+use crate::categories;
+use crate::client_spend;
+use crate::compliance;
+use crate::cosign;
+use crate::credentials;
+use crate::dispute_resolution;
 use crate::escrow_core;
+use crate::escrow_management;
+use crate::finality;
+use crate::idle_yield;
+use crate::leaderboard;
+use crate::marketplace;
+use crate::milestone_log;
+use crate::netting;
+use crate::profiles;
+use crate::ratings;
+use crate::refund_system;
+use crate::reputation;
+use crate::retainage;
+use crate::retainer;
+use crate::stakes;
+use crate::streaming;
+use crate::team;
 use crate::storage_types::{
-    DataKey, EscrowStatus, MilestoneStatus, Milestone, DeCentPayError, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+    ActivityAction, DataKey, EscrowKey, Deliverable, EscrowData, EscrowProgress, EscrowStatus, JobCategory, MilestoneArbiters, MilestoneStatus, Milestone, DeCentPayError, INSTANCE_BUMP_AMOUNT,
+    INSTANCE_LIFETIME_THRESHOLD,
 };
-use soroban_sdk::{token, Address, Env, String, Vec, Error};
+use soroban_sdk::{Address, BytesN, Env, String, Vec, Error};
 
 #[allow(dead_code)]
 const DISPUTE_PERIOD: u32 = 604800; // 7 days in seconds
 const REPUTATION_PER_MILESTONE: u32 = 10;
 const REPUTATION_PER_ESCROW: u32 = 25;
-const MIN_REP_ELIGIBLE_ESCROW_VALUE: i128 = 10000000000000000; // 0.01 in stroops
+const MAX_MANIFEST_ENTRIES: u32 = 20;
 
 pub fn start_work(env: &Env, escrow_id: u32, beneficiary: Address) -> Result<(), Error> {
     beneficiary.require_auth();
 
-    escrow_core::require_valid_escrow(env, escrow_id)?;
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
     let mut escrow = escrow_core::get_escrow(env, escrow_id)
         .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
 
@@ -32,26 +58,17 @@ pub fn start_work(env: &Env, escrow_id: u32, beneficiary: Address) -> Result<(),
     escrow.work_started = true;
     escrow.status = EscrowStatus::InProgress;
 
-    // Update platform fees
-    if escrow.platform_fee > 0 {
-        let token_key = escrow.token.clone().unwrap_or(env.current_contract_address());
-        let current_fees: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::TotalFeesByToken(token_key.clone()))
-            .unwrap_or(0);
-        env.storage()
-            .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        env.storage()
-            .instance()
-            .set(
-                &DataKey::TotalFeesByToken(token_key),
-                &(current_fees + escrow.platform_fee),
-            );
-    }
+    // Lock the performance bond if the depositor requires one
+    bonds::lock_performance_bond(env, escrow_id, &escrow, &beneficiary)?;
+    // Starting in time earns the application stake back
+    stakes::return_stake(env, escrow_id, &escrow, &beneficiary)?;
+    retainer::start(env, escrow_id);
+
+    // Work starting is what earns the platform its fee
+    escrow_core::collect_platform_fee(env, escrow_id, &escrow)?;
 
     escrow_core::save_escrow(env, escrow_id, &escrow);
+    milestone_log::record_activity(env, escrow_id, &beneficiary, ActivityAction::WorkStarted, None);
     Ok(())
 }
 
@@ -61,10 +78,11 @@ pub fn submit_milestone(
     milestone_index: u32,
     beneficiary: Address,
     description: String,
+    manifest: Vec<Deliverable>,
 ) -> Result<(), Error> {
     beneficiary.require_auth();
 
-    escrow_core::require_valid_escrow(env, escrow_id)?;
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
     let escrow = escrow_core::get_escrow(env, escrow_id)
         .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
 
@@ -76,6 +94,10 @@ pub fn submit_milestone(
         return Err(Error::from_contract_error(DeCentPayError::InvalidEscrowStatus as u32));
     }
 
+    if manifest.len() > MAX_MANIFEST_ENTRIES {
+        return Err(Error::from_contract_error(DeCentPayError::TooManyDeliverables as u32));
+    }
+
     if milestone_index >= escrow.milestone_count {
         return Err(Error::from_contract_error(DeCentPayError::InvalidMilestone as u32));
     }
@@ -92,8 +114,9 @@ pub fn submit_milestone(
     }
 
     milestone.status = MilestoneStatus::Submitted;
-    milestone.submitted_at = env.ledger().sequence();
+    milestone.submitted_at = env.ledger().timestamp();
     milestone.description = description;
+    milestone.manifest = manifest;
 
     // Save milestone
     env.storage()
@@ -102,15 +125,28 @@ pub fn submit_milestone(
     env.storage()
         .instance()
         .set(&DataKey::Milestone(escrow_id, milestone_index), &milestone);
-    
+    milestone_log::record_activity(env, escrow_id, &beneficiary, ActivityAction::MilestoneSubmitted(milestone_index), None);
     Ok(())
 }
 
+/// Build a submission manifest from bare content hashes (IPFS CIDs or file digests)
+pub fn hash_manifest(env: &Env, hashes: Option<Vec<BytesN<32>>>) -> Vec<Deliverable> {
+    let mut manifest = Vec::new(env);
+    for hash in hashes.unwrap_or_else(|| Vec::new(env)).iter() {
+        manifest.push_back(Deliverable {
+            label: String::from_str(env, ""),
+            hash,
+            size: 0,
+        });
+    }
+    manifest
+}
+
 pub fn approve_milestone(env: &Env, escrow_id: u32, milestone_index: u32, depositor: Address) -> Result<(), Error> {
     depositor.require_auth();
 
-    escrow_core::require_valid_escrow(env, escrow_id)?;
-    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
         .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
 
     if escrow.depositor != depositor {
@@ -126,7 +162,7 @@ pub fn approve_milestone(env: &Env, escrow_id: u32, milestone_index: u32, deposi
     }
 
     // Get milestone
-    let mut milestone: crate::storage_types::Milestone = env
+    let milestone: crate::storage_types::Milestone = env
         .storage()
         .instance()
         .get::<DataKey, crate::storage_types::Milestone>(&DataKey::Milestone(escrow_id, milestone_index))
@@ -136,96 +172,385 @@ pub fn approve_milestone(env: &Env, escrow_id: u32, milestone_index: u32, deposi
         return Err(Error::from_contract_error(DeCentPayError::MilestoneNotSubmitted as u32));
     }
 
-    let amount = milestone.amount;
-    milestone.status = MilestoneStatus::Approved;
-    milestone.approved_at = env.ledger().sequence();
+    approve_and_pay(env, escrow_id, milestone_index, escrow, milestone)?;
+    milestone_log::record_activity(env, escrow_id, &depositor, ActivityAction::MilestoneApproved(milestone_index), None);
+    Ok(())
+}
 
-    // Get beneficiary address before moving
-    let beneficiary_addr = escrow.beneficiary.clone().unwrap();
-    
-    // Update escrow
-    escrow.paid_amount += amount;
-    
-    // Update escrowed amount
-    let token_key = escrow.token.as_ref().map(|t| t.clone()).unwrap_or_else(|| env.current_contract_address());
-    let current_escrowed: i128 = env
-        .storage()
+/// Approve and pay a milestone delivered off-chain that was never submitted
+pub fn mark_milestone_complete(env: &Env, escrow_id: u32, milestone_index: u32, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let milestone = get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+
+    if milestone.status != MilestoneStatus::NotStarted
+        && milestone.status != MilestoneStatus::Rejected
+        && milestone.status != MilestoneStatus::PartiallyApproved
+    {
+        return Err(Error::from_contract_error(DeCentPayError::MilestoneAlreadyProcessed as u32));
+    }
+
+    approve_and_pay(env, escrow_id, milestone_index, escrow, milestone)?;
+    milestone_log::record_activity(env, escrow_id, &depositor, ActivityAction::MilestoneApproved(milestone_index), None);
+    Ok(())
+}
+
+/// Pay out part of a submitted milestone and send the remainder back for rework
+pub fn approve_milestone_partial(
+    env: &Env,
+    escrow_id: u32,
+    milestone_index: u32,
+    approved_amount: i128,
+    depositor: Address,
+) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let mut milestone = check_partial_approval(env, escrow_id, milestone_index, &escrow, &depositor)?;
+
+    // Approving the whole remainder is a regular approval
+    if approved_amount <= 0 || approved_amount >= milestone.amount {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    milestone.status = MilestoneStatus::PartiallyApproved;
+    milestone.amount -= approved_amount;
+    milestone.released += approved_amount;
+    release_payment(env, escrow_id, &mut escrow, approved_amount, Some(milestone_index))?;
+
+    env.storage()
         .instance()
-        .get(&DataKey::EscrowedAmount(token_key.clone()))
-        .unwrap_or(0);
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::Milestone(escrow_id, milestone_index), &milestone);
+    escrow_core::save_escrow(env, escrow_id, &escrow);
+    milestone_log::record_activity(env, escrow_id, &depositor, ActivityAction::MilestonePartiallyApproved(milestone_index), None);
+    Ok(())
+}
+
+/// Check the depositor may pay out part of a submitted milestone, returning the milestone
+pub fn check_partial_approval(
+    env: &Env,
+    escrow_id: u32,
+    milestone_index: u32,
+    escrow: &EscrowData,
+    depositor: &Address,
+) -> Result<Milestone, Error> {
+    if escrow.depositor != *depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let milestone = get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+
+    if milestone.status != MilestoneStatus::Submitted {
+        return Err(Error::from_contract_error(DeCentPayError::MilestoneNotSubmitted as u32));
+    }
+
+    // The threshold applies to the milestone's full value, so partial approvals cannot split it up
+    if cosign::requires_cosign(env, escrow_id, milestone.amount + milestone.released) {
+        return Err(Error::from_contract_error(DeCentPayError::CosignRequired as u32));
+    }
+    Ok(milestone)
+}
+
+/// Approve a milestone and pay it out, or hold it for an arbiter co-signature when the escrow
+/// requires one at this amount
+pub fn approve_and_pay(
+    env: &Env,
+    escrow_id: u32,
+    milestone_index: u32,
+    escrow: EscrowData,
+    milestone: Milestone,
+) -> Result<(), Error> {
+    if cosign::requires_cosign(env, escrow_id, milestone.amount + milestone.released) {
+        cosign::request(env, escrow_id, milestone_index);
+        return Ok(());
+    }
+    pay_approved(env, escrow_id, milestone_index, escrow, milestone)
+}
+
+/// Mark a milestone approved and pay it out. Approvals of a submission count towards the
+/// depositor's on-time record; anything else is flagged as completed by the client.
+pub fn pay_approved(
+    env: &Env,
+    escrow_id: u32,
+    milestone_index: u32,
+    mut escrow: EscrowData,
+    mut milestone: Milestone,
+) -> Result<(), Error> {
+    let was_submitted = milestone.status == MilestoneStatus::Submitted;
+    milestone.status = MilestoneStatus::Approved;
+    milestone.approved_at = env.ledger().timestamp();
+
+    if was_submitted {
+        let on_time = milestone.approved_at - milestone.submitted_at <= ratings::CLIENT_REVIEW_WINDOW;
+        ratings::record_client_outcome(env, &escrow.depositor, |record| {
+            if on_time {
+                record.on_time_approvals += 1;
+            } else {
+                record.late_approvals += 1;
+            }
+        });
+    } else {
+        milestone.completed_by_client = true;
+    }
+
+    release_payment(env, escrow_id, &mut escrow, milestone.amount, Some(milestone_index))?;
+    settle_revision_fee(env, escrow_id, milestone_index, &escrow, true)?;
+
+    // Save milestone and escrow
     env.storage()
         .instance()
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
     env.storage()
         .instance()
-        .set(
-            &DataKey::EscrowedAmount(token_key),
-            &(current_escrowed - amount),
-        );
-
-    // Transfer funds to beneficiary
-    if let Some(token_addr) = &escrow.token {
-        let token_client = token::Client::new(env, &token_addr);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &beneficiary_addr,
-            &amount,
-        );
+        .remove(&EscrowKey::ApprovalVotes(escrow_id, milestone_index));
+    env.storage()
+        .instance()
+        .set(&DataKey::Milestone(escrow_id, milestone_index), &milestone);
+    escrow_core::save_escrow(env, escrow_id, &escrow);
+    Ok(())
+}
+
+/// Pay `amount` of an escrow to its beneficiary and complete the escrow once fully paid.
+/// The payout of an approved milestone streams instead when the escrow is in streaming mode.
+pub fn release_payment(
+    env: &Env,
+    escrow_id: u32,
+    escrow: &mut EscrowData,
+    amount: i128,
+    approved_milestone: Option<u32>,
+) -> Result<(), Error> {
+    let beneficiary_addr = escrow
+        .beneficiary
+        .clone()
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotActive as u32))?;
+    let payout_addr = profiles::payout_address(env, &beneficiary_addr);
+    compliance::require_not_flagged(env, &beneficiary_addr)?;
+    compliance::require_not_flagged(env, &payout_addr)?;
+    idle_yield::unwind(env, escrow_id, escrow)?;
+    
+    // Update escrow; any retainage stays in the contract until the warranty ends
+    escrow.paid_amount += amount;
+    let payout = amount - retainage::withhold(env, escrow_id, amount);
+    credentials::on_payment_earned(env, &beneficiary_addr, escrow.token.as_ref(), amount, escrow_id);
+    client_spend::record_release(env, &escrow.depositor, escrow.token.as_ref(), amount);
+
+    // Streamed payouts stay escrowed until the beneficiary claims them
+    if let Some(milestone_index) = approved_milestone {
+        if streaming::open_stream(env, escrow_id, milestone_index, payout) {
+            credit_milestone_reputation(env, escrow_id, escrow, &beneficiary_addr);
+            return complete_if_paid(env, escrow_id, escrow);
+        }
+    }
+    
+    // Update escrowed amount
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -payout)?;
+
+    pay_beneficiary(env, escrow_id, escrow, &beneficiary_addr, payout)?;
+    credit_milestone_reputation(env, escrow_id, escrow, &beneficiary_addr);
+    complete_if_paid(env, escrow_id, escrow)
+}
+
+/// Transfer funds to the beneficiary's payout address (or split across the team), or book them for netting
+pub fn pay_beneficiary(
+    env: &Env,
+    escrow_id: u32,
+    escrow: &EscrowData,
+    beneficiary_addr: &Address,
+    amount: i128,
+) -> Result<(), Error> {
+    if let Some(splits) = team::get_team_splits(env, escrow_id) {
+        team::pay_team(env, escrow, &splits, amount)?;
+    } else if netting::is_netting_active(env, &escrow.depositor, beneficiary_addr) {
+        netting::credit(env, escrow.token.as_ref(), beneficiary_addr, &escrow.depositor, amount)?;
     } else {
-        // Transfer native XLM using Stellar Asset Contract (SAC)
-        let native_token_str = String::from_str(env, "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC");
-        let native_token_address = Address::from_string(&native_token_str);
-        let native_token_client = token::Client::new(env, &native_token_address);
-        native_token_client.transfer(
-            &env.current_contract_address(),
-            &beneficiary_addr,
-            &amount,
-        );
-    }
-
-    // Update reputation
-    if escrow.total_amount >= MIN_REP_ELIGIBLE_ESCROW_VALUE {
-        update_reputation(env, beneficiary_addr.clone(), REPUTATION_PER_MILESTONE);
-    }
-
-    // Check if escrow is complete
-    if escrow.paid_amount == escrow.total_amount {
-        escrow.status = EscrowStatus::Released;
-        if escrow.total_amount >= MIN_REP_ELIGIBLE_ESCROW_VALUE {
-            update_reputation(env, beneficiary_addr.clone(), REPUTATION_PER_ESCROW);
-            update_reputation(env, escrow.depositor.clone(), REPUTATION_PER_ESCROW);
-            
-            // Update completed escrows count
-            let beneficiary_completed: u32 = env
-                .storage()
-                .instance()
-                .get(&DataKey::CompletedEscrows(beneficiary_addr.clone()))
-                .unwrap_or(0);
-            env.storage()
-                .instance()
-                .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-            env.storage()
-                .instance()
-                .set(
-                    &DataKey::CompletedEscrows(beneficiary_addr.clone()),
-                    &(beneficiary_completed + 1),
-                );
-            
-            let depositor_completed: u32 = env
+        let payout_addr = profiles::payout_address(env, beneficiary_addr);
+        escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &payout_addr, amount);
+    }
+    Ok(())
+}
+
+fn credit_milestone_reputation(env: &Env, escrow_id: u32, escrow: &EscrowData, beneficiary_addr: &Address) {
+    let category = categories::get_job_category(env, escrow_id);
+    if is_reputation_eligible(env, escrow) {
+        for member in team::members(env, escrow_id, beneficiary_addr).iter() {
+            update_reputation(env, escrow_id, category, member, REPUTATION_PER_MILESTONE);
+        }
+    }
+}
+
+/// Check the depositor may tip a released escrow's beneficiary, returning the beneficiary
+pub fn check_bonus(env: &Env, escrow: &EscrowData, depositor: &Address) -> Result<Address, Error> {
+    if escrow.depositor != *depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::Released {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidEscrowStatus as u32));
+    }
+
+    let beneficiary = escrow
+        .beneficiary
+        .clone()
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidEscrowStatus as u32))?;
+    compliance::require_not_flagged(env, &beneficiary)?;
+    compliance::require_not_flagged(env, &profiles::payout_address(env, &beneficiary))?;
+    Ok(beneficiary)
+}
+
+/// Tip the beneficiary (or team) of a released escrow from the depositor, less any tip fee
+pub fn send_bonus(env: &Env, escrow_id: u32, amount: i128, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let beneficiary_addr = check_bonus(env, &escrow, &depositor)?;
+
+    if amount <= 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    let tip_fee = amount * i128::from(admin::get_tip_fee_bp(env)) / 10000;
+    if tip_fee > 0 {
+        let token_key = escrow.token.clone().unwrap_or_else(|| env.current_contract_address());
+        admin::accrue_fees(env, token_key, tip_fee);
+    }
+    // The tip passes through the contract so team splits and netting apply as for milestone payouts
+    escrow_core::transfer(env, escrow.token.as_ref(), &depositor, &env.current_contract_address(), amount);
+    pay_beneficiary(env, escrow_id, &escrow, &beneficiary_addr, amount - tip_fee)?;
+
+    escrow.bonus_paid += amount;
+    escrow_core::save_escrow(env, escrow_id, &escrow);
+    milestone_log::record_activity(env, escrow_id, &depositor, ActivityAction::BonusSent, None);
+    Ok(())
+}
+
+/// Mark an escrow Released and credit both parties once everything owed has been paid
+pub fn complete_if_paid(env: &Env, escrow_id: u32, escrow: &mut EscrowData) -> Result<(), Error> {
+    if escrow.paid_amount != escrow.total_amount {
+        return Ok(());
+    }
+    let beneficiary = escrow
+        .beneficiary
+        .clone()
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotActive as u32))?;
+    let beneficiary_addr = &beneficiary;
+    let members = team::members(env, escrow_id, beneficiary_addr);
+    let category = categories::get_job_category(env, escrow_id);
+    escrow.status = EscrowStatus::Released;
+    finality::record_closed(env, escrow_id);
+    retainage::start_warranty(env, escrow_id);
+    dispute_resolution::refund_arbitration_fee(env, escrow_id, escrow)?;
+    ratings::record_client_outcome(env, &escrow.depositor, |record| record.completed_hires += 1);
+    for member in members.iter() {
+        achievements::on_escrow_completed(env, &member, escrow_id);
+    }
+    marketplace::reward_reputation_stake(env, escrow_id, beneficiary_addr);
+    bonds::return_performance_bond(env, escrow_id, escrow)?;
+    if is_reputation_eligible(env, escrow) {
+        // Category standing reflects work delivered, so the depositor earns only general reputation
+        update_reputation(env, escrow_id, None, escrow.depositor.clone(), REPUTATION_PER_ESCROW);
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        // Update completed escrows count for every team member
+        for member in members.iter() {
+            update_reputation(env, escrow_id, category, member.clone(), REPUTATION_PER_ESCROW);
+            let member_completed: u32 = env
                 .storage()
                 .instance()
-                .get(&DataKey::CompletedEscrows(escrow.depositor.clone()))
+                .get(&DataKey::CompletedEscrows(member.clone()))
                 .unwrap_or(0);
             env.storage()
                 .instance()
-                .set(
-                    &DataKey::CompletedEscrows(escrow.depositor.clone()),
-                    &(depositor_completed + 1),
-                );
+                .set(&DataKey::CompletedEscrows(member), &(member_completed + 1));
+        }
+        
+        let depositor_completed: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::CompletedEscrows(escrow.depositor.clone()))
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(
+                &DataKey::CompletedEscrows(escrow.depositor.clone()),
+                &(depositor_completed + 1),
+            );
+    }
+    Ok(())
+}
+
+/// Settle a disputed milestone, paying `beneficiary_amount` to the beneficiary and refunding the rest to the depositor
+pub fn resolve_disputed_milestone(
+    env: &Env,
+    escrow_id: u32,
+    milestone_index: u32,
+    beneficiary_amount: i128,
+) -> Result<(), Error> {
+    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    if escrow.status != EscrowStatus::Disputed {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let mut milestone = get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    if milestone.status != MilestoneStatus::Disputed {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidMilestone as u32));
+    }
+    if beneficiary_amount < 0 || beneficiary_amount > milestone.amount {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    milestone.status = MilestoneStatus::Resolved;
+    escrow.status = EscrowStatus::InProgress;
+    clear_dispute(env, escrow_id, milestone_index);
+
+    // Milestones approved before the dispute have already been paid out, short of any unclaimed stream
+    if milestone.approved_at != 0 {
+        streaming::settle_disputed(env, escrow_id, milestone_index, &mut escrow, beneficiary_amount)?;
+    } else {
+        let refund = milestone.amount - beneficiary_amount;
+        if refund > 0 {
+            idle_yield::unwind(env, escrow_id, &escrow)?;
+            escrow.total_amount -= refund;
+            escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -refund)?;
+            refund_system::refund_to_depositor(env, escrow_id, &escrow, refund)?;
+        }
+        settle_revision_fee(env, escrow_id, milestone_index, &escrow, beneficiary_amount > 0)?;
+        if beneficiary_amount > 0 {
+            milestone.approved_at = env.ledger().timestamp();
+            release_payment(env, escrow_id, &mut escrow, beneficiary_amount, None)?;
+        } else {
+            complete_if_paid(env, escrow_id, &mut escrow)?;
         }
     }
 
-    // Save milestone and escrow
     env.storage()
         .instance()
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
@@ -233,7 +558,164 @@ pub fn approve_milestone(env: &Env, escrow_id: u32, milestone_index: u32, deposi
         .instance()
         .set(&DataKey::Milestone(escrow_id, milestone_index), &milestone);
     escrow_core::save_escrow(env, escrow_id, &escrow);
-    
+    Ok(())
+}
+
+/// Settle a disputed escrow as a whole, paying `beneficiary_bp` of everything still unpaid to the
+/// beneficiary and refunding the rest. The depositor's share of a disputed milestone that was
+/// already approved comes back out of its unclaimed stream, then the retainage holdback.
+pub fn split_remaining(env: &Env, escrow_id: u32, milestone_index: u32, beneficiary_bp: u32) -> Result<(), Error> {
+    let mut escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    if escrow.status != EscrowStatus::Disputed {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    let mut milestone = get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    if milestone.status != MilestoneStatus::Disputed {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidMilestone as u32));
+    }
+    if beneficiary_bp > 10000 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+    }
+
+    escrow.status = EscrowStatus::InProgress;
+    clear_dispute(env, escrow_id, milestone_index);
+    idle_yield::unwind(env, escrow_id, &escrow)?;
+
+    if milestone.approved_at != 0 {
+        let clawback = milestone.amount * i128::from(10000 - beneficiary_bp) / 10000;
+        let total_before = escrow.total_amount;
+        streaming::settle_disputed(env, escrow_id, milestone_index, &mut escrow, milestone.amount - clawback)?;
+        let from_retainage = retainage::claw_back(env, escrow_id, &escrow, clawback - (total_before - escrow.total_amount))?;
+        escrow.total_amount -= from_retainage;
+        escrow.paid_amount -= from_retainage;
+    }
+
+    // Nothing else is delivered on this escrow
+    milestone.status = MilestoneStatus::Resolved;
+    env.storage()
+        .instance()
+        .set(&DataKey::Milestone(escrow_id, milestone_index), &milestone);
+    for other_index in 0..escrow.milestone_count {
+        if let Some(mut other) = get_milestone(env, escrow_id, other_index) {
+            if matches!(
+                other.status,
+                MilestoneStatus::NotStarted | MilestoneStatus::Submitted | MilestoneStatus::Rejected | MilestoneStatus::PartiallyApproved
+            ) {
+                other.status = MilestoneStatus::Resolved;
+                env.storage()
+                    .instance()
+                    .set(&DataKey::Milestone(escrow_id, other_index), &other);
+            }
+        }
+    }
+    refund_revision_fees(env, escrow_id, &escrow)?;
+
+    let remaining = escrow.total_amount - escrow.paid_amount;
+    let beneficiary_amount = remaining * i128::from(beneficiary_bp) / 10000;
+    let refund = remaining - beneficiary_amount;
+    if refund > 0 {
+        escrow.total_amount -= refund;
+        escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -refund)?;
+        refund_system::refund_to_depositor(env, escrow_id, &escrow, refund)?;
+    }
+    if beneficiary_amount > 0 {
+        release_payment(env, escrow_id, &mut escrow, beneficiary_amount, None)?;
+    } else {
+        complete_if_paid(env, escrow_id, &mut escrow)?;
+    }
+
+    escrow_core::save_escrow(env, escrow_id, &escrow);
+    Ok(())
+}
+
+/// Drop the votes and appointed arbiter of a settled dispute
+fn clear_dispute(env: &Env, escrow_id: u32, milestone_index: u32) {
+    env.storage()
+        .instance()
+        .remove(&EscrowKey::DisputeVotes(escrow_id, milestone_index));
+    env.storage()
+        .instance()
+        .remove(&EscrowKey::AppointedArbiter(escrow_id, milestone_index));
+}
+
+/// Fund extra revisions on a milestone beyond the agreed scope; paid to the beneficiary on approval
+pub fn purchase_revision(env: &Env, escrow_id: u32, milestone_index: u32, fee: i128, depositor: Address) -> Result<(), Error> {
+    depositor.require_auth();
+
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    if escrow.depositor != depositor {
+        return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32));
+    }
+
+    if escrow.status != EscrowStatus::InProgress {
+        return Err(Error::from_contract_error(DeCentPayError::EscrowNotActive as u32));
+    }
+
+    if fee <= 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAmount as u32));
+    }
+
+    let milestone = get_milestone(env, escrow_id, milestone_index)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
+    if milestone.status == MilestoneStatus::Approved || milestone.status == MilestoneStatus::Resolved {
+        return Err(Error::from_contract_error(DeCentPayError::MilestoneAlreadyProcessed as u32));
+    }
+
+    escrow_core::transfer(env, escrow.token.as_ref(), &depositor, &env.current_contract_address(), fee);
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), fee)?;
+
+    let held = get_revision_fee(env, escrow_id, milestone_index);
+    env.storage()
+        .instance()
+        .set(&EscrowKey::RevisionFee(escrow_id, milestone_index), &(held + fee));
+    Ok(())
+}
+
+/// Get the revision fee funded on a milestone
+pub fn get_revision_fee(env: &Env, escrow_id: u32, milestone_index: u32) -> i128 {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .get(&EscrowKey::RevisionFee(escrow_id, milestone_index))
+        .unwrap_or(0)
+}
+
+/// Pay a held revision fee to the beneficiary or refund it to the depositor
+fn settle_revision_fee(env: &Env, escrow_id: u32, milestone_index: u32, escrow: &EscrowData, to_beneficiary: bool) -> Result<(), Error> {
+    let fee = get_revision_fee(env, escrow_id, milestone_index);
+    if fee == 0 {
+        return Ok(());
+    }
+    env.storage().instance().remove(&EscrowKey::RevisionFee(escrow_id, milestone_index));
+
+    let recipient = match &escrow.beneficiary {
+        Some(beneficiary) if to_beneficiary => profiles::payout_address(env, beneficiary),
+        _ => refund_system::refund_destination(env, escrow_id, escrow),
+    };
+    escrow_core::update_escrowed_amount(env, escrow.token.as_ref(), -fee)?;
+    escrow_core::transfer(env, escrow.token.as_ref(), &env.current_contract_address(), &recipient, fee);
+    Ok(())
+}
+
+/// Refund the revision fee of a milestone that is being dropped
+pub fn refund_revision_fee(env: &Env, escrow_id: u32, milestone_index: u32, escrow: &EscrowData) -> Result<(), Error> {
+    settle_revision_fee(env, escrow_id, milestone_index, escrow, false)?;
+    Ok(())
+}
+
+/// Refund every unreleased revision fee of an escrow that is being closed
+pub fn refund_revision_fees(env: &Env, escrow_id: u32, escrow: &EscrowData) -> Result<(), Error> {
+    for milestone_index in 0..escrow.milestone_count {
+        settle_revision_fee(env, escrow_id, milestone_index, escrow, false)?;
+    }
     Ok(())
 }
 
@@ -246,7 +728,7 @@ pub fn reject_milestone(
 ) -> Result<(), Error> {
     depositor.require_auth();
 
-    escrow_core::require_valid_escrow(env, escrow_id)?;
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
     let escrow = escrow_core::get_escrow(env, escrow_id)
         .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
 
@@ -276,6 +758,7 @@ pub fn reject_milestone(
     // Update milestone status to Rejected
     milestone.status = MilestoneStatus::Rejected;
     milestone.rejection_reason = Some(reason);
+    ratings::record_client_outcome(env, &depositor, |record| record.rejections += 1);
 
     // Save milestone
     env.storage()
@@ -284,7 +767,7 @@ pub fn reject_milestone(
     env.storage()
         .instance()
         .set(&DataKey::Milestone(escrow_id, milestone_index), &milestone);
-    
+    milestone_log::record_activity(env, escrow_id, &depositor, ActivityAction::MilestoneRejected(milestone_index), milestone.rejection_reason);
     Ok(())
 }
 
@@ -294,10 +777,11 @@ pub fn resubmit_milestone(
     milestone_index: u32,
     beneficiary: Address,
     description: String,
+    manifest: Vec<Deliverable>,
 ) -> Result<(), Error> {
     beneficiary.require_auth();
 
-    escrow_core::require_valid_escrow(env, escrow_id)?;
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
     let escrow = escrow_core::get_escrow(env, escrow_id)
         .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
 
@@ -309,6 +793,10 @@ pub fn resubmit_milestone(
         return Err(Error::from_contract_error(DeCentPayError::InvalidEscrowStatus as u32));
     }
 
+    if manifest.len() > MAX_MANIFEST_ENTRIES {
+        return Err(Error::from_contract_error(DeCentPayError::TooManyDeliverables as u32));
+    }
+
     if milestone_index >= escrow.milestone_count {
         return Err(Error::from_contract_error(DeCentPayError::InvalidMilestone as u32));
     }
@@ -320,15 +808,18 @@ pub fn resubmit_milestone(
         .get::<DataKey, crate::storage_types::Milestone>(&DataKey::Milestone(escrow_id, milestone_index))
         .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
 
-    // Only allow resubmission if milestone is Rejected
-    if milestone.status != MilestoneStatus::Rejected {
+    // Only allow resubmission of rejected or partially approved milestones
+    if milestone.status != MilestoneStatus::Rejected && milestone.status != MilestoneStatus::PartiallyApproved {
         return Err(Error::from_contract_error(DeCentPayError::MilestoneAlreadyProcessed as u32));
     }
 
+    milestone_log::archive_submission(env, escrow_id, milestone_index, &milestone);
+
     // Update milestone status to Submitted and update description
     milestone.status = MilestoneStatus::Submitted;
-    milestone.submitted_at = env.ledger().sequence();
+    milestone.submitted_at = env.ledger().timestamp();
     milestone.description = description;
+    milestone.manifest = manifest;
     // Clear rejection reason when resubmitting
     milestone.rejection_reason = None;
 
@@ -339,7 +830,7 @@ pub fn resubmit_milestone(
     env.storage()
         .instance()
         .set(&DataKey::Milestone(escrow_id, milestone_index), &milestone);
-    
+    milestone_log::record_activity(env, escrow_id, &beneficiary, ActivityAction::MilestoneResubmitted(milestone_index), None);
     Ok(())
 }
 
@@ -352,14 +843,41 @@ pub fn dispute_milestone(
 ) -> Result<(), Error> {
     disputer.require_auth();
 
-    escrow_core::require_valid_escrow(env, escrow_id)?;
+    escrow_core::require_mutable_escrow(env, escrow_id)?;
     let mut escrow = escrow_core::get_escrow(env, escrow_id)
         .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+    let mut milestone = check_dispute(env, escrow_id, milestone_index, &escrow, &disputer)?;
+
+    // Update milestone status to Disputed
+    milestone.status = MilestoneStatus::Disputed;
+    milestone.disputed_at = env.ledger().timestamp();
+    milestone.disputed_by = Some(disputer.clone());
+    milestone.dispute_reason = Some(reason);
+
+    // Update escrow status to Disputed
+    escrow.status = EscrowStatus::Disputed;
+    if let Some(beneficiary) = &escrow.beneficiary {
+        achievements::on_dispute_opened(env, beneficiary);
+    }
+
+    // Save milestone and escrow
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::Milestone(escrow_id, milestone_index), &milestone);
+    escrow_core::save_escrow(env, escrow_id, &escrow);
+    milestone_log::record_activity(env, escrow_id, &disputer, ActivityAction::MilestoneDisputed(milestone_index), milestone.dispute_reason);
+    Ok(())
+}
 
+/// Check a party may dispute a milestone, returning the milestone
+pub fn check_dispute(env: &Env, escrow_id: u32, milestone_index: u32, escrow: &EscrowData, disputer: &Address) -> Result<Milestone, Error> {
     // Check if disputer is either depositor or beneficiary
-    let is_depositor = escrow.depositor == disputer;
-    let is_beneficiary = escrow.beneficiary == Some(disputer.clone());
-    
+    let is_depositor = escrow.depositor == *disputer;
+    let is_beneficiary = escrow.beneficiary.as_ref() == Some(disputer);
+
     if !is_depositor && !is_beneficiary {
         return Err(Error::from_contract_error(DeCentPayError::OnlyDepositor as u32)); // Use OnlyDepositor as generic error for unauthorized
     }
@@ -372,40 +890,30 @@ pub fn dispute_milestone(
         return Err(Error::from_contract_error(DeCentPayError::InvalidMilestone as u32));
     }
 
-    // Get milestone
-    let mut milestone: crate::storage_types::Milestone = env
-        .storage()
-        .instance()
-        .get::<DataKey, crate::storage_types::Milestone>(&DataKey::Milestone(escrow_id, milestone_index))
+    let milestone = get_milestone(env, escrow_id, milestone_index)
         .ok_or_else(|| Error::from_contract_error(DeCentPayError::InvalidMilestone as u32))?;
 
-    // Can dispute submitted or approved milestones
-    if milestone.status != MilestoneStatus::Submitted && milestone.status != MilestoneStatus::Approved {
+    // Can dispute submitted or approved milestones; the depositor can also dispute overdue
+    // milestones and retainer periods that have not been claimed yet
+    let overdue = is_depositor
+        && (refund_system::is_milestone_overdue(env, escrow_id, milestone_index)
+            || (retainer::get_retainer(env, escrow_id).is_some() && milestone.status == MilestoneStatus::NotStarted));
+    if milestone.status != MilestoneStatus::Submitted && milestone.status != MilestoneStatus::Approved && !overdue {
         return Err(Error::from_contract_error(DeCentPayError::MilestoneNotSubmitted as u32));
     }
+    Ok(milestone)
+}
 
-    // Update milestone status to Disputed
-    milestone.status = MilestoneStatus::Disputed;
-    milestone.disputed_at = env.ledger().sequence();
-    milestone.disputed_by = Some(disputer.clone());
-    milestone.dispute_reason = Some(reason);
-
-    // Update escrow status to Disputed
-    escrow.status = EscrowStatus::Disputed;
-
-    // Save milestone and escrow
-    env.storage()
-        .instance()
-        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-    env.storage()
-        .instance()
-        .set(&DataKey::Milestone(escrow_id, milestone_index), &milestone);
-    escrow_core::save_escrow(env, escrow_id, &escrow);
-    
-    Ok(())
+fn is_reputation_eligible(env: &Env, escrow: &EscrowData) -> bool {
+    escrow.total_amount >= admin::get_value_thresholds(env, escrow.token.clone()).reputation_eligible_value
 }
 
-fn update_reputation(env: &Env, user: Address, points: u32) {
+fn update_reputation(env: &Env, escrow_id: u32, category: Option<JobCategory>, user: Address, points: u32) {
+    if let Some(category) = category {
+        let category_rep = categories::add_category_reputation(env, &user, category, points);
+        achievements::on_category_reputation(env, &user, category_rep, escrow_id);
+    }
+
     let current_rep: u32 = env
         .storage()
         .instance()
@@ -416,7 +924,9 @@ fn update_reputation(env: &Env, user: Address, points: u32) {
         .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
     env.storage()
         .instance()
-        .set(&DataKey::Reputation(user), &(current_rep + points));
+        .set(&DataKey::Reputation(user.clone()), &(current_rep + points));
+    leaderboard::record_reputation(env, &user, current_rep + points);
+    reputation::record_gain(env, &user, points);
 }
 
 /// Get a milestone by escrow_id and milestone_index
@@ -453,3 +963,110 @@ pub fn get_milestones(env: &Env, escrow_id: u32) -> Vec<Milestone> {
     }
 }
 
+
+/// Get milestone status counts and payout progress for an escrow
+pub fn get_progress(env: &Env, escrow_id: u32) -> Result<EscrowProgress, Error> {
+    escrow_core::require_valid_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    let mut progress = EscrowProgress {
+        milestone_count: escrow.milestone_count,
+        not_started: 0,
+        submitted: 0,
+        approved: 0,
+        disputed: 0,
+        resolved: 0,
+        rejected: 0,
+        removed: 0,
+        partially_approved: 0,
+        paid_amount: escrow.paid_amount,
+        total_amount: escrow.total_amount,
+        completion_percent: 0,
+    };
+
+    for milestone in get_milestones(env, escrow_id).iter() {
+        match milestone.status {
+            MilestoneStatus::NotStarted => progress.not_started += 1,
+            MilestoneStatus::Submitted => progress.submitted += 1,
+            MilestoneStatus::Approved => progress.approved += 1,
+            MilestoneStatus::Disputed => progress.disputed += 1,
+            MilestoneStatus::Resolved => progress.resolved += 1,
+            MilestoneStatus::Rejected => progress.rejected += 1,
+            MilestoneStatus::Removed => progress.removed += 1,
+            MilestoneStatus::PartiallyApproved => progress.partially_approved += 1,
+        }
+    }
+
+    if escrow.total_amount > 0 {
+        let percent = (escrow.paid_amount * 100) / escrow.total_amount;
+        progress.completion_percent = u32::try_from(percent.clamp(0, 100)).unwrap_or(100);
+    }
+
+    Ok(progress)
+}
+
+/// Designate arbiters for a milestone of a new escrow, in addition to or instead of the escrow's arbiters
+pub fn set_milestone_arbiters(
+    env: &Env,
+    escrow_id: u32,
+    escrow: &EscrowData,
+    milestone_index: u32,
+    milestone_arbiters: MilestoneArbiters,
+) -> Result<(), Error> {
+    if milestone_index >= escrow.milestone_count {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidMilestone as u32));
+    }
+
+    // An override must name a panel that can actually reach a ruling
+    if milestone_arbiters.arbiters.is_empty() {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidParameter as u32));
+    }
+    if milestone_arbiters.required_confirmations == 0 {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidConfirmations as u32));
+    }
+    escrow_management::validate_arbiters(&milestone_arbiters.arbiters, milestone_arbiters.required_confirmations)?;
+    if milestone_arbiters.arbiters.contains(&escrow.depositor)
+        || escrow.beneficiary.as_ref().is_some_and(|ben| milestone_arbiters.arbiters.contains(ben))
+    {
+        return Err(Error::from_contract_error(DeCentPayError::InvalidAddress as u32));
+    }
+
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::MilestoneArbiters(escrow_id, milestone_index), &milestone_arbiters);
+    Ok(())
+}
+
+/// Get the effective arbiter panel for disputes on a milestone
+pub fn get_milestone_arbiters(env: &Env, escrow_id: u32, milestone_index: u32) -> Result<MilestoneArbiters, Error> {
+    escrow_core::require_valid_escrow(env, escrow_id)?;
+    let escrow = escrow_core::get_escrow(env, escrow_id)
+        .ok_or_else(|| Error::from_contract_error(DeCentPayError::EscrowNotFound as u32))?;
+
+    let default = MilestoneArbiters {
+        arbiters: escrow.arbiters.clone(),
+        required_confirmations: escrow.required_confirmations,
+        replace_default: false,
+    };
+    let Some(mut overrides) = env
+        .storage()
+        .instance()
+        .get::<DataKey, MilestoneArbiters>(&DataKey::MilestoneArbiters(escrow_id, milestone_index))
+    else {
+        return Ok(default);
+    };
+
+    if !overrides.replace_default {
+        for arbiter in escrow.arbiters.iter() {
+            if !overrides.arbiters.contains(&arbiter) {
+                overrides.arbiters.push_back(arbiter);
+            }
+        }
+        overrides.required_confirmations = overrides.required_confirmations.max(escrow.required_confirmations);
+    }
+    Ok(overrides)
+}